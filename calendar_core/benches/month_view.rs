@@ -0,0 +1,171 @@
+//! Time spent building the month grid of a calendar with 100k events: the
+//! occurrences of the visible weeks, then the contents of every cell.
+//!
+//! Run it with `cargo bench --bench month_view`.
+
+use std::time::Instant;
+
+use calendar_core::{
+    calendars::{self, Rgb},
+    date, lunar, Calendar, Category, Event, EventTime, NamedCalendar, Recurrence,
+};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+const EVENTS: usize = 100_000;
+const ROUNDS: u32 = 50;
+
+/// What a cell of the month grid shows
+struct Cell {
+    label: String,
+    note: String,
+    entries: Vec<String>,
+}
+
+/// Events spread over twenty years, a few per day: all-day and timed ones
+/// in three calendars, one of them hidden, and a weekly event every
+/// thousand
+fn events() -> Vec<Event> {
+    let start = NaiveDate::from_ymd_opt(2010, 1, 1).unwrap();
+
+    (0..EVENTS)
+        .map(|i| {
+            let date = start + Duration::days((i * 7 % 7300) as i64);
+            let time = (i % 2 == 0).then(|| EventTime {
+                start: NaiveTime::from_hms_opt((i % 24) as u32, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(((i + 1) % 24) as u32, 0, 0).unwrap(),
+                zone: Tz::Europe__Berlin,
+                reminder: None,
+            });
+            let recurrence = (i % 1000 == 0).then_some(Recurrence::Weekly { until: None });
+
+            let mut event = Event::new(format!("Event {i}"), date, time, recurrence, Category::Todo);
+            event.calendar = (i % 3) as u64 + 1;
+            event
+        })
+        .collect()
+}
+
+fn named_calendars() -> Vec<NamedCalendar> {
+    let mut calendars: Vec<NamedCalendar> = (1..=3)
+        .map(|id| NamedCalendar::new(id, format!("Calendar {id}"), Rgb(0, 0, 0)))
+        .collect();
+    calendars[2].visible = false;
+
+    calendars
+}
+
+/// Text of an event in a cell, with its start in the calendar's zone
+fn entry(event: &Event, zone: Tz) -> String {
+    match event.start_in(zone) {
+        Some(start) => format!("{} {}", start.format("%H:%M"), event.title),
+        None => event.title.clone(),
+    }
+}
+
+/// The six weeks of the month of `month`, starting on Monday
+fn grid_days(month: NaiveDate) -> Vec<NaiveDate> {
+    let first = date::first_of_month(month);
+    let start = first - Duration::days(i64::from(date::days_since(first.weekday(), Weekday::Mon)));
+
+    start.iter_days().take(42).collect()
+}
+
+fn cell(day: NaiveDate, entries: Vec<String>) -> Cell {
+    Cell {
+        label: day.day().to_string(),
+        note: lunar::from_solar(day, lunar::VIETNAM).to_string(),
+        entries,
+    }
+}
+
+/// What the view did before the index: filter every event for every cell
+fn month_grid_per_cell(calendar: &Calendar, named: &[NamedCalendar], month: NaiveDate) -> Vec<Cell> {
+    let zone = calendar.zone();
+
+    grid_days(month)
+        .into_iter()
+        .map(|day| {
+            let entries = calendar
+                .events()
+                .iter()
+                .filter(|event| match event.recurrence {
+                    Some(recurrence) => recurrence
+                        .occurrences(event.date, day - Duration::days(1), day + Duration::days(1))
+                        .into_iter()
+                        .any(|date| event.on(date).date_in(zone) == day),
+                    None => event.date_in(zone) == day,
+                })
+                .filter(|event| calendars::is_visible(named, event.calendar))
+                .map(|event| entry(event, zone))
+                .collect();
+
+            cell(day, entries)
+        })
+        .collect()
+}
+
+/// What the view does now: the occurrences of the weeks shown, then the
+/// cells
+fn month_grid(calendar: &Calendar, named: &[NamedCalendar], month: NaiveDate) -> Vec<Cell> {
+    let zone = calendar.zone();
+    let days = grid_days(month);
+    let mut occurrences = calendar.occurrences(days[0], days[days.len() - 1]);
+
+    days.into_iter()
+        .map(|day| {
+            let entries = occurrences
+                .remove(&day)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|i| &calendar.events()[i])
+                .filter(|event| calendars::is_visible(named, event.calendar))
+                .map(|event| entry(event, zone))
+                .collect();
+
+            cell(day, entries)
+        })
+        .collect()
+}
+
+fn time<T>(label: &str, rounds: u32, mut f: impl FnMut() -> T) {
+    let start = Instant::now();
+
+    for _ in 0..rounds {
+        std::hint::black_box(f());
+    }
+
+    let per_round = start.elapsed() / rounds;
+
+    println!("{label:<32} {:>12?}", per_round);
+}
+
+fn main() {
+    let events = events();
+    let named = named_calendars();
+
+    let start = Instant::now();
+    let mut calendar = Calendar::new(events, Tz::Asia__Ho_Chi_Minh);
+    println!("{:<32} {:>12?}", "index 100k events", start.elapsed());
+
+    let month = NaiveDate::from_ymd_opt(2020, 6, 1).unwrap();
+    // Repeating events come last in a cell of the indexed grid
+    let shown = |cells: Vec<Cell>| -> Vec<(String, String, Vec<String>)> {
+        cells
+            .into_iter()
+            .map(|mut cell| {
+                cell.entries.sort();
+                (cell.label, cell.note, cell.entries)
+            })
+            .collect()
+    };
+    assert_eq!(shown(month_grid_per_cell(&calendar, &named, month)), shown(month_grid(&calendar, &named, month)));
+
+    time("month grid, filter per cell", 3, || month_grid_per_cell(&calendar, &named, month));
+    time("month grid, indexed", ROUNDS, || month_grid(&calendar, &named, month));
+
+    let start = Instant::now();
+    calendar.add(Event::new("Added".to_string(), month, None, None, Category::Todo));
+    calendar.remove(EVENTS / 2);
+    println!("{:<32} {:>12?}", "add + remove", start.elapsed());
+}
@@ -0,0 +1,223 @@
+//! Events with the index answering date and text queries on them.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::{event::Event, index::EventIndex};
+
+/// Every event of a calendar, displayed in one time zone.
+///
+/// Events are addressed by position, which changes when an earlier event
+/// is removed, or by `Event::id`, which never does.
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    events: Vec<Event>,
+    index: EventIndex,
+    zone: Tz,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar::new(Vec::new(), Tz::UTC)
+    }
+}
+
+impl Calendar {
+    /// Calendar of `events` displayed in `zone`. Events without an id,
+    /// from files written before ids existed, are given one.
+    pub fn new(mut events: Vec<Event>, zone: Tz) -> Self {
+        assign_ids(&mut events);
+
+        let mut calendar = Calendar {
+            events,
+            index: EventIndex::default(),
+            zone,
+        };
+        calendar.reindex();
+
+        calendar
+    }
+
+    /// Every event, in the order they were added
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The events, to be saved
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+
+    /// Time zone deciding the day timed events fall on
+    pub fn zone(&self) -> Tz {
+        self.zone
+    }
+
+    /// Display the calendar in `zone`, moving timed events to their day there
+    pub fn set_zone(&mut self, zone: Tz) {
+        if zone != self.zone {
+            self.zone = zone;
+            self.reindex();
+        }
+    }
+
+    /// Event at position `i`, to edit its text. Changes to its date, time
+    /// or recurrence must be followed by `reindex`.
+    pub fn event_mut(&mut self, i: usize) -> Option<&mut Event> {
+        self.events.get_mut(i)
+    }
+
+    /// Position of the event with `id`
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.events.iter().position(|event| event.id == id)
+    }
+
+    /// Add `event`, returning the id it was given
+    pub fn add(&mut self, mut event: Event) -> u64 {
+        event.id = next_id(&self.events);
+
+        if event.recurrence.is_some() {
+            self.index.insert_recurring(self.events.len());
+        } else {
+            self.index.insert(event.date_in(self.zone), self.events.len());
+        }
+
+        let id = event.id;
+        self.events.push(event);
+
+        id
+    }
+
+    /// Remove the event at position `i`
+    pub fn remove(&mut self, i: usize) -> Option<Event> {
+        if i < self.events.len() {
+            self.index.remove(i);
+
+            Some(self.events.remove(i))
+        } else {
+            None
+        }
+    }
+
+    /// Rebuild the index after events were changed in place
+    pub fn reindex(&mut self) {
+        let zone = self.zone;
+
+        self.index = EventIndex::build(
+            self.events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.recurrence.is_none())
+                .map(|(i, event)| (i, event.date_in(zone))),
+        );
+
+        for (i, event) in self.events.iter().enumerate() {
+            if event.recurrence.is_some() {
+                self.index.insert_recurring(i);
+            }
+        }
+    }
+
+    /// Positions of the events happening between `from` and `to`, both
+    /// included, by day, with repeating events expanded
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> BTreeMap<NaiveDate, Vec<usize>> {
+        let mut by_date: BTreeMap<NaiveDate, Vec<usize>> = self
+            .index
+            .range(from, to)
+            .map(|(date, positions)| (*date, positions.to_vec()))
+            .collect();
+
+        for &i in self.index.recurring() {
+            for (_, date) in self.repeats(i, from, to) {
+                by_date.entry(date).or_default().push(i);
+            }
+        }
+
+        by_date
+    }
+
+    /// The event at position `i` as it happens on `date`, a day
+    /// `occurrences` gave it in the calendar's zone
+    pub fn occurrence(&self, i: usize, date: NaiveDate) -> Event {
+        let event = &self.events[i];
+
+        if event.recurrence.is_none() {
+            return event.clone();
+        }
+
+        match self.repeats(i, date, date).first() {
+            Some(&(own, _)) => event.on(own),
+            None => event.on(date),
+        }
+    }
+
+    /// Occurrences of the repeating event at position `i` falling between
+    /// `from` and `to` in the calendar's zone, as their day in the event's
+    /// own zone and their day in the calendar's
+    fn repeats(&self, i: usize, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+        let event = &self.events[i];
+        let recurrence = match event.recurrence {
+            Some(recurrence) => recurrence,
+            None => return Vec::new(),
+        };
+
+        // A timed occurrence can fall on the day before or after in the
+        // calendar's zone
+        let (own_from, own_to) = match event.time {
+            Some(_) => (from.pred_opt().unwrap_or(from), to.succ_opt().unwrap_or(to)),
+            None => (from, to),
+        };
+
+        recurrence
+            .occurrences(event.date, own_from, own_to)
+            .into_iter()
+            .map(|own| (own, event.on(own).date_in(self.zone)))
+            .filter(|(_, date)| (from..=to).contains(date))
+            .collect()
+    }
+
+    /// Timed events happening between `from` and `to`, as their position
+    /// with the start and end of the occurrence in the calendar's zone
+    pub fn timed_occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<(usize, DateTime<Tz>, DateTime<Tz>)> {
+        let mut timed = Vec::new();
+
+        for (date, positions) in self.occurrences(from, to) {
+            for i in positions {
+                let occurrence = self.occurrence(i, date);
+
+                if let (Some(start), Some(end)) = (occurrence.start_in(self.zone), occurrence.end_in(self.zone)) {
+                    timed.push((i, start, end));
+                }
+            }
+        }
+
+        timed
+    }
+
+    /// Positions of the events matching `query`, by date
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> = (0..self.events.len())
+            .filter(|&i| self.events[i].matches(query))
+            .collect();
+
+        positions.sort_by_key(|&i| self.events[i].date);
+        positions
+    }
+}
+
+/// Id for an event about to be added to `events`
+pub fn next_id(events: &[Event]) -> u64 {
+    events.iter().map(|event| event.id).max().unwrap_or(0) + 1
+}
+
+/// Give the events without an id, from files written before ids existed,
+/// the ids following the highest one
+pub fn assign_ids(events: &mut [Event]) {
+    let first = next_id(events);
+
+    for (event, id) in events.iter_mut().filter(|event| event.id == 0).zip(first..) {
+        event.id = id;
+    }
+}
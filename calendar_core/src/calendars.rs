@@ -0,0 +1,153 @@
+//! Named calendars events are filed in, such as Work or Personal, each with
+//! its own color and shown or hidden as a whole.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::event::Event;
+
+/// Color of a calendar, written `#rrggbb` in the calendar file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// Colors given to new calendars in turn
+pub const COLORS: [Rgb; 8] = [
+    Rgb(0x3b, 0x82, 0xf6),
+    Rgb(0x16, 0xa3, 0x4a),
+    Rgb(0xdc, 0x26, 0x26),
+    Rgb(0xd9, 0x77, 0x06),
+    Rgb(0x93, 0x33, 0xea),
+    Rgb(0x08, 0x91, 0xb2),
+    Rgb(0xdb, 0x27, 0x77),
+    Rgb(0x65, 0x73, 0x8b),
+];
+
+/// Calendar events are filed in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedCalendar {
+    /// Stable identifier, kept by the events filed in it
+    pub id: u64,
+    pub name: String,
+    pub color: Rgb,
+    /// Whether its events are displayed
+    #[serde(default = "shown")]
+    pub visible: bool,
+}
+
+fn shown() -> bool {
+    true
+}
+
+impl NamedCalendar {
+    /// Visible calendar
+    pub fn new(id: u64, name: String, color: Rgb) -> Self {
+        NamedCalendar {
+            id,
+            name,
+            color,
+            visible: true,
+        }
+    }
+}
+
+impl std::fmt::Display for NamedCalendar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl std::str::FromStr for Rgb {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let digits = value.strip_prefix('#').unwrap_or(value);
+        let channel = |at: usize| {
+            digits
+                .get(at..at + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                .ok_or_else(|| format!("`{value}` is not a #rrggbb color"))
+        };
+
+        if digits.len() != 6 {
+            return Err(format!("`{value}` is not a #rrggbb color"));
+        }
+
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The one calendar of a new calendar file
+pub fn defaults() -> Vec<NamedCalendar> {
+    vec![NamedCalendar::new(1, "Personal".to_string(), COLORS[0])]
+}
+
+/// Id for a calendar about to be added to `calendars`
+pub fn next_id(calendars: &[NamedCalendar]) -> u64 {
+    calendars.iter().map(|calendar| calendar.id).max().unwrap_or(0) + 1
+}
+
+/// Color for a calendar about to be added, the first of `COLORS` not used
+/// yet
+pub fn next_color(calendars: &[NamedCalendar]) -> Rgb {
+    COLORS
+        .iter()
+        .copied()
+        .find(|color| calendars.iter().all(|calendar| calendar.color != *color))
+        .unwrap_or(COLORS[calendars.len() % COLORS.len()])
+}
+
+/// Calendar `id` of `calendars`
+pub fn find(calendars: &[NamedCalendar], id: u64) -> Option<&NamedCalendar> {
+    calendars.iter().find(|calendar| calendar.id == id)
+}
+
+/// Whether events filed in `id` are displayed, those of unknown calendars
+/// always being
+pub fn is_visible(calendars: &[NamedCalendar], id: u64) -> bool {
+    find(calendars, id).is_none_or(|calendar| calendar.visible)
+}
+
+/// Give files written before calendars existed their default calendar, and
+/// file events of no or an unknown calendar in the first one
+pub fn repair(calendars: &mut Vec<NamedCalendar>, events: &mut [Event]) {
+    if calendars.is_empty() {
+        *calendars = defaults();
+    }
+
+    let first = calendars[0].id;
+
+    for event in events {
+        if find(calendars, event.calendar).is_none() {
+            event.calendar = first;
+        }
+    }
+}
+
+/// Remove calendar `id` unless it is the last one, returning the calendar
+/// its events are to be moved to
+pub fn remove(calendars: &mut Vec<NamedCalendar>, id: u64) -> Option<u64> {
+    if calendars.len() < 2 {
+        return None;
+    }
+
+    calendars.retain(|calendar| calendar.id != id);
+
+    Some(calendars[0].id)
+}
@@ -0,0 +1,65 @@
+//! Timed events of the same named calendar happening at the same time.
+
+use chrono::NaiveDate;
+
+use crate::{calendar::Calendar, event::Event};
+
+/// Two events overlapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    /// Day the overlap starts on, in the zone of the calendar
+    pub date: NaiveDate,
+    /// Position of the event starting first
+    pub first: usize,
+    pub second: usize,
+}
+
+/// Every conflict starting between `from` and `to`, by start
+pub fn between(calendar: &Calendar, from: NaiveDate, to: NaiveDate) -> Vec<Conflict> {
+    // Events running past midnight may overlap the first day's
+    let mut timed = calendar.timed_occurrences(from.pred_opt().unwrap_or(from), to);
+    timed.sort_by_key(|&(_, start, _)| start);
+
+    let mut conflicts = Vec::new();
+
+    for (at, &(first, start, end)) in timed.iter().enumerate() {
+        for &(second, other_start, other_end) in timed[at + 1..].iter().take_while(|(_, other_start, _)| *other_start < end) {
+            let date = other_start.date_naive();
+
+            if first != second
+                && other_end > start
+                && calendar.events()[first].calendar == calendar.events()[second].calendar
+                && (from..=to).contains(&date)
+            {
+                conflicts.push(Conflict { date, first, second });
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Positions of the events of the calendar `event` is filed in overlapping
+/// it on its day, leaving out its own `position` when it is in `calendar`
+pub fn overlapping(calendar: &Calendar, event: &Event, position: Option<usize>) -> Vec<usize> {
+    let zone = calendar.zone();
+
+    let (start, end) = match (event.start_in(zone), event.end_in(zone)) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return Vec::new(),
+    };
+
+    let day = start.date_naive();
+    let mut positions: Vec<usize> = calendar
+        .timed_occurrences(day.pred_opt().unwrap_or(day), day.succ_opt().unwrap_or(day))
+        .into_iter()
+        .filter(|&(i, other_start, other_end)| {
+            Some(i) != position && calendar.events()[i].calendar == event.calendar && other_start < end && start < other_end
+        })
+        .map(|(i, ..)| i)
+        .collect();
+
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
@@ -0,0 +1,74 @@
+//! Month arithmetic and week layout on plain dates.
+
+use chrono::{Datelike, Months, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Today as seen from `zone`
+pub fn today(zone: Tz) -> NaiveDate {
+    Utc::now().with_timezone(&zone).date_naive()
+}
+
+/// First day of the month of `date`
+pub fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Last day of the month of `date`, leap years included
+pub fn last_of_month(date: NaiveDate) -> NaiveDate {
+    next_month(first_of_month(date))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(date)
+}
+
+/// How many days the month of `date` has
+pub fn days_in_month(date: NaiveDate) -> u32 {
+    last_of_month(date).day()
+}
+
+/// Same day one month later, clamped to the end of that month
+/// (January 31 gives the last day of February)
+pub fn next_month(date: NaiveDate) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(1))
+}
+
+/// Same day one month earlier, clamped to the end of that month
+pub fn prev_month(date: NaiveDate) -> Option<NaiveDate> {
+    date.checked_sub_months(Months::new(1))
+}
+
+/// Position of `weekday` in a week starting on `first`, from 0 to 6
+pub fn days_since(weekday: Weekday, first: Weekday) -> u32 {
+    (7 + weekday.num_days_from_monday() - first.num_days_from_monday()) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn month_rollover() {
+        assert_eq!(next_month(day(2025, 12, 15)), Some(day(2026, 1, 15)));
+        assert_eq!(prev_month(day(2026, 1, 15)), Some(day(2025, 12, 15)));
+        assert_eq!(next_month(day(2025, 1, 31)), Some(day(2025, 2, 28)));
+        assert_eq!(prev_month(day(2025, 3, 31)), Some(day(2025, 2, 28)));
+        assert_eq!(next_month(day(2025, 8, 31)), Some(day(2025, 9, 30)));
+        assert_eq!(last_of_month(day(2025, 12, 1)), day(2025, 12, 31));
+        assert_eq!(first_of_month(day(2025, 12, 31)), day(2025, 12, 1));
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(days_in_month(day(2024, 2, 1)), 29);
+        assert_eq!(days_in_month(day(2023, 2, 1)), 28);
+        assert_eq!(days_in_month(day(2000, 2, 1)), 29);
+        assert_eq!(days_in_month(day(1900, 2, 1)), 28);
+        assert_eq!(last_of_month(day(2024, 2, 17)), day(2024, 2, 29));
+        assert_eq!(next_month(day(2024, 1, 31)), Some(day(2024, 2, 29)));
+        assert_eq!(prev_month(day(2024, 3, 30)), Some(day(2024, 2, 29)));
+        assert_eq!(next_month(day(2024, 2, 29)), Some(day(2024, 3, 29)));
+    }
+}
@@ -0,0 +1,135 @@
+//! Events, as saved in the calendar file.
+
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{recurrence::Recurrence, zone};
+
+/// Something happening on a day, possibly at set hours and repeating
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// Stable identifier, for the command line and exports. Zero until the
+    /// event is added to a calendar.
+    #[serde(default)]
+    pub id: u64,
+    pub title: String,
+    /// Day of the event, in the zone of `time` for timed events
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub notes: String,
+    /// Hours of the event, none for all-day events
+    #[serde(default)]
+    pub time: Option<EventTime>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub category: Category,
+    /// Id of the named calendar the event is filed in, zero until it is
+    #[serde(default)]
+    pub calendar: u64,
+}
+
+/// Wall-clock hours of a timed event, in the zone it was planned in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventTime {
+    pub start: NaiveTime,
+    /// End, on the next day when not after `start`
+    pub end: NaiveTime,
+    pub zone: Tz,
+    /// Minutes before `start` to be reminded at
+    #[serde(default)]
+    pub reminder: Option<u32>,
+}
+
+/// Kind of event, deciding its color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Category {
+    #[default]
+    Todo,
+    Reminder,
+    /// Public holidays, generated rather than created
+    Holiday,
+}
+
+impl Category {
+    /// Categories events can be created in, holidays being generated
+    pub const EDITABLE: &[Category] = &[Category::Todo, Category::Reminder];
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Category::Todo => "Todo",
+            Category::Reminder => "Reminder",
+            Category::Holiday => "Holiday",
+        })
+    }
+}
+
+impl Event {
+    /// Event with no details, to be given an id by `Calendar::add`
+    pub fn new(title: String, date: NaiveDate, time: Option<EventTime>, recurrence: Option<Recurrence>, category: Category) -> Self {
+        Event {
+            id: 0,
+            title,
+            date,
+            description: String::new(),
+            location: String::new(),
+            url: String::new(),
+            notes: String::new(),
+            time,
+            recurrence,
+            category,
+            calendar: 0,
+        }
+    }
+
+    /// Start of a timed event, seen from `zone`
+    pub fn start_in(&self, zone: Tz) -> Option<DateTime<Tz>> {
+        self.time
+            .map(|time| zone::resolve(time.zone, self.date, time.start).with_timezone(&zone))
+    }
+
+    /// End of a timed event, seen from `zone`; an end before the start
+    /// means the event runs past midnight
+    pub fn end_in(&self, zone: Tz) -> Option<DateTime<Tz>> {
+        self.time.map(|time| {
+            let date = if time.end <= time.start {
+                self.date.succ_opt().unwrap_or(self.date)
+            } else {
+                self.date
+            };
+
+            zone::resolve(time.zone, date, time.end).with_timezone(&zone)
+        })
+    }
+
+    /// Day the event falls on when the calendar is displayed in `zone`
+    pub fn date_in(&self, zone: Tz) -> NaiveDate {
+        self.start_in(zone).map_or(self.date, |start| start.date_naive())
+    }
+
+    /// Case-insensitive match against every text field of the event
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+
+        [&self.title, &self.description, &self.location, &self.url, &self.notes]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    /// The event as it happens on `date`, for repeating events
+    pub fn on(&self, date: NaiveDate) -> Event {
+        Event {
+            date,
+            ..self.clone()
+        }
+    }
+}
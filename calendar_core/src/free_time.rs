@@ -0,0 +1,111 @@
+//! Free time between the timed events of a calendar, to find when a new
+//! event fits.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+use crate::{calendar::Calendar, event::Category};
+
+/// Where to look for free time, in the zone of the calendar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotSearch {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Length of the event to fit
+    pub minutes: u32,
+    /// Working hours, every day of the range
+    pub day_start: NaiveTime,
+    pub day_end: NaiveTime,
+    /// Whether Saturdays and Sundays are skipped
+    pub weekdays_only: bool,
+    /// Days skipped as a whole, such as public holidays
+    pub days_off: Vec<NaiveDate>,
+    /// Named calendars whose events take time, every one when empty
+    pub calendars: Vec<u64>,
+    /// Nothing starts earlier, so that slots already past are not offered
+    pub not_before: Option<NaiveDateTime>,
+}
+
+/// Free time an event of the searched length fits in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// Slots of `search` in `calendar`, by start. Each stretch of free time
+/// offers its start, rounded up to the quarter hour, then every full hour
+/// the event still fits at. All-day events take no time, except holidays
+/// which take the whole day.
+pub fn find(calendar: &Calendar, search: &SlotSearch) -> Vec<Slot> {
+    let length = Duration::minutes(i64::from(search.minutes.max(1)));
+    let busy = busy(calendar, search);
+    let mut slots = Vec::new();
+
+    let days = std::iter::successors(Some(search.from), |day| day.succ_opt()).take_while(|day| *day <= search.to);
+
+    for day in days {
+        if search.days_off.contains(&day) || (search.weekdays_only && matches!(day.weekday(), Weekday::Sat | Weekday::Sun)) {
+            continue;
+        }
+
+        let close = day.and_time(search.day_end);
+        let open = day.and_time(search.day_start);
+        let open = search.not_before.map_or(open, |not_before| open.max(not_before));
+        let mut free_from = open;
+
+        for &(start, end) in busy.iter().filter(|(start, end)| *end > open && *start < close) {
+            push_slots(&mut slots, free_from, start.min(close), length);
+            free_from = free_from.max(end);
+        }
+
+        push_slots(&mut slots, free_from, close, length);
+    }
+
+    slots
+}
+
+/// Times taken by events of the searched calendars, by start, from the day
+/// before the range so that events running past midnight count
+fn busy(calendar: &Calendar, search: &SlotSearch) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let first = search.from.pred_opt().unwrap_or(search.from);
+    let searched = |i: usize| search.calendars.is_empty() || search.calendars.contains(&calendar.events()[i].calendar);
+
+    let mut busy: Vec<(NaiveDateTime, NaiveDateTime)> = calendar
+        .timed_occurrences(first, search.to)
+        .into_iter()
+        .filter(|(i, ..)| searched(*i))
+        .map(|(_, start, end)| (start.naive_local(), end.naive_local()))
+        .collect();
+
+    for (date, positions) in calendar.occurrences(first, search.to) {
+        let holiday = |i: &usize| calendar.events()[*i].time.is_none() && calendar.events()[*i].category == Category::Holiday;
+
+        for _ in positions.into_iter().filter(|i| searched(*i)).filter(holiday) {
+            let start = date.and_time(NaiveTime::MIN);
+
+            busy.push((start, start + Duration::days(1)));
+        }
+    }
+
+    busy.sort();
+    busy
+}
+
+fn push_slots(slots: &mut Vec<Slot>, from: NaiveDateTime, until: NaiveDateTime, length: Duration) {
+    let quarters = (i64::from(from.time().num_seconds_from_midnight()) + 899) / 900;
+    let mut start = from.date().and_time(NaiveTime::MIN) + Duration::minutes(quarters * 15);
+
+    while start + length <= until {
+        let end = start + length;
+
+        slots.push(Slot {
+            date: start.date(),
+            start: start.time(),
+            end: end.time(),
+        });
+
+        // The next full hour
+        start = start.with_minute(0).unwrap_or(start) + Duration::hours(1);
+    }
+}
@@ -0,0 +1,183 @@
+//! Public holidays, computed from rules rather than fetched.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::lunar::{self, LunarDate};
+
+/// Country whose public holidays can be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Country {
+    Vietnam,
+    UnitedStates,
+    Germany,
+}
+
+impl Country {
+    /// Every country with holiday rules
+    pub const ALL: [Country; 3] = [Country::Vietnam, Country::UnitedStates, Country::Germany];
+
+    /// ISO 3166 code of the country, as settings name it
+    pub fn code(self) -> &'static str {
+        match self {
+            Country::Vietnam => "VN",
+            Country::UnitedStates => "US",
+            Country::Germany => "DE",
+        }
+    }
+
+    /// Country of an ISO 3166 code in any case, if it has holiday rules
+    pub fn from_code(code: &str) -> Option<Country> {
+        Country::ALL
+            .into_iter()
+            .find(|country| country.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    fn rules(self) -> &'static [(&'static str, Rule)] {
+        match self {
+            Country::Vietnam => VIETNAM,
+            Country::UnitedStates => UNITED_STATES,
+            Country::Germany => GERMANY,
+        }
+    }
+}
+
+/// One public holiday, on one date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    /// Name in the language of the country
+    pub name: &'static str,
+    pub date: NaiveDate,
+    pub country: Country,
+}
+
+/// How the date of a holiday is found in a given year
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// Same day every year
+    Fixed { month: u32, day: u32 },
+    /// `n`th `weekday` of the month, counted from the end when negative
+    NthWeekday { month: u32, weekday: Weekday, n: i8 },
+    /// Days after Easter Sunday
+    Easter(i64),
+    /// Days after a date of the Vietnamese lunar calendar
+    Lunar { month: u32, day: u32, offset: i64 },
+}
+
+impl Rule {
+    /// Date of the holiday in `year`, a lunar year for lunar rules
+    fn date(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            Rule::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            Rule::NthWeekday { month, weekday, n } => nth_weekday(year, month, weekday, n),
+            Rule::Easter(offset) => easter(year).map(|easter| easter + Duration::days(offset)),
+            Rule::Lunar { month, day, offset } => {
+                let lunar = LunarDate {
+                    year,
+                    month,
+                    day,
+                    leap: false,
+                };
+
+                lunar::to_solar(lunar, lunar::VIETNAM).map(|date| date + Duration::days(offset))
+            }
+        }
+    }
+}
+
+const VIETNAM: &[(&str, Rule)] = &[
+    ("Tết Dương lịch", Rule::Fixed { month: 1, day: 1 }),
+    ("Giao thừa", Rule::Lunar { month: 1, day: 1, offset: -1 }),
+    ("Tết Nguyên Đán", Rule::Lunar { month: 1, day: 1, offset: 0 }),
+    ("Mùng 2 Tết", Rule::Lunar { month: 1, day: 2, offset: 0 }),
+    ("Mùng 3 Tết", Rule::Lunar { month: 1, day: 3, offset: 0 }),
+    ("Giỗ Tổ Hùng Vương", Rule::Lunar { month: 3, day: 10, offset: 0 }),
+    ("Ngày Giải phóng miền Nam", Rule::Fixed { month: 4, day: 30 }),
+    ("Quốc tế Lao động", Rule::Fixed { month: 5, day: 1 }),
+    ("Quốc khánh", Rule::Fixed { month: 9, day: 2 }),
+];
+
+const UNITED_STATES: &[(&str, Rule)] = &[
+    ("New Year's Day", Rule::Fixed { month: 1, day: 1 }),
+    ("Martin Luther King Jr. Day", Rule::NthWeekday { month: 1, weekday: Weekday::Mon, n: 3 }),
+    ("Presidents' Day", Rule::NthWeekday { month: 2, weekday: Weekday::Mon, n: 3 }),
+    ("Memorial Day", Rule::NthWeekday { month: 5, weekday: Weekday::Mon, n: -1 }),
+    ("Juneteenth", Rule::Fixed { month: 6, day: 19 }),
+    ("Independence Day", Rule::Fixed { month: 7, day: 4 }),
+    ("Labor Day", Rule::NthWeekday { month: 9, weekday: Weekday::Mon, n: 1 }),
+    ("Columbus Day", Rule::NthWeekday { month: 10, weekday: Weekday::Mon, n: 2 }),
+    ("Veterans Day", Rule::Fixed { month: 11, day: 11 }),
+    ("Thanksgiving", Rule::NthWeekday { month: 11, weekday: Weekday::Thu, n: 4 }),
+    ("Christmas Day", Rule::Fixed { month: 12, day: 25 }),
+];
+
+const GERMANY: &[(&str, Rule)] = &[
+    ("Neujahr", Rule::Fixed { month: 1, day: 1 }),
+    ("Karfreitag", Rule::Easter(-2)),
+    ("Ostermontag", Rule::Easter(1)),
+    ("Tag der Arbeit", Rule::Fixed { month: 5, day: 1 }),
+    ("Christi Himmelfahrt", Rule::Easter(39)),
+    ("Pfingstmontag", Rule::Easter(50)),
+    ("Tag der Deutschen Einheit", Rule::Fixed { month: 10, day: 3 }),
+    ("1. Weihnachtstag", Rule::Fixed { month: 12, day: 25 }),
+    ("2. Weihnachtstag", Rule::Fixed { month: 12, day: 26 }),
+];
+
+/// Holidays of `countries` between `from` and `to`, both included, by date
+pub fn between(countries: &[Country], from: NaiveDate, to: NaiveDate) -> Vec<Holiday> {
+    let mut holidays: Vec<Holiday> = countries
+        .iter()
+        .flat_map(|&country| {
+            // One year of margin on each side catches lunar holidays that
+            // land in the neighbouring solar year
+            (from.year() - 1..=to.year() + 1).flat_map(move |year| {
+                country.rules().iter().filter_map(move |&(name, rule)| {
+                    Some(Holiday {
+                        name,
+                        date: rule.date(year)?,
+                        country,
+                    })
+                })
+            })
+        })
+        .filter(|holiday| (from..=to).contains(&holiday.date))
+        .collect();
+
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+/// `n`th `weekday` of `month`, or the `-n`th from its end when negative
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i8) -> Option<NaiveDate> {
+    if n > 0 {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+    } else if n < 0 {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = crate::date::last_of_month(first);
+        let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+
+        Some(last - Duration::days(i64::from(back) + 7 * (i64::from(-n) - 1)))
+    } else {
+        None
+    }
+}
+
+/// Easter Sunday of the Gregorian calendar
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
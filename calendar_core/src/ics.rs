@@ -0,0 +1,673 @@
+//! iCalendar (RFC 5545) export and import.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    event::{Category, Event, EventTime},
+    holidays::Holiday,
+    recurrence::Recurrence,
+    zone,
+};
+
+/// How far ahead lunar recurrences are written out, since they have no
+/// RRULE equivalent
+const RECURRENCE_YEARS: i64 = 10;
+
+/// Property keeping lunar recurrences in single-event resources, where
+/// they cannot be written out
+const LUNAR_YEARLY: &str = "X-DATE-PICKER-LUNAR-YEARLY";
+
+/// Events and holidays as an iCalendar document
+pub fn export(events: &[Event], holidays: &[Holiday]) -> String {
+    document(Vec::new(), events, holidays)
+}
+
+/// Events as a calendar named `name` for other programs to subscribe to,
+/// asking them to fetch it again every `refresh_minutes` (RFC 7986)
+pub fn feed(name: &str, events: &[Event], refresh_minutes: u32) -> String {
+    let properties = vec![
+        format!("NAME:{}", escape(name)),
+        format!("X-WR-CALNAME:{}", escape(name)),
+        format!("REFRESH-INTERVAL;VALUE=DURATION:PT{refresh_minutes}M"),
+        format!("X-PUBLISHED-TTL:PT{refresh_minutes}M"),
+    ];
+
+    document(properties, events, &[])
+}
+
+/// Calendar with `properties` of its own, then the events, those repeating
+/// on the lunar calendar once per occurrence, and the holidays
+fn document(properties: Vec<String>, events: &[Event], holidays: &[Holiday]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//calendar_project//date_picker//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    lines.extend(properties);
+
+    for event in events {
+        let rule = repeat_rule(event);
+
+        let dates = match event.recurrence {
+            Some(recurrence) if rule.is_none() => recurrence.occurrences(
+                event.date,
+                event.date,
+                event.date + Duration::days(RECURRENCE_YEARS * 366),
+            ),
+            _ => vec![event.date],
+        };
+
+        for date in dates {
+            let occurrence = event.on(date);
+
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", uid(event, date)));
+            lines.push(format!("DTSTAMP:{stamp}"));
+
+            match (occurrence.start_in(Tz::UTC), occurrence.end_in(Tz::UTC)) {
+                (Some(start), Some(end)) => {
+                    lines.push(format!("DTSTART:{}", utc(start)));
+                    lines.push(format!("DTEND:{}", utc(end)));
+                }
+                _ => push_all_day(&mut lines, date),
+            }
+
+            lines.extend(rule.clone());
+            push_details(&mut lines, event);
+
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    for holiday in holidays {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:holiday-{:?}-{}@date_picker", holiday.country, holiday.date));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        push_all_day(&mut lines, holiday.date);
+        lines.push(format!("SUMMARY:{}", escape(holiday.name)));
+        lines.push("CATEGORIES:HOLIDAY".to_string());
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// One event as a document of its own, the way CalDAV servers store them.
+/// Times keep the zone they were planned in and lunar recurrences are kept
+/// in a private property, so that `parse` gives the event back.
+pub fn resource(event: &Event, uid: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//calendar_project//date_picker//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape(uid)),
+        format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+    ];
+
+    match (event.time, event.end_in(Tz::UTC)) {
+        (Some(time), Some(end)) => {
+            let end_date = end.with_timezone(&time.zone).date_naive();
+
+            lines.push(format!("DTSTART{}", local(time.zone, event.date.and_time(time.start))));
+            lines.push(format!("DTEND{}", local(time.zone, end_date.and_time(time.end))));
+        }
+        _ => push_all_day(&mut lines, event.date),
+    }
+
+    if let Some(Recurrence::LunarYearly { month, day }) = event.recurrence {
+        lines.push(format!("{LUNAR_YEARLY}:{month}/{day}"));
+    }
+
+    lines.extend(repeat_rule(event));
+
+    push_details(&mut lines, event);
+
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// Event read from a document, with the identifier it has there
+#[derive(Debug, Clone, PartialEq)]
+pub struct Imported {
+    pub uid: String,
+    pub event: Event,
+}
+
+/// Why a document could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line the error was found on, counting from 1
+    pub line: usize,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Events of an iCalendar document.
+///
+/// What events here cannot hold is simplified: an event spanning several
+/// days keeps its first one, repeat rules other than daily, weekday,
+/// weekly and lunar ones are dropped, and categories other than reminders
+/// and holidays become todos.
+pub fn parse(document: &str) -> Result<Vec<Imported>, ParseError> {
+    let mut imported = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut draft: Option<Draft> = None;
+    let mut calendars = 0;
+
+    for (line, content) in unfold(document) {
+        let error = |reason: String| ParseError { line, reason };
+
+        let property = Property::split(&content).ok_or_else(|| error(format!("expected NAME:value, found \"{content}\"")))?;
+
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.to_uppercase();
+
+                if component == "VCALENDAR" {
+                    calendars += 1;
+                } else if components.is_empty() {
+                    return Err(error(format!("{component} outside of a calendar")));
+                }
+
+                if component == "VEVENT" {
+                    draft = Some(Draft::default());
+                }
+
+                components.push(component);
+            }
+            "END" => {
+                let component = property.value.to_uppercase();
+
+                if components.pop().as_deref() != Some(component.as_str()) {
+                    return Err(error(format!("END:{component} does not close the open component")));
+                }
+
+                if component == "VEVENT" {
+                    if let Some(event) = draft.take().map(|draft| draft.finish(line)).transpose()? {
+                        imported.push(event);
+                    }
+                }
+            }
+            _ => match (components.last().map(String::as_str), draft.as_mut()) {
+                (Some("VEVENT"), Some(draft)) => draft.read(property).map_err(error)?,
+                (Some("VALARM"), Some(draft)) if property.name == "TRIGGER" => draft.trigger(&property),
+                _ => {}
+            },
+        }
+    }
+
+    if let Some(open) = components.last() {
+        return Err(ParseError {
+            line: document.lines().count(),
+            reason: format!("{open} is not closed"),
+        });
+    }
+
+    if calendars == 0 {
+        return Err(ParseError {
+            line: 1,
+            reason: "not an iCalendar document".to_string(),
+        });
+    }
+
+    Ok(imported)
+}
+
+/// Content line split into its parts, the name upper-cased and the value
+/// still escaped
+struct Property {
+    name: String,
+    parameters: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn split(line: &str) -> Option<Property> {
+        let mut quoted = false;
+        let mut colon = None;
+        let mut semicolons = Vec::new();
+
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                ';' if !quoted => semicolons.push(i),
+                ':' if !quoted => {
+                    colon = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let colon = colon?;
+        let name_end = semicolons.first().copied().unwrap_or(colon);
+        let name = line[..name_end].trim().to_uppercase();
+
+        if name.is_empty() {
+            return None;
+        }
+
+        let bounds: Vec<usize> = semicolons.iter().copied().chain([colon]).collect();
+        let parameters = bounds
+            .windows(2)
+            .filter_map(|pair| {
+                let (key, value) = line[pair[0] + 1..pair[1]].split_once('=')?;
+
+                Some((key.trim().to_uppercase(), value.trim_matches('"').to_string()))
+            })
+            .collect();
+
+        Some(Property {
+            name,
+            parameters,
+            value: line[colon + 1..].to_string(),
+        })
+    }
+
+    fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Start or end of an event
+#[derive(Clone, Copy)]
+enum When {
+    Date(NaiveDate),
+    Time(DateTime<Tz>),
+}
+
+impl When {
+    fn read(property: &Property) -> Result<When, String> {
+        let value = property.value.trim();
+        let invalid = || format!("invalid {} \"{value}\"", property.name);
+
+        if property.parameter("VALUE") == Some("DATE") || value.len() == 8 {
+            return NaiveDate::parse_from_str(value, "%Y%m%d")
+                .map(When::Date)
+                .map_err(|_| invalid());
+        }
+
+        let (local, utc) = match value.strip_suffix('Z') {
+            Some(local) => (local, true),
+            None => (value, false),
+        };
+
+        let local = NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+
+        // Times with no zone are the same wall-clock time everywhere,
+        // which is best approached with the zone of this computer
+        let zone = if utc {
+            Tz::UTC
+        } else {
+            match property.parameter("TZID") {
+                Some(name) => name.parse().unwrap_or(Tz::UTC),
+                None => zone::local_zone(),
+            }
+        };
+
+        Ok(When::Time(zone::resolve(zone, local.date(), local.time())))
+    }
+}
+
+/// Properties of an event read so far
+#[derive(Default)]
+struct Draft {
+    uid: String,
+    summary: String,
+    description: String,
+    location: String,
+    url: String,
+    comment: String,
+    categories: String,
+    start: Option<When>,
+    end: Option<When>,
+    duration: Option<Duration>,
+    recurrence: Option<Recurrence>,
+    until: Option<When>,
+    reminder: Option<u32>,
+}
+
+impl Draft {
+    fn read(&mut self, property: Property) -> Result<(), String> {
+        match property.name.as_str() {
+            "UID" => self.uid = unescape(&property.value),
+            "SUMMARY" => self.summary = unescape(&property.value),
+            "DESCRIPTION" => self.description = unescape(&property.value),
+            "LOCATION" => self.location = unescape(&property.value),
+            "URL" => self.url = unescape(&property.value),
+            "COMMENT" => self.comment = unescape(&property.value),
+            "CATEGORIES" => self.categories = unescape(&property.value).to_uppercase(),
+            "DTSTART" => self.start = Some(When::read(&property)?),
+            "DTEND" => self.end = Some(When::read(&property)?),
+            "DURATION" => self.duration = duration(property.value.trim()),
+            LUNAR_YEARLY => {
+                let lunar = property
+                    .value
+                    .split_once('/')
+                    .and_then(|(month, day)| Some((month.trim().parse().ok()?, day.trim().parse().ok()?)));
+
+                match lunar {
+                    Some((month @ 1..=12, day @ 1..=30)) => self.recurrence = Some(Recurrence::LunarYearly { month, day }),
+                    _ => return Err(format!("invalid {LUNAR_YEARLY} \"{}\"", property.value)),
+                }
+            }
+            "RRULE" if self.recurrence.is_none() => {
+                if let Some((recurrence, until)) = read_rule(property.value.trim())? {
+                    self.recurrence = Some(recurrence);
+                    self.until = until;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Alarm of the event; only alarms before the start are kept
+    fn trigger(&mut self, property: &Property) {
+        if property.parameter("VALUE") == Some("DATE-TIME") || property.parameter("RELATED") == Some("END") {
+            return;
+        }
+
+        if let Some(before) = duration(property.value.trim()).filter(|before| *before <= Duration::zero()) {
+            self.reminder = u32::try_from(-before.num_minutes()).ok();
+        }
+    }
+
+    fn finish(self, line: usize) -> Result<Imported, ParseError> {
+        let start = self.start.ok_or_else(|| ParseError {
+            line,
+            reason: "event without DTSTART".to_string(),
+        })?;
+
+        let category = if self.categories.split(',').any(|category| category.trim() == "REMINDER") {
+            Category::Reminder
+        } else if self.categories.split(',').any(|category| category.trim() == "HOLIDAY") {
+            Category::Holiday
+        } else {
+            Category::Todo
+        };
+
+        let until = match (self.until, start) {
+            (Some(When::Date(until)), _) => Some(until),
+            (Some(When::Time(until)), When::Time(start)) => Some(until.with_timezone(&start.timezone()).date_naive()),
+            (Some(When::Time(until)), When::Date(_)) => Some(until.date_naive()),
+            (None, _) => None,
+        };
+
+        let recurrence = match (self.recurrence, until) {
+            (Some(Recurrence::Daily { .. }), Some(until)) => Some(Recurrence::Daily { until: Some(until) }),
+            (Some(Recurrence::Weekdays { .. }), Some(until)) => Some(Recurrence::Weekdays { until: Some(until) }),
+            (Some(Recurrence::Weekly { .. }), Some(until)) => Some(Recurrence::Weekly { until: Some(until) }),
+            (recurrence, _) => recurrence,
+        };
+
+        let (date, time) = match start {
+            When::Date(date) => (date, None),
+            When::Time(start) => {
+                let end = match self.end {
+                    Some(When::Time(end)) => end.with_timezone(&start.timezone()),
+                    _ => start + self.duration.unwrap_or_else(|| Duration::hours(1)),
+                };
+
+                let time = EventTime {
+                    start: start.time(),
+                    end: end.time(),
+                    zone: start.timezone(),
+                    reminder: self.reminder,
+                };
+
+                (start.date_naive(), Some(time))
+            }
+        };
+
+        // Documents are expected to name their events, but a stable
+        // identifier is needed either way
+        let uid = if self.uid.is_empty() {
+            format!("{}-{}", date.format("%Y%m%d"), self.summary)
+        } else {
+            self.uid
+        };
+
+        let mut event = Event::new(self.summary, date, time, recurrence, category);
+        event.description = self.description;
+        event.location = self.location;
+        event.url = self.url;
+        event.notes = self.comment;
+
+        Ok(Imported { uid, event })
+    }
+}
+
+/// Repeat rule of an `RRULE` value with the end it names, none for rules
+/// events here cannot follow
+fn read_rule(value: &str) -> Result<Option<(Recurrence, Option<When>)>, String> {
+    let mut frequency = None;
+    let mut days = None;
+    let mut until = None;
+
+    for part in value.split(';') {
+        match part.split_once('=').map(|(key, value)| (key.trim().to_uppercase(), value.trim())) {
+            Some((key, value)) if key == "FREQ" => frequency = Some(value.to_uppercase()),
+            Some((key, value)) if key == "BYDAY" => days = Some(value.to_uppercase()),
+            Some((key, "1")) if key == "INTERVAL" => {}
+            Some((key, _)) if key == "WKST" => {}
+            Some((key, value)) if key == "UNTIL" => {
+                let property = Property {
+                    name: "UNTIL".to_string(),
+                    parameters: Vec::new(),
+                    value: value.to_string(),
+                };
+
+                until = Some(When::read(&property)?);
+            }
+            _ => return Ok(None),
+        }
+    }
+
+    let recurrence = match (frequency.as_deref(), days.as_deref()) {
+        (Some("DAILY" | "WEEKLY"), Some("MO,TU,WE,TH,FR")) => Recurrence::Weekdays { until: None },
+        (Some("DAILY"), None) => Recurrence::Daily { until: None },
+        // A single day is the one of the start, as other programs write it
+        (Some("WEEKLY"), None) => Recurrence::Weekly { until: None },
+        (Some("WEEKLY"), Some(day)) if day.len() == 2 => Recurrence::Weekly { until: None },
+        _ => return Ok(None),
+    };
+
+    Ok(Some((recurrence, until)))
+}
+
+/// `RRULE` line of an event repeating daily, on weekdays or weekly. The
+/// last occurrence is named by its start, in UTC for timed events.
+fn repeat_rule(event: &Event) -> Option<String> {
+    let (rule, until) = match event.recurrence? {
+        Recurrence::LunarYearly { .. } => return None,
+        Recurrence::Daily { until } => ("FREQ=DAILY", until),
+        Recurrence::Weekdays { until } => ("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", until),
+        Recurrence::Weekly { until } => ("FREQ=WEEKLY", until),
+    };
+
+    let until = until.map(|until| match event.on(until).start_in(Tz::UTC) {
+        Some(start) => utc(start),
+        None => until.format("%Y%m%d").to_string(),
+    });
+
+    Some(match until {
+        Some(until) => format!("RRULE:{rule};UNTIL={until}"),
+        None => format!("RRULE:{rule}"),
+    })
+}
+
+/// Content lines of a document with the line each starts on, folded lines
+/// being joined back
+fn unfold(document: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+
+    for (i, line) in document.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+
+    lines
+}
+
+/// Signed duration such as `-PT15M` or `P1DT2H`; weeks are accepted too
+fn duration(value: &str) -> Option<Duration> {
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut seen = false;
+
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                seen = true;
+
+                total += match c {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    _ => Duration::seconds(n),
+                };
+            }
+            _ => return None,
+        }
+    }
+
+    (seen && number.is_empty()).then_some(total * sign)
+}
+
+/// Title, category, text fields and reminder of an event
+fn push_details(lines: &mut Vec<String>, event: &Event) {
+    lines.push(format!("SUMMARY:{}", escape(&event.title)));
+    lines.push(format!("CATEGORIES:{}", escape(&event.category.to_string().to_uppercase())));
+
+    for (property, value) in [
+        ("DESCRIPTION", &event.description),
+        ("LOCATION", &event.location),
+        ("URL", &event.url),
+        ("COMMENT", &event.notes),
+    ] {
+        if !value.is_empty() {
+            lines.push(format!("{property}:{}", escape(value)));
+        }
+    }
+
+    if let Some(minutes) = event.time.and_then(|time| time.reminder) {
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape(&event.title)));
+        lines.push(format!("TRIGGER:-PT{minutes}M"));
+        lines.push("END:VALARM".to_string());
+    }
+}
+
+fn push_all_day(lines: &mut Vec<String>, date: NaiveDate) {
+    lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+    lines.push(format!(
+        "DTEND;VALUE=DATE:{}",
+        date.succ_opt().unwrap_or(date).format("%Y%m%d")
+    ));
+}
+
+fn utc(instant: DateTime<Tz>) -> String {
+    instant.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Parameters and value of a wall-clock time in `zone`
+fn local(zone: Tz, time: NaiveDateTime) -> String {
+    if zone == Tz::UTC {
+        format!(":{}Z", time.format("%Y%m%dT%H%M%S"))
+    } else {
+        format!(";TZID={}:{}", zone.name(), time.format("%Y%m%dT%H%M%S"))
+    }
+}
+
+/// Stable identifier of one occurrence of an event
+fn uid(event: &Event, date: NaiveDate) -> String {
+    format!("event-{}-{}@date_picker", event.id, date.format("%Y%m%d"))
+}
+
+/// Escape a TEXT value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Reverse of `escape`
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Split a content line into lines of at most 75 octets, continuation
+/// lines starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded
+}
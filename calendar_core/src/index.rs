@@ -0,0 +1,68 @@
+//! Lookup of events by date.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+/// Positions of events in a list of events, keyed by their date
+#[derive(Debug, Clone, Default)]
+pub struct EventIndex {
+    by_date: BTreeMap<NaiveDate, Vec<usize>>,
+    /// Events repeating on more than one date, expanded when queried
+    recurring: Vec<usize>,
+}
+
+impl EventIndex {
+    /// Build the index from the position and date of every event
+    pub fn build(dates: impl IntoIterator<Item = (usize, NaiveDate)>) -> Self {
+        let mut index = Self::default();
+
+        for (i, date) in dates {
+            index.insert(date, i);
+        }
+
+        index
+    }
+
+    /// Record the event at position `i` under `date`
+    pub fn insert(&mut self, date: NaiveDate, i: usize) {
+        self.by_date.entry(date).or_default().push(i);
+    }
+
+    /// Record the repeating event at position `i`
+    pub fn insert_recurring(&mut self, i: usize) {
+        self.recurring.push(i);
+    }
+
+    /// Forget the event at position `i`, shifting the positions after it
+    /// the same way `Vec::remove` does
+    pub fn remove(&mut self, i: usize) {
+        let shift = |positions: &mut Vec<usize>| {
+            positions.retain(|&position| position != i);
+
+            for position in positions.iter_mut().filter(|position| **position > i) {
+                *position -= 1;
+            }
+        };
+
+        self.by_date.retain(|_, positions| {
+            shift(positions);
+
+            !positions.is_empty()
+        });
+
+        shift(&mut self.recurring);
+    }
+
+    /// Positions of the repeating events
+    pub fn recurring(&self) -> &[usize] {
+        &self.recurring
+    }
+
+    /// Events between `from` and `to`, both included, grouped by date
+    pub fn range(&self, from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = (&NaiveDate, &[usize])> {
+        self.by_date
+            .range(from..=to)
+            .map(|(date, positions)| (date, positions.as_slice()))
+    }
+}
@@ -0,0 +1,29 @@
+//! Calendar model without any interface: events, the named calendars they
+//! are filed in and their recurrences, date arithmetic, lunar dates, public
+//! holidays, iCalendar import and export, events typed as a phrase, free
+//! time and overlaps between events and the calendar file.
+//!
+//! The `date_picker` window and command line are built on it, and so can
+//! other frontends.
+
+pub mod calendar;
+pub mod calendars;
+pub mod conflicts;
+pub mod date;
+pub mod event;
+pub mod free_time;
+pub mod holidays;
+pub mod ics;
+pub mod index;
+pub mod lunar;
+pub mod merge;
+pub mod quick_add;
+pub mod recurrence;
+pub mod storage;
+pub mod zone;
+
+pub use calendar::Calendar;
+pub use calendars::NamedCalendar;
+pub use event::{Category, Event, EventTime};
+pub use recurrence::Recurrence;
+pub use storage::SavedState;
@@ -0,0 +1,246 @@
+//! Vietnamese lunisolar calendar.
+//!
+//! Months start on the day of the new moon and the winter solstice always
+//! falls in month 11; a year with 13 new moons between two such months gets
+//! a leap month, the first one that contains no major solar term. The
+//! astronomy follows Ho Ngoc Duc's algorithms, computed at the meridian of
+//! the given UTC offset: 7 hours for Vietnam, 8 hours for the Chinese rules.
+
+use std::f64::consts::PI;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// UTC offset, in hours, of the Vietnamese calendar
+pub const VIETNAM: f64 = 7.0;
+
+/// Julian day number of 0001-01-01 minus one
+const CE_OFFSET: i64 = 1_721_425;
+
+/// Julian day of the new moon of 1900-01-01, the origin of `new_moon`
+const EPOCH: f64 = 2_415_021.076_998_695;
+
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// Date of the lunar calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LunarDate {
+    /// Lunar year, starting at the Tết following the solar new year
+    pub year: i32,
+    /// Month, from 1 to 12
+    pub month: u32,
+    /// Day, from 1 to 30
+    pub day: u32,
+    /// Whether the month is the leap month following `month`
+    pub leap: bool,
+}
+
+impl std::fmt::Display for LunarDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}{}", self.day, self.month, if self.leap { "N" } else { "" })
+    }
+}
+
+fn julian_day(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) + CE_OFFSET
+}
+
+fn from_julian_day(day: i64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(day - CE_OFFSET).ok()?)
+}
+
+/// Julian day of the `k`th new moon after 1900-01-01
+fn new_moon(k: i64) -> f64 {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let dr = PI / 180.0;
+
+    let mut jd = 2_415_020.759_33 + 29.530_588_68 * k + 0.000_117_8 * t2 - 0.000_000_155 * t3;
+    jd += 0.000_33 * ((166.56 + 132.87 * t - 0.009_173 * t2) * dr).sin();
+
+    let m = 359.2242 + 29.105_356_08 * k - 0.000_033_3 * t2 - 0.000_003_47 * t3;
+    let mpr = 306.0253 + 385.816_918_06 * k + 0.010_730_6 * t2 + 0.000_012_36 * t3;
+    let f = 21.2964 + 390.670_506_46 * k - 0.001_652_8 * t2 - 0.000_002_39 * t3;
+
+    let mut c1 = (0.1734 - 0.000_393 * t) * (m * dr).sin() + 0.0021 * (2.0 * dr * m).sin();
+    c1 = c1 - 0.4068 * (mpr * dr).sin() + 0.0161 * (dr * 2.0 * mpr).sin();
+    c1 -= 0.0004 * (dr * 3.0 * mpr).sin();
+    c1 = c1 + 0.0104 * (dr * 2.0 * f).sin() - 0.0051 * (dr * (m + mpr)).sin();
+    c1 = c1 - 0.0074 * (dr * (m - mpr)).sin() + 0.0004 * (dr * (2.0 * f + m)).sin();
+    c1 = c1 - 0.0004 * (dr * (2.0 * f - m)).sin() - 0.0006 * (dr * (2.0 * f + mpr)).sin();
+    c1 = c1 + 0.0010 * (dr * (2.0 * f - mpr)).sin() + 0.0005 * (dr * (2.0 * mpr + m)).sin();
+
+    let delta_t = if t < -11.0 {
+        0.001 + 0.000_839 * t + 0.000_226_1 * t2 - 0.000_008_45 * t3 - 0.000_000_081 * t * t3
+    } else {
+        -0.000_278 + 0.000_265 * t + 0.000_262 * t2
+    };
+
+    jd + c1 - delta_t
+}
+
+/// Day number of the `k`th new moon, at the meridian of `offset`
+fn new_moon_day(k: i64, offset: f64) -> i64 {
+    (new_moon(k) + 0.5 + offset / 24.0).floor() as i64
+}
+
+/// Apparent longitude of the sun at Julian day `jd`, in radians
+fn sun_longitude(jd: f64) -> f64 {
+    let t = (jd - 2_451_545.0) / 36525.0;
+    let t2 = t * t;
+    let dr = PI / 180.0;
+
+    let m = 357.529_10 + 35_999.050_30 * t - 0.000_155_9 * t2 - 0.000_000_48 * t * t2;
+    let l0 = 280.466_45 + 36_000.769_83 * t + 0.000_303_2 * t2;
+    let dl = (1.914_600 - 0.004_817 * t - 0.000_014 * t2) * (dr * m).sin()
+        + (0.019_993 - 0.000_101 * t) * (dr * 2.0 * m).sin()
+        + 0.000_290 * (dr * 3.0 * m).sin();
+
+    let l = (l0 + dl) * dr;
+
+    l - PI * 2.0 * (l / (PI * 2.0)).floor()
+}
+
+/// Major solar term (0 to 11) in effect at the start of `day`
+fn solar_term(day: i64, offset: f64) -> i64 {
+    (sun_longitude(day as f64 - 0.5 - offset / 24.0) / PI * 6.0).floor() as i64
+}
+
+/// Day number of the start of month 11, the one with the winter solstice,
+/// of the lunar year ending in `year`
+fn month_11(year: i32, offset: f64) -> i64 {
+    let last_day = NaiveDate::from_ymd_opt(year, 12, 31).map_or(0, julian_day);
+    let k = ((last_day as f64 - 2_415_021.0) / SYNODIC_MONTH).floor() as i64;
+    let start = new_moon_day(k, offset);
+
+    if solar_term(start, offset) >= 9 {
+        new_moon_day(k - 1, offset)
+    } else {
+        start
+    }
+}
+
+/// How many months after the month 11 starting at `a11` the leap month comes
+fn leap_month_offset(a11: i64, offset: f64) -> i64 {
+    let k = ((a11 as f64 - EPOCH) / SYNODIC_MONTH + 0.5).floor() as i64;
+    let mut i = 1;
+    let mut arc = solar_term(new_moon_day(k + i, offset), offset);
+
+    loop {
+        let last = arc;
+        i += 1;
+        arc = solar_term(new_moon_day(k + i, offset), offset);
+
+        if arc == last || i >= 14 {
+            return i - 1;
+        }
+    }
+}
+
+/// Lunar date of `date`
+pub fn from_solar(date: NaiveDate, offset: f64) -> LunarDate {
+    let day_number = julian_day(date);
+    let k = ((day_number as f64 - EPOCH) / SYNODIC_MONTH).floor() as i64;
+
+    let mut month_start = new_moon_day(k + 1, offset);
+    if month_start > day_number {
+        month_start = new_moon_day(k, offset);
+    }
+
+    let mut a11 = month_11(date.year(), offset);
+    let mut b11 = a11;
+    let mut year = date.year();
+
+    if a11 >= month_start {
+        a11 = month_11(date.year() - 1, offset);
+    } else {
+        year += 1;
+        b11 = month_11(date.year() + 1, offset);
+    }
+
+    let day = (day_number - month_start + 1) as u32;
+    let diff = (month_start - a11) / 29;
+    let mut leap = false;
+    let mut month = diff + 11;
+
+    if b11 - a11 > 365 {
+        let leap_month_diff = leap_month_offset(a11, offset);
+
+        if diff >= leap_month_diff {
+            month = diff + 10;
+            leap = diff == leap_month_diff;
+        }
+    }
+
+    if month > 12 {
+        month -= 12;
+    }
+
+    if month >= 11 && diff < 4 {
+        year -= 1;
+    }
+
+    LunarDate {
+        year,
+        month: month as u32,
+        day,
+        leap,
+    }
+}
+
+/// Solar date of `lunar`, `None` when that month does not exist (a leap
+/// month in a year without one) or is shorter than `lunar.day`
+pub fn to_solar(lunar: LunarDate, offset: f64) -> Option<NaiveDate> {
+    let (start, length) = month_bounds(lunar, offset)?;
+
+    if lunar.day == 0 || i64::from(lunar.day) > length {
+        return None;
+    }
+
+    from_julian_day(start + i64::from(lunar.day) - 1)
+}
+
+/// Number of days, 29 or 30, of the month of `lunar`
+pub fn month_length(lunar: LunarDate, offset: f64) -> Option<u32> {
+    month_bounds(lunar, offset).map(|(_, length)| length as u32)
+}
+
+/// Day number of the first day of the month of `lunar`, and its length
+fn month_bounds(lunar: LunarDate, offset: f64) -> Option<(i64, i64)> {
+    let (a11, b11) = if lunar.month < 11 {
+        (month_11(lunar.year - 1, offset), month_11(lunar.year, offset))
+    } else {
+        (month_11(lunar.year, offset), month_11(lunar.year + 1, offset))
+    };
+
+    let k = (0.5 + (a11 as f64 - EPOCH) / SYNODIC_MONTH).floor() as i64;
+    let mut off = i64::from(lunar.month) - 11;
+
+    if off < 0 {
+        off += 12;
+    }
+
+    if b11 - a11 > 365 {
+        let leap_off = leap_month_offset(a11, offset);
+        let mut leap_month = leap_off - 2;
+
+        if leap_month < 0 {
+            leap_month += 12;
+        }
+
+        if lunar.leap && i64::from(lunar.month) != leap_month {
+            return None;
+        } else if lunar.leap || off >= leap_off {
+            off += 1;
+        }
+    } else if lunar.leap {
+        return None;
+    }
+
+    let start = new_moon_day(k + off, offset);
+    let next = new_moon_day(k + off + 1, offset);
+
+    Some((start, next - start))
+}
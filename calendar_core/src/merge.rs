@@ -0,0 +1,53 @@
+//! Three-way merge of events changed in two places at once, such as the
+//! window and a synced copy of the calendar file.
+
+use crate::{calendar::next_id, event::Event};
+
+/// Events of `mine` and `theirs`, both changed from `base`, by id.
+///
+/// Changes made on one side only are kept. An event changed on both sides
+/// keeps the local version, and one deleted on a side but changed on the
+/// other is kept. Events added on both sides with the same id are both
+/// kept, the local one getting a new id.
+pub fn merge(base: &[Event], mine: &[Event], theirs: &[Event]) -> Vec<Event> {
+    let find = |events: &'_ [Event], id: u64| events.iter().find(|event| event.id == id).cloned();
+
+    let mut merged = Vec::with_capacity(theirs.len().max(mine.len()));
+    let mut renumbered = Vec::new();
+
+    for ours in mine {
+        match (find(base, ours.id), find(theirs, ours.id)) {
+            (Some(original), Some(their)) => merged.push(if *ours != original { ours.clone() } else { their }),
+            // Deleted there, kept only if changed here
+            (Some(original), None) => {
+                if *ours != original {
+                    merged.push(ours.clone());
+                }
+            }
+            (None, Some(their)) if their != *ours => {
+                merged.push(their);
+                renumbered.push(ours.clone());
+            }
+            (None, _) => merged.push(ours.clone()),
+        }
+    }
+
+    for their in theirs {
+        if find(mine, their.id).is_some() {
+            continue;
+        }
+
+        match find(base, their.id) {
+            // Deleted here, kept only if changed there
+            Some(original) if original == *their => {}
+            _ => merged.push(their.clone()),
+        }
+    }
+
+    for mut event in renumbered {
+        event.id = next_id(&merged);
+        merged.push(event);
+    }
+
+    merged
+}
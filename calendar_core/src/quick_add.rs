@@ -0,0 +1,645 @@
+//! Events typed as one phrase, such as "lunch with An tomorrow 12:30",
+//! "standup every weekday 9am" or "họp nhóm thứ hai 9h sáng", in English
+//! and Vietnamese.
+//!
+//! Dates written with slashes are day first, `17/2` being the 17th of
+//! February, and dates without a year are the next ones to come. "Next
+//! Monday" and "thứ hai tuần sau" are the Monday of next week. Words that
+//! are not part of a date, time or repeat rule make the title.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+
+use crate::recurrence::Recurrence;
+
+/// Words dropped from the title along with the date or time they introduce
+const FILLERS: [&str; 7] = ["at", "on", "from", "luc", "vao", "tu", "ngay"];
+
+/// Words between the two ends of a range of dates or times
+const CONNECTORS: [&str; 7] = ["-", "–", "to", "until", "till", "through", "den"];
+
+/// What a phrase says of an event
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QuickAdd {
+    pub title: String,
+    /// First day, none when the phrase names none
+    pub date: Option<NaiveDate>,
+    pub start: Option<NaiveTime>,
+    /// End of a range such as `9-10am`
+    pub end: Option<NaiveTime>,
+    pub recurrence: Option<Recurrence>,
+}
+
+impl QuickAdd {
+    /// Whether the phrase says more than the title
+    pub fn is_planned(&self) -> bool {
+        self.date.is_some() || self.start.is_some() || self.recurrence.is_some()
+    }
+}
+
+/// Read `phrase` typed on `today`
+pub fn parse(phrase: &str, today: NaiveDate) -> QuickAdd {
+    let words = split(phrase);
+    let mut used = vec![false; words.len()];
+    let mut found = Found::default();
+
+    let mut i = 0;
+    while i < words.len() {
+        match found.read(&words[i..], today) {
+            0 => i += 1,
+            n => {
+                used[i..i + n].fill(true);
+                i += n;
+            }
+        }
+    }
+
+    for i in (0..words.len().saturating_sub(1)).rev() {
+        if !used[i] && used[i + 1] && FILLERS.contains(&words[i].plain.as_str()) {
+            used[i] = true;
+        }
+    }
+
+    let mut title = String::new();
+    for (word, _) in words.iter().zip(&used).filter(|(_, used)| !**used) {
+        if !title.is_empty() && !word.joined {
+            title.push(' ');
+        }
+        title.push_str(word.text);
+    }
+
+    found.finish(title.trim_matches([',', ';', ':', '-', '–', ' ']).to_string(), today)
+}
+
+/// Part of the phrase, words with a dash being split around it
+struct Word<'a> {
+    text: &'a str,
+    /// Lower-cased without punctuation around it nor Vietnamese accents
+    plain: String,
+    /// Whether it follows the previous word without a space
+    joined: bool,
+}
+
+impl<'a> Word<'a> {
+    fn new(text: &'a str, joined: bool) -> Self {
+        Word {
+            text,
+            plain: plain(text.trim_matches([',', ';', '!', '?', '(', ')', '"']).trim_end_matches('.')),
+            joined,
+        }
+    }
+
+    /// Whether it is written in lower case, names such as "Mai" not being
+    /// read as dates
+    fn is_lowercase(&self) -> bool {
+        self.text.chars().next().is_some_and(char::is_lowercase)
+    }
+}
+
+fn split(phrase: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+
+    for chunk in phrase.split_whitespace() {
+        if NaiveDate::parse_from_str(chunk.trim_end_matches([',', ';', '.']), "%Y-%m-%d").is_ok() {
+            words.push(Word::new(chunk, false));
+            continue;
+        }
+
+        let mut rest = chunk;
+        let mut joined = false;
+
+        while let Some(at) = rest.find(['-', '–']) {
+            let dash = rest[at..].chars().next().map_or(1, char::len_utf8);
+
+            if at > 0 {
+                words.push(Word::new(&rest[..at], joined));
+                joined = true;
+            }
+
+            words.push(Word::new(&rest[at..at + dash], joined));
+            joined = true;
+            rest = &rest[at + dash..];
+        }
+
+        if !rest.is_empty() {
+            words.push(Word::new(rest, joined));
+        }
+    }
+
+    words
+}
+
+/// Lower case without the accents of Vietnamese, which are often left out
+/// when typing
+fn plain(word: &str) -> String {
+    word.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'á' | 'ả' | 'ã' | 'ạ' | 'ă' | 'ằ' | 'ắ' | 'ẳ' | 'ẵ' | 'ặ' | 'â' | 'ầ' | 'ấ' | 'ẩ' | 'ẫ' | 'ậ' => 'a',
+            'è' | 'é' | 'ẻ' | 'ẽ' | 'ẹ' | 'ê' | 'ề' | 'ế' | 'ể' | 'ễ' | 'ệ' => 'e',
+            'ì' | 'í' | 'ỉ' | 'ĩ' | 'ị' => 'i',
+            'ò' | 'ó' | 'ỏ' | 'õ' | 'ọ' | 'ô' | 'ồ' | 'ố' | 'ổ' | 'ỗ' | 'ộ' | 'ơ' | 'ờ' | 'ớ' | 'ở' | 'ỡ' | 'ợ' => 'o',
+            'ù' | 'ú' | 'ủ' | 'ũ' | 'ụ' | 'ư' | 'ừ' | 'ứ' | 'ử' | 'ữ' | 'ự' => 'u',
+            'ỳ' | 'ý' | 'ỷ' | 'ỹ' | 'ỵ' => 'y',
+            'đ' => 'd',
+            c => c,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Repeat {
+    Daily,
+    Weekdays,
+    Weekly(Option<Weekday>),
+}
+
+/// Parts of the event read so far, the first of each kind counting
+#[derive(Default)]
+struct Found {
+    date: Option<NaiveDate>,
+    /// End of a range of days, or the day a repeat rule stops
+    last: Option<NaiveDate>,
+    start: Option<NaiveTime>,
+    end: Option<NaiveTime>,
+    repeat: Option<Repeat>,
+}
+
+impl Found {
+    /// Read what `words` start with, returning how many words it took
+    fn read(&mut self, words: &[Word], today: NaiveDate) -> usize {
+        if self.repeat.is_none() {
+            if let Some((n, repeat)) = repeat(words) {
+                self.repeat = Some(repeat);
+                return n;
+            }
+        }
+
+        if self.last.is_none() && matches!(words[0].plain.as_str(), "until" | "till" | "den") {
+            if let Some((n, last, _)) = date(&words[1..], today) {
+                self.last = Some(last);
+                return n + 1;
+            }
+        }
+
+        if self.date.is_none() {
+            if let Some((n, first, last)) = date(words, today) {
+                self.date = Some(first);
+                self.last = self.last.or(last);
+                return n;
+            }
+        }
+
+        if self.start.is_none() {
+            if let Some((n, start, end)) = time(words) {
+                self.start = Some(start);
+                self.end = end;
+                return n;
+            }
+        }
+
+        0
+    }
+
+    fn finish(self, title: String, today: NaiveDate) -> QuickAdd {
+        // A range of days is the same event every day
+        let repeat = self.repeat.or(self.last.map(|_| Repeat::Daily));
+
+        let date = self.date.or_else(|| match repeat? {
+            Repeat::Weekly(Some(weekday)) => Some(on_or_after(today, weekday)),
+            Repeat::Weekdays => std::iter::successors(Some(today), |day| day.succ_opt())
+                .find(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun)),
+            _ => Some(today),
+        });
+
+        let until = self.last;
+        let recurrence = repeat.map(|repeat| match repeat {
+            Repeat::Daily => Recurrence::Daily { until },
+            Repeat::Weekdays => Recurrence::Weekdays { until },
+            Repeat::Weekly(_) => Recurrence::Weekly { until },
+        });
+
+        QuickAdd {
+            title,
+            date,
+            start: self.start,
+            end: self.end,
+            recurrence,
+        }
+    }
+}
+
+fn repeat(words: &[Word]) -> Option<(usize, Repeat)> {
+    let plain = |i: usize| words.get(i).map(|word| word.plain.as_str());
+
+    let found = match (plain(0)?, plain(1), plain(2), plain(3)) {
+        ("daily" | "everyday", ..) => (1, Repeat::Daily),
+        ("weekdays", ..) => (1, Repeat::Weekdays),
+        ("weekly", ..) => (1, Repeat::Weekly(None)),
+        ("every" | "each", Some("day"), ..) => (2, Repeat::Daily),
+        ("every" | "each", Some("weekday" | "weekdays"), ..) => (2, Repeat::Weekdays),
+        ("every" | "each", Some("week"), ..) => (2, Repeat::Weekly(None)),
+        ("moi" | "hang" | "cac", Some("ngay"), Some("trong"), Some("tuan")) => (4, Repeat::Weekdays),
+        ("cac", Some("ngay"), Some("thuong"), _) => (3, Repeat::Weekdays),
+        ("ngay", Some("thuong"), ..) => (2, Repeat::Weekdays),
+        ("moi" | "hang", Some("ngay"), ..) => (2, Repeat::Daily),
+        ("moi" | "hang", Some("tuan"), ..) => (2, Repeat::Weekly(None)),
+        ("every" | "each" | "moi", ..) => {
+            let (n, weekday) = weekday(&words[1..])?;
+
+            (n + 1, Repeat::Weekly(Some(weekday)))
+        }
+        // thứ hai hằng tuần
+        _ => {
+            let (n, weekday) = weekday(words)?;
+
+            match (plain(n), plain(n + 1)) {
+                (Some("moi" | "hang"), Some("tuan")) => (n + 2, Repeat::Weekly(Some(weekday))),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(found)
+}
+
+/// First day and the last of a range, with how many words they took
+fn date(words: &[Word], today: NaiveDate) -> Option<(usize, NaiveDate, Option<NaiveDate>)> {
+    if let Some((n, date)) = relative(words, today).or_else(|| weekday_date(words, today)) {
+        return Some((n, date, None));
+    }
+
+    let connected = |at: usize| words.get(at).is_some_and(|word| CONNECTORS.contains(&word.plain.as_str()));
+
+    // 17-20 Feb, 17-20/2
+    if let Some(first) = day(words.first()?).filter(|_| connected(1)) {
+        if let Some((n, last)) = single(&words[2..], today) {
+            if let Some(first) = last.with_day(first).filter(|first| *first <= last) {
+                return Some((n + 2, first, Some(last)));
+            }
+        }
+    }
+
+    let (n, first) = single(words, today)?;
+
+    // Feb 17 - Feb 20, Feb 17-20
+    if connected(n) {
+        if let Some((m, last)) = single(&words[n + 1..], first) {
+            return Some((n + m + 1, first, Some(last)));
+        }
+
+        if let Some(last) = words.get(n + 1).and_then(day).and_then(|last| first.with_day(last)).filter(|last| *last > first) {
+            return Some((n + 2, first, Some(last)));
+        }
+    }
+
+    Some((n, first, None))
+}
+
+/// Days named from today
+fn relative(words: &[Word], today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let plain = |i: usize| words.get(i).map(|word| word.plain.as_str());
+
+    let (n, days) = match (plain(0)?, plain(1), plain(2)) {
+        ("day", Some("after"), Some("tomorrow")) => (3, 2),
+        ("today" | "tonight", ..) => (1, 0),
+        ("tomorrow" | "tmr" | "tmrw", ..) => (1, 1),
+        ("hom" | "toi", Some("nay"), _) => (2, 0),
+        ("ngay", Some("mai"), _) => (2, 1),
+        ("ngay", Some("kia"), _) => (2, 2),
+        ("in", Some(count), Some(unit @ ("day" | "days" | "week" | "weeks"))) => {
+            let count: i64 = count.parse().ok()?;
+
+            (3, if unit.starts_with("week") { count * 7 } else { count })
+        }
+        (count, Some(unit @ ("ngay" | "tuan")), Some("nua")) => {
+            let count: i64 = count.parse().ok()?;
+
+            (3, if unit == "tuan" { count * 7 } else { count })
+        }
+        // Capitalized, "Mai" is more likely a name
+        ("mai", ..) if words[0].is_lowercase() => (1, 1),
+        _ if words[0].text.to_lowercase() == "mốt" => (1, 2),
+        _ => return None,
+    };
+
+    Some((n, today.checked_add_signed(Duration::days(days))?))
+}
+
+/// Monday, next Friday, thứ hai tuần sau
+fn weekday_date(words: &[Word], today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let (skip, mut next) = match words.first()?.plain.as_str() {
+        "next" => (1, true),
+        "this" => (1, false),
+        _ => (0, false),
+    };
+
+    let (n, weekday) = weekday(&words[skip..])?;
+    let mut n = skip + n;
+
+    if words.get(n).is_some_and(|word| word.plain == "tuan")
+        && words.get(n + 1).is_some_and(|word| matches!(word.plain.as_str(), "sau" | "toi"))
+    {
+        next = true;
+        n += 2;
+    }
+
+    let date = if next {
+        let monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+
+        monday + Duration::days(7 + i64::from(weekday.num_days_from_monday()))
+    } else {
+        on_or_after(today, weekday)
+    };
+
+    Some((n, date))
+}
+
+fn weekday(words: &[Word]) -> Option<(usize, Weekday)> {
+    let first = words.first()?.plain.as_str();
+    let second = words.get(1).map(|word| word.plain.as_str());
+
+    let vietnamese = match (first, second) {
+        ("thu", Some("hai" | "2")) => Some(Weekday::Mon),
+        ("thu", Some("ba" | "3")) => Some(Weekday::Tue),
+        ("thu", Some("tu" | "4")) => Some(Weekday::Wed),
+        ("thu", Some("nam" | "5")) => Some(Weekday::Thu),
+        ("thu", Some("sau" | "6")) => Some(Weekday::Fri),
+        ("thu", Some("bay" | "7")) => Some(Weekday::Sat),
+        ("chu", Some("nhat")) => Some(Weekday::Sun),
+        _ => None,
+    };
+
+    if let Some(weekday) = vietnamese {
+        return Some((2, weekday));
+    }
+
+    let weekday = match first {
+        "monday" | "mon" | "t2" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" | "t3" => Weekday::Tue,
+        "wednesday" | "wed" | "t4" => Weekday::Wed,
+        "thursday" | "thu" | "thur" | "thurs" | "t5" => Weekday::Thu,
+        "friday" | "fri" | "t6" => Weekday::Fri,
+        "saturday" | "t7" => Weekday::Sat,
+        "sunday" | "cn" => Weekday::Sun,
+        _ => return None,
+    };
+
+    Some((1, weekday))
+}
+
+fn on_or_after(day: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday() - day.weekday().num_days_from_monday()) % 7;
+
+    day + Duration::days(i64::from(ahead))
+}
+
+/// One written date, those without a year being the first on or after
+/// `since`
+fn single(words: &[Word], since: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let first = words.first()?;
+
+    if let Ok(date) = NaiveDate::parse_from_str(&first.plain, "%Y-%m-%d") {
+        return Some((1, date));
+    }
+
+    // 17/2, 17/2/2027
+    let mut parts = first.plain.split('/');
+    if let (Some(day), Some(month)) = (parts.next(), parts.next()) {
+        let year = match parts.next() {
+            Some(year) => Some(year_of(year)?),
+            None => None,
+        };
+
+        return Some((1, dated(day.parse().ok()?, month.parse().ok()?, year, since)?));
+    }
+
+    let year_at = |at: usize| words.get(at).and_then(|word| year_of(&word.plain).filter(|_| word.plain.len() == 4));
+
+    // Feb 17, Feb 17th 2027
+    if let Some(month) = month(&first.plain) {
+        let day = day(words.get(1)?)?;
+        let year = year_at(2);
+
+        return Some((2 + usize::from(year.is_some()), dated(day, month, year, since)?));
+    }
+
+    let day = day(first)?;
+
+    // 17 tháng 2, 17 tháng 2 năm 2027
+    if words.get(1).is_some_and(|word| word.plain == "thang") {
+        let month = words.get(2)?.plain.parse().ok()?;
+        let year = words.get(3).filter(|word| word.plain == "nam").and_then(|_| year_at(4));
+
+        return Some((3 + 2 * usize::from(year.is_some()), dated(day, month, year, since)?));
+    }
+
+    // 17 Feb, 17th of February
+    let of = usize::from(words.get(1).is_some_and(|word| word.plain == "of"));
+    let month = month(&words.get(1 + of)?.plain)?;
+    let year = year_at(2 + of);
+
+    Some((2 + of + usize::from(year.is_some()), dated(day, month, year, since)?))
+}
+
+fn dated(day: u32, month: u32, year: Option<i32>, since: NaiveDate) -> Option<NaiveDate> {
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+        None => {
+            let date = NaiveDate::from_ymd_opt(since.year(), month, day)?;
+
+            if date < since {
+                NaiveDate::from_ymd_opt(since.year() + 1, month, day)
+            } else {
+                Some(date)
+            }
+        }
+    }
+}
+
+fn year_of(value: &str) -> Option<i32> {
+    let year: i32 = value.parse().ok()?;
+
+    match value.len() {
+        2 => Some(2000 + year),
+        4 if (1900..=2200).contains(&year) => Some(year),
+        _ => None,
+    }
+}
+
+/// Day of the month, with an English ordinal suffix or not
+fn day(word: &Word) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.plain.strip_suffix(suffix))
+        .unwrap_or(&word.plain);
+
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn month(word: &str) -> Option<u32> {
+    let month = match word {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+
+    Some(month)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Half {
+    Morning,
+    /// trưa: 11 and 12 are before noon, 1 and later after
+    Noon,
+    Afternoon,
+}
+
+/// Time of day as written, before the half of the day is settled
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    hour: u32,
+    minute: u32,
+    half: Option<Half>,
+    /// Whether it is written as a time rather than a bare number
+    marked: bool,
+}
+
+impl Clock {
+    fn time(self) -> Option<NaiveTime> {
+        let hour = match self.half {
+            Some(Half::Morning) if self.hour == 12 => 0,
+            Some(Half::Afternoon) if self.hour < 12 => self.hour + 12,
+            Some(Half::Noon) if self.hour < 11 => self.hour + 12,
+            _ => self.hour,
+        };
+
+        NaiveTime::from_hms_opt(hour, self.minute, 0)
+    }
+}
+
+/// Start and end of a time or range of times, with how many words they
+/// took
+fn time(words: &[Word]) -> Option<(usize, NaiveTime, Option<NaiveTime>)> {
+    // A bare number is taken for a time after "at" only
+    let skip = usize::from(matches!(words.first()?.plain.as_str(), "at" | "luc"));
+
+    let (n, mut start) = moment(&words[skip..])?;
+    let mut n = skip + n;
+
+    let end = match words.get(n) {
+        Some(word) if CONNECTORS.contains(&word.plain.as_str()) => moment(&words[n + 1..]),
+        _ => None,
+    };
+
+    let end = match end {
+        Some((m, mut end)) if start.marked || end.marked || skip == 1 => {
+            n += m + 1;
+
+            // 9-10am, 11-1pm
+            match (start.half, end.half) {
+                (None, Some(half)) => start.half = Some(if start.hour % 12 <= end.hour % 12 { half } else { Half::Morning }),
+                (Some(half), None) => end.half = Some(if end.hour % 12 >= start.hour % 12 { half } else { Half::Afternoon }),
+                _ => {}
+            }
+
+            Some(end.time()?)
+        }
+        _ if start.marked || skip == 1 => None,
+        _ => return None,
+    };
+
+    Some((n, start.time()?, end))
+}
+
+/// One time with the words after it telling the half of the day
+fn moment(words: &[Word]) -> Option<(usize, Clock)> {
+    let mut clock = clock(&words.first()?.plain)?;
+    let mut n = 1;
+    let plain = |i: usize| words.get(i).map(|word| word.plain.as_str());
+
+    // 9 giờ, 9 giờ 30, 9 giờ rưỡi
+    if !clock.marked && plain(1) == Some("gio") {
+        clock.marked = true;
+        n = 2;
+
+        match plain(2) {
+            Some("ruoi") => {
+                clock.minute = 30;
+                n = 3;
+            }
+            Some(minute) => {
+                if let Some(minute) = minute.parse().ok().filter(|minute| *minute < 60) {
+                    clock.minute = minute;
+                    n = 3 + usize::from(plain(3) == Some("phut"));
+                }
+            }
+            None => {}
+        }
+    }
+
+    if clock.half.is_none() && clock.hour <= 12 {
+        let half = match plain(n) {
+            Some("am" | "a.m" | "sang") => Some(Half::Morning),
+            Some("pm" | "p.m" | "chieu" | "toi") => Some(Half::Afternoon),
+            Some("trua") => Some(Half::Noon),
+            _ => None,
+        };
+
+        if half.is_some() {
+            clock.half = half;
+            clock.marked = true;
+
+            // tối nay is also today
+            if !(plain(n) == Some("toi") && plain(n + 1) == Some("nay")) {
+                n += 1;
+            }
+        }
+    }
+
+    Some((n, clock))
+}
+
+/// 9, 9am, 9:30pm, 9h, 9h30, 9g30, noon
+fn clock(word: &str) -> Option<Clock> {
+    match word {
+        "noon" | "midday" => return Some(Clock { hour: 12, minute: 0, half: None, marked: true }),
+        "midnight" => return Some(Clock { hour: 0, minute: 0, half: None, marked: true }),
+        _ => {}
+    }
+
+    let (word, half) = if let Some(word) = word.strip_suffix("am").or_else(|| word.strip_suffix("a.m")) {
+        (word, Some(Half::Morning))
+    } else if let Some(word) = word.strip_suffix("pm").or_else(|| word.strip_suffix("p.m")) {
+        (word, Some(Half::Afternoon))
+    } else {
+        (word, None)
+    };
+
+    let (hour, minute, marked) = match word.split_once(':').or_else(|| word.split_once(['h', 'g'])) {
+        Some((hour, minute)) => (hour, minute, true),
+        None => (word, "", half.is_some()),
+    };
+
+    if hour.is_empty() || !hour.chars().all(|c| c.is_ascii_digit()) || !minute.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hour: u32 = hour.parse().ok()?;
+    let minute: u32 = if minute.is_empty() { 0 } else { minute.parse().ok()? };
+
+    if hour > 23 || minute > 59 || (half.is_some() && !(1..=12).contains(&hour)) {
+        return None;
+    }
+
+    Some(Clock { hour, minute, half, marked })
+}
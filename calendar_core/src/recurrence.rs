@@ -0,0 +1,111 @@
+//! Repeating events.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::lunar::{self, LunarDate};
+
+/// How an event repeats after the date it was created on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recurrence {
+    /// Every year on the same day of the same lunar month. Events created in
+    /// a leap month repeat in the regular month of that number, and day 30
+    /// falls back to day 29 in years where the month is short.
+    LunarYearly {
+        /// Lunar month, leap months counting as the regular one
+        month: u32,
+        /// Day of the lunar month, from 1 to 30
+        day: u32,
+    },
+    /// Every day, until the last day given if any
+    Daily {
+        #[serde(default)]
+        until: Option<NaiveDate>,
+    },
+    /// Monday to Friday
+    Weekdays {
+        #[serde(default)]
+        until: Option<NaiveDate>,
+    },
+    /// Every week on the weekday of the first date
+    Weekly {
+        #[serde(default)]
+        until: Option<NaiveDate>,
+    },
+}
+
+impl Recurrence {
+    /// Every year on the lunar date of `date`
+    pub fn lunar_yearly(date: NaiveDate) -> Self {
+        let lunar = lunar::from_solar(date, lunar::VIETNAM);
+
+        Recurrence::LunarYearly {
+            month: lunar.month,
+            day: lunar.day,
+        }
+    }
+
+    /// Dates between `from` and `to`, both included, of an event first
+    /// happening on `start`
+    pub fn occurrences(&self, start: NaiveDate, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let from = from.max(start);
+
+        if from > to {
+            return Vec::new();
+        }
+
+        let mut dates: Vec<NaiveDate> = match *self {
+            Recurrence::LunarYearly { month, day } => {
+                let first_year = lunar::from_solar(from, lunar::VIETNAM).year;
+                let last_year = lunar::from_solar(to, lunar::VIETNAM).year;
+
+                (first_year..=last_year)
+                    .filter_map(|year| {
+                        let month_start = LunarDate {
+                            year,
+                            month,
+                            day: 1,
+                            leap: false,
+                        };
+                        let length = lunar::month_length(month_start, lunar::VIETNAM)?;
+
+                        lunar::to_solar(
+                            LunarDate {
+                                day: day.min(length),
+                                ..month_start
+                            },
+                            lunar::VIETNAM,
+                        )
+                    })
+                    .filter(|date| (from..=to).contains(date))
+                    .collect()
+            }
+            Recurrence::Daily { until } | Recurrence::Weekdays { until } | Recurrence::Weekly { until } => {
+                let last = until.map_or(to, |until| until.min(to));
+
+                std::iter::successors(Some(from), |date| date.succ_opt())
+                    .take_while(|date| *date <= last)
+                    .filter(|date| match self {
+                        Recurrence::Weekdays { .. } => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+                        Recurrence::Weekly { .. } => date.weekday() == start.weekday(),
+                        _ => true,
+                    })
+                    .collect()
+            }
+        };
+
+        if from == start && !dates.contains(&start) {
+            dates.insert(0, start);
+        }
+
+        dates
+    }
+
+    /// Last day the event repeats on, none when it goes on forever
+    pub fn until(&self) -> Option<NaiveDate> {
+        match *self {
+            Recurrence::LunarYearly { .. } => None,
+            Recurrence::Daily { until } | Recurrence::Weekdays { until } | Recurrence::Weekly { until } => until,
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Safe access to the calendar file, shared by the window and the command
+//! line.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono_tz::Tz;
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar,
+    calendars::{self, NamedCalendar},
+    event::Event,
+    holidays::Country,
+};
+
+/// Contents of the calendar file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedState {
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub display_zone: Option<Tz>,
+    #[serde(default)]
+    pub secondary_zone: Option<Tz>,
+    #[serde(default)]
+    pub show_lunar: bool,
+    /// Countries whose holidays were shown, in files written before they
+    /// were chosen in the settings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holiday_countries: Vec<Country>,
+    #[serde(default)]
+    pub calendars: Vec<NamedCalendar>,
+}
+
+/// Why the calendar file could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The file or its lock could not be opened or read
+    File(io::ErrorKind),
+    /// The file is not a calendar, `line` and `column` starting at 1
+    Format { line: usize, column: usize, reason: String },
+}
+
+/// Why the calendar file could not be written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    /// The folder or the lock of the file could not be opened
+    File(io::ErrorKind),
+    Write(io::ErrorKind),
+    Format(String),
+    /// Another program changed the file since it was last read
+    Changed,
+}
+
+impl LoadError {
+    /// Whether there is simply no calendar file yet
+    pub fn is_missing(&self) -> bool {
+        *self == LoadError::File(io::ErrorKind::NotFound)
+    }
+
+    fn format(error: serde_json::Error) -> Self {
+        let (line, column) = (error.line(), error.column());
+        let message = error.to_string();
+        let location = format!(" at line {line} column {column}");
+
+        LoadError::Format {
+            line,
+            column,
+            reason: message.strip_suffix(&location).unwrap_or(&message).to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::File(kind) => write!(f, "cannot read the calendar file: {kind}"),
+            LoadError::Format { line, column, reason } => {
+                write!(f, "invalid calendar file at line {line}, column {column}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::File(kind) => write!(f, "cannot open the calendar file: {kind}"),
+            SaveError::Write(kind) => write!(f, "cannot write the calendar file: {kind}"),
+            SaveError::Format(reason) => write!(f, "cannot encode the calendar: {reason}"),
+            SaveError::Changed => write!(f, "the calendar file was changed by another program"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl std::error::Error for SaveError {}
+
+impl SavedState {
+    /// Read the calendar file at `path`, the caller holding its lock
+    pub fn read(path: &Path) -> Result<SavedState, LoadError> {
+        let contents = fs::read_to_string(path).map_err(|error| LoadError::File(error.kind()))?;
+
+        let mut state: SavedState = serde_json::from_str(&contents).map_err(LoadError::format)?;
+
+        // Files written before events had ids
+        calendar::assign_ids(&mut state.events);
+
+        // And before they were filed in named calendars
+        calendars::repair(&mut state.calendars, &mut state.events);
+
+        Ok(state)
+    }
+
+    /// Write the calendar file at `path`, the caller holding its lock
+    pub fn write(&self, path: &Path) -> Result<(), SaveError> {
+        let json = serde_json::to_string_pretty(self).map_err(|error| SaveError::Format(error.to_string()))?;
+
+        write_atomic(path, json.as_bytes()).map_err(|error| SaveError::Write(error.kind()))
+    }
+
+    /// Read the calendar file at `path` under its lock
+    pub fn load(path: &Path) -> Result<SavedState, LoadError> {
+        let _lock = lock(path).map_err(|error| LoadError::File(error.kind()))?;
+
+        Self::read(path)
+    }
+
+    /// Write the calendar file at `path` under its lock
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        let _lock = lock(path).map_err(|error| SaveError::File(error.kind()))?;
+
+        self.write(path)
+    }
+
+    /// Write the calendar file at `path` under its lock, unless its events
+    /// are none of `known`, as another program changed them meanwhile. A
+    /// missing file is written again.
+    pub fn replace(&self, path: &Path, known: &[Vec<Event>]) -> Result<(), SaveError> {
+        let _lock = lock(path).map_err(|error| SaveError::File(error.kind()))?;
+
+        match Self::read(path) {
+            Ok(current) if !known.contains(&current.events) => return Err(SaveError::Changed),
+            Err(LoadError::Format { .. }) => return Err(SaveError::Changed),
+            Err(LoadError::File(kind)) if kind != io::ErrorKind::NotFound => return Err(SaveError::File(kind)),
+            _ => {}
+        }
+
+        self.write(path)
+    }
+}
+
+/// Calendar file used unless the settings say otherwise
+pub fn default_path() -> PathBuf {
+    let mut path = if let Some(project_dirs) = directories_next::ProjectDirs::from("rs", "Iced", "CalendarApp") {
+        project_dirs.data_dir().into()
+    } else {
+        std::env::current_dir().unwrap_or_default()
+    };
+
+    path.push("calendar.json");
+
+    path
+}
+
+/// Exclusive hold on a calendar file, released when dropped
+pub struct Lock {
+    _file: File,
+}
+
+/// Wait until no other process holds the lock of `path`, then take it
+pub fn lock(path: &Path) -> io::Result<Lock> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling(path, "lock"))?;
+
+    file.lock_exclusive()?;
+
+    Ok(Lock { _file: file })
+}
+
+/// Replace the contents of `path` by writing them aside first, so readers
+/// never see a half-written file
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temporary = sibling(path, "tmp");
+
+    {
+        let mut file = File::create(&temporary)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+
+    fs::rename(temporary, path)
+}
+
+/// `path` with `extension` appended, `calendar.json.lock` for instance
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+
+    name.into()
+}
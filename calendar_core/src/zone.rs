@@ -0,0 +1,40 @@
+//! Time zones events are planned and displayed in.
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+
+/// Time zone of the system, UTC when it cannot be determined
+pub fn local_zone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Every IANA zone, for pick lists
+pub fn all() -> &'static [Tz] {
+    &chrono_tz::TZ_VARIANTS
+}
+
+/// Instant at which the clocks of `zone` show `time` on `date`.
+///
+/// A time repeated when the clocks go back resolves to its first occurrence,
+/// and a time skipped when they go forward is moved past the gap.
+pub fn resolve(zone: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    let local = date.and_time(time);
+
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(instant) => instant,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => zone
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .unwrap_or_else(|| zone.from_utc_datetime(&local)),
+    }
+}
+
+/// Wall-clock time in `other` when the clocks of `zone` show `time` on
+/// `date`, following daylight saving changes of either zone on that date
+pub fn convert(zone: Tz, date: NaiveDate, time: NaiveTime, other: Tz) -> NaiveTime {
+    resolve(zone, date, time).with_timezone(&other).time()
+}
@@ -0,0 +1,295 @@
+use calendar_core::{
+    conflicts::{self, Conflict},
+    free_time::{self, Slot, SlotSearch},
+    ics, zone, Calendar, Category, Event, EventTime, Recurrence,
+};
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+mod common;
+
+use common::{day, event, time, timed};
+
+#[test]
+fn ids_are_assigned_and_kept() {
+    let mut legacy = event("Legacy", day(2026, 3, 1));
+    legacy.id = 0;
+
+    let mut calendar = Calendar::new(vec![legacy], Tz::UTC);
+    assert_eq!(calendar.events()[0].id, 1);
+
+    let first = calendar.add(event("First", day(2026, 3, 2)));
+    let second = calendar.add(event("Second", day(2026, 3, 3)));
+    assert_eq!((first, second), (2, 3));
+
+    calendar.remove(calendar.position(first).unwrap());
+    assert_eq!(calendar.position(second), Some(1));
+    assert_eq!(calendar.add(event("Third", day(2026, 3, 4))), 4);
+}
+
+#[test]
+fn occurrences_by_day() {
+    let mut calendar = Calendar::default();
+    let meeting = calendar.add(event("Meeting", day(2026, 3, 10)));
+    calendar.add(event("Later", day(2026, 5, 1)));
+    let tet = calendar.add(Event::new(
+        "Tết".to_string(),
+        day(2025, 1, 29),
+        None,
+        Some(Recurrence::lunar_yearly(day(2025, 1, 29))),
+        Category::Holiday,
+    ));
+
+    let by_day = calendar.occurrences(day(2026, 2, 1), day(2026, 3, 31));
+    let titles = |date| -> Vec<u64> { by_day[&date].iter().map(|&i| calendar.events()[i].id).collect() };
+
+    assert_eq!(by_day.len(), 2);
+    assert_eq!(titles(day(2026, 2, 17)), vec![tet]);
+    assert_eq!(titles(day(2026, 3, 10)), vec![meeting]);
+}
+
+#[test]
+fn timed_events_move_with_the_display_zone() {
+    let time = EventTime {
+        start: time(23, 0),
+        end: time(23, 30),
+        zone: Tz::UTC,
+        reminder: None,
+    };
+    let mut calendar = Calendar::new(
+        vec![Event::new("Call".to_string(), day(2026, 6, 1), Some(time), None, Category::Reminder)],
+        Tz::UTC,
+    );
+
+    assert!(calendar.occurrences(day(2026, 6, 1), day(2026, 6, 1)).contains_key(&day(2026, 6, 1)));
+
+    calendar.set_zone(Tz::Asia__Ho_Chi_Minh);
+    assert!(calendar.occurrences(day(2026, 6, 1), day(2026, 6, 1)).is_empty());
+    assert!(calendar.occurrences(day(2026, 6, 2), day(2026, 6, 2)).contains_key(&day(2026, 6, 2)));
+}
+
+#[test]
+fn repeating_events_move_with_the_display_zone() {
+    // Monday June 1, 2026 at 23:00 UTC is already Tuesday in Ho Chi Minh City
+    let mut weekly = timed("Weekly call", day(2026, 6, 1), time(23, 0), time(23, 30), 0);
+    weekly.recurrence = Some(Recurrence::Weekly { until: None });
+    let once = timed("Call", day(2026, 6, 8), time(23, 0), time(23, 30), 0);
+    let calendar = Calendar::new(vec![weekly, once], Tz::Asia__Ho_Chi_Minh);
+
+    assert!(calendar.occurrences(day(2026, 6, 8), day(2026, 6, 8)).is_empty());
+    assert_eq!(calendar.occurrences(day(2026, 6, 2), day(2026, 6, 2))[&day(2026, 6, 2)], vec![0]);
+    assert_eq!(calendar.occurrences(day(2026, 6, 9), day(2026, 6, 9))[&day(2026, 6, 9)], vec![1, 0]);
+
+    let starts: Vec<_> = calendar
+        .timed_occurrences(day(2026, 6, 9), day(2026, 6, 9))
+        .into_iter()
+        .map(|(_, start, _)| start)
+        .collect();
+    assert_eq!(starts.len(), 2);
+    assert_eq!(starts[0], starts[1]);
+}
+
+#[test]
+fn secondary_zone_follows_one_sided_dst_changes() {
+    // Los Angeles moves its clocks forward at 10:00 UTC on March 8, 2026,
+    // after 10:00 in Berlin, which only does on March 29
+    let in_los_angeles = |date| zone::convert(Tz::Europe__Berlin, date, time(10, 0), Tz::America__Los_Angeles);
+
+    let week: Vec<NaiveTime> = day(2026, 3, 5).iter_days().take(7).map(in_los_angeles).collect();
+    let hour = |hour| time(hour, 0);
+
+    assert_eq!(week, vec![hour(1), hour(1), hour(1), hour(1), hour(2), hour(2), hour(2)]);
+}
+
+#[test]
+fn search_matches_every_field_by_date() {
+    let mut calendar = Calendar::default();
+    let mut dentist = event("Dentist", day(2026, 4, 2));
+    dentist.location = "Main street".to_string();
+    calendar.add(dentist);
+    calendar.add(event("Street food", day(2026, 1, 5)));
+    calendar.add(event("Gym", day(2026, 2, 1)));
+
+    let titles: Vec<&str> = calendar
+        .search("STREET")
+        .into_iter()
+        .map(|i| calendar.events()[i].title.as_str())
+        .collect();
+
+    assert_eq!(titles, vec!["Street food", "Dentist"]);
+}
+
+#[test]
+fn ics_export_escapes_and_folds() {
+    let mut calendar = Calendar::default();
+    let mut event = event("Lunch; with Anna, Ben", day(2026, 7, 14));
+    event.description = "a".repeat(100);
+    calendar.add(event);
+
+    let document = ics::export(calendar.events(), &[]);
+
+    assert!(document.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(document.ends_with("END:VCALENDAR\r\n"));
+    assert!(document.contains("SUMMARY:Lunch\\; with Anna\\, Ben\r\n"));
+    assert!(document.contains("UID:event-1-20260714@date_picker\r\n"));
+    assert!(document.contains("DTSTART;VALUE=DATE:20260714\r\n"));
+    assert!(document.split("\r\n").all(|line| line.len() <= 75));
+}
+
+#[test]
+fn ics_feed_is_named_and_readable() {
+    let mut calendar = Calendar::default();
+    calendar.add(event("Standup", day(2026, 7, 14)));
+
+    let document = ics::feed("Work, team", calendar.events(), 15);
+
+    assert!(document.contains("X-WR-CALNAME:Work\\, team\r\n"));
+    assert!(document.contains("REFRESH-INTERVAL;VALUE=DURATION:PT15M\r\n"));
+
+    let imported = ics::parse(&document).unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].event.title, "Standup");
+}
+
+#[test]
+fn ics_resource_round_trips() {
+    let mut event = Event::new(
+        "Giỗ ông, at home\nbring rice".to_string(),
+        day(2026, 3, 18),
+        Some(EventTime {
+            start: time(22, 0),
+            end: time(1, 30),
+            zone: Tz::Asia__Ho_Chi_Minh,
+            reminder: Some(45),
+        }),
+        Some(Recurrence::lunar_yearly(day(2026, 3, 18))),
+        Category::Reminder,
+    );
+    event.location = "Huế".to_string();
+    event.notes = "x".repeat(120);
+
+    let imported = ics::parse(&ics::resource(&event, "giỗ@example.com")).unwrap();
+
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].uid, "giỗ@example.com");
+    assert_eq!(imported[0].event, event);
+}
+
+#[test]
+fn ics_repeat_rules_round_trip() {
+    let mut event = event("Standup", day(2026, 10, 19));
+    event.time = Some(EventTime {
+        start: time(9, 0),
+        end: time(9, 15),
+        zone: Tz::Asia__Ho_Chi_Minh,
+        reminder: None,
+    });
+    event.recurrence = Some(Recurrence::Weekdays { until: Some(day(2026, 12, 18)) });
+
+    let resource = ics::resource(&event, "standup");
+    assert!(resource.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;UNTIL=20261218T020000Z\r\n"));
+    assert_eq!(ics::parse(&resource).unwrap()[0].event, event);
+
+    let document = ics::export(&[event], &[]);
+    assert_eq!(document.matches("BEGIN:VEVENT").count(), 1);
+
+    let weekly = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261020\r\n\
+        RRULE:FREQ=WEEKLY;BYDAY=TU\r\nSUMMARY:Class\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261020\r\nRRULE:FREQ=MONTHLY\r\nSUMMARY:Rent\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let imported = ics::parse(weekly).unwrap();
+
+    assert_eq!(imported[0].event.recurrence, Some(Recurrence::Weekly { until: None }));
+    assert_eq!(imported[1].event.recurrence, None);
+}
+
+#[test]
+fn ics_parse_reads_other_programs() {
+    let document = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\nEND:VTIMEZONE\r\n\
+        BEGIN:VEVENT\r\nUID:a\r\nDTSTART;TZID=\"Europe/Berlin\":20260601T090000\r\n\
+        DURATION:PT30M\r\nSUMMARY:Stand\r\n  up\r\nBEGIN:VALARM\r\nTRIGGER:-PT10M\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:b\r\nDTSTART;VALUE=DATE:20261224\r\nSUMMARY:Eve\r\nCATEGORIES:WORK,HOLIDAY\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    let imported = ics::parse(document).unwrap();
+
+    let time = imported[0].event.time.unwrap();
+    assert_eq!(imported[0].event.title, "Stand up");
+    assert_eq!((time.start, time.end), (NaiveTime::from_hms_opt(9, 0, 0).unwrap(), NaiveTime::from_hms_opt(9, 30, 0).unwrap()));
+    assert_eq!((time.zone, time.reminder), (Tz::Europe__Berlin, Some(10)));
+
+    assert_eq!(imported[1].event.time, None);
+    assert_eq!(imported[1].event.category, Category::Holiday);
+
+    let error = ics::parse("BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:no start\nEND:VEVENT\nEND:VCALENDAR\n").unwrap_err();
+    assert_eq!(error.line, 4);
+    assert!(ics::parse("BEGIN:VCALENDAR\nnonsense\n").is_err());
+}
+
+#[test]
+fn free_slots_fit_between_events() {
+    let mut calendar = Calendar::default();
+    calendar.add(timed("Standup", day(2026, 10, 19), time(9, 0), time(9, 20), 1));
+    calendar.add(timed("Review", day(2026, 10, 19), time(10, 30), time(12, 0), 1));
+    calendar.add(timed("Gym", day(2026, 10, 19), time(13, 0), time(14, 0), 2));
+    calendar.add(timed("Late call", day(2026, 10, 19), time(23, 0), time(10, 0), 1));
+    calendar.add(event("Leave", day(2026, 10, 21)));
+
+    let search = SlotSearch {
+        from: day(2026, 10, 19),
+        to: day(2026, 10, 21),
+        minutes: 60,
+        day_start: time(9, 0),
+        day_end: time(15, 0),
+        weekdays_only: true,
+        days_off: vec![day(2026, 10, 21)],
+        calendars: vec![1],
+        not_before: Some(day(2026, 10, 19).and_time(time(9, 5))),
+    };
+
+    let slot = |date: NaiveDate, start: NaiveTime, end: NaiveTime| Slot { date, start, end };
+
+    assert_eq!(
+        free_time::find(&calendar, &search),
+        vec![
+            slot(day(2026, 10, 19), time(9, 30), time(10, 30)),
+            slot(day(2026, 10, 19), time(12, 0), time(13, 0)),
+            slot(day(2026, 10, 19), time(13, 0), time(14, 0)),
+            slot(day(2026, 10, 19), time(14, 0), time(15, 0)),
+            slot(day(2026, 10, 20), time(10, 0), time(11, 0)),
+            slot(day(2026, 10, 20), time(11, 0), time(12, 0)),
+            slot(day(2026, 10, 20), time(12, 0), time(13, 0)),
+            slot(day(2026, 10, 20), time(13, 0), time(14, 0)),
+            slot(day(2026, 10, 20), time(14, 0), time(15, 0)),
+        ]
+    );
+}
+
+#[test]
+fn overlapping_events_conflict() {
+    let mut calendar = Calendar::default();
+    calendar.add(timed("Standup", day(2026, 10, 19), time(9, 0), time(9, 30), 1));
+    calendar.add(timed("Review", day(2026, 10, 19), time(9, 15), time(10, 0), 1));
+    calendar.add(timed("Gym", day(2026, 10, 19), time(9, 0), time(10, 0), 2));
+    calendar.add(timed("Lunch", day(2026, 10, 19), time(10, 0), time(11, 0), 1));
+    calendar.add(timed("Late call", day(2026, 10, 19), time(23, 0), time(1, 0), 1));
+    calendar.add(timed("Early call", day(2026, 10, 20), time(0, 30), time(1, 30), 1));
+    calendar.add(event("Leave", day(2026, 10, 19)));
+
+    assert_eq!(
+        conflicts::between(&calendar, day(2026, 10, 19), day(2026, 10, 20)),
+        vec![
+            Conflict { date: day(2026, 10, 19), first: 0, second: 1 },
+            Conflict { date: day(2026, 10, 20), first: 4, second: 5 },
+        ]
+    );
+    assert_eq!(
+        conflicts::between(&calendar, day(2026, 10, 20), day(2026, 10, 20)),
+        vec![Conflict { date: day(2026, 10, 20), first: 4, second: 5 }]
+    );
+
+    let moved = timed("Moved", day(2026, 10, 19), time(9, 45), time(10, 15), 1);
+    assert_eq!(conflicts::overlapping(&calendar, &moved, None), vec![1, 3]);
+    assert_eq!(conflicts::overlapping(&calendar, &calendar.events()[1], Some(1)), vec![0]);
+    assert_eq!(conflicts::overlapping(&calendar, &calendar.events()[6], Some(6)), Vec::<usize>::new());
+}
@@ -0,0 +1,28 @@
+//! Helpers shared by the tests, each test file using some of them.
+
+#![allow(dead_code)]
+
+use calendar_core::{Category, Event, EventTime};
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+pub fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+pub fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// All-day event, not added to a calendar yet
+pub fn event(title: &str, date: NaiveDate) -> Event {
+    Event::new(title.to_string(), date, None, None, Category::Todo)
+}
+
+/// Event from `start` to `end` in UTC, filed in `calendar`
+pub fn timed(title: &str, date: NaiveDate, start: NaiveTime, end: NaiveTime, calendar: u64) -> Event {
+    let mut event = event(title, date);
+    event.time = Some(EventTime { start, end, zone: Tz::UTC, reminder: None });
+    event.calendar = calendar;
+    event
+}
@@ -0,0 +1,94 @@
+use calendar_core::{
+    date, holidays,
+    holidays::Country,
+    lunar::{self, LunarDate},
+    Recurrence,
+};
+use chrono::Weekday;
+
+mod common;
+
+use common::day;
+
+#[test]
+fn week_layout() {
+    assert_eq!(date::days_since(Weekday::Sun, Weekday::Sun), 0);
+    assert_eq!(date::days_since(Weekday::Sun, Weekday::Mon), 6);
+    assert_eq!(date::days_since(Weekday::Wed, Weekday::Mon), 2);
+}
+
+#[test]
+fn lunar_new_year() {
+    assert_eq!(
+        lunar::from_solar(day(2026, 2, 17), lunar::VIETNAM),
+        LunarDate { year: 2026, month: 1, day: 1, leap: false }
+    );
+    assert_eq!(
+        lunar::to_solar(LunarDate { year: 2025, month: 1, day: 1, leap: false }, lunar::VIETNAM),
+        Some(day(2025, 1, 29))
+    );
+}
+
+#[test]
+fn lunar_round_trip() {
+    for date in day(2020, 1, 1).iter_days().take(3 * 366) {
+        let lunar = lunar::from_solar(date, lunar::VIETNAM);
+
+        assert_eq!(lunar::to_solar(lunar, lunar::VIETNAM), Some(date), "{lunar}");
+    }
+}
+
+#[test]
+fn lunar_yearly_recurrence() {
+    let tet = day(2025, 1, 29);
+    let recurrence = Recurrence::lunar_yearly(tet);
+
+    assert_eq!(recurrence, Recurrence::LunarYearly { month: 1, day: 1 });
+    assert_eq!(
+        recurrence.occurrences(tet, day(2024, 1, 1), day(2027, 1, 1)),
+        vec![tet, day(2026, 2, 17)]
+    );
+}
+
+#[test]
+fn public_holidays() {
+    let year = |country| holidays::between(&[country], day(2026, 1, 1), day(2026, 12, 31));
+
+    let vietnam = year(Country::Vietnam);
+    assert!(vietnam.iter().any(|holiday| holiday.name == "Tết Nguyên Đán" && holiday.date == day(2026, 2, 17)));
+
+    let united_states = year(Country::UnitedStates);
+    assert!(united_states.iter().any(|holiday| holiday.name == "Thanksgiving" && holiday.date == day(2026, 11, 26)));
+    assert!(united_states.iter().any(|holiday| holiday.name == "Memorial Day" && holiday.date == day(2026, 5, 25)));
+
+    let germany = year(Country::Germany);
+    assert!(germany.iter().any(|holiday| holiday.name == "Karfreitag" && holiday.date == day(2026, 4, 3)));
+}
+
+#[test]
+fn country_codes() {
+    for country in Country::ALL {
+        assert_eq!(Country::from_code(country.code()), Some(country));
+    }
+
+    assert_eq!(Country::from_code(" vn"), Some(Country::Vietnam));
+    assert_eq!(Country::from_code("FR"), None);
+}
+
+#[test]
+fn weekly_recurrences() {
+    let monday = day(2026, 10, 19);
+
+    assert_eq!(
+        Recurrence::Weekdays { until: None }.occurrences(monday, day(2026, 10, 23), day(2026, 10, 27)),
+        vec![day(2026, 10, 23), day(2026, 10, 26), day(2026, 10, 27)]
+    );
+    assert_eq!(
+        Recurrence::Weekly { until: Some(day(2026, 11, 2)) }.occurrences(monday, monday, day(2026, 12, 31)),
+        vec![monday, day(2026, 10, 26), day(2026, 11, 2)]
+    );
+    assert_eq!(
+        Recurrence::Daily { until: Some(day(2026, 10, 20)) }.occurrences(monday, day(2026, 10, 1), day(2026, 10, 31)),
+        vec![monday, day(2026, 10, 20)]
+    );
+}
@@ -0,0 +1,62 @@
+use calendar_core::{merge::merge, Category, Event};
+
+mod common;
+
+use common::day;
+
+fn event(id: u64, title: &str) -> Event {
+    let mut event = Event::new(title.to_string(), day(2026, 5, 1), None, None, Category::Todo);
+    event.id = id;
+    event
+}
+
+fn titles(events: &[Event]) -> Vec<(u64, &str)> {
+    let mut titles: Vec<(u64, &str)> = events.iter().map(|event| (event.id, event.title.as_str())).collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn changes_on_each_side_are_kept() {
+    let base = vec![event(1, "Gym"), event(2, "Dentist"), event(3, "Call mom")];
+    let mine = vec![event(1, "Gym at 7"), event(2, "Dentist"), event(4, "Groceries")];
+    let theirs = vec![event(1, "Gym"), event(2, "Dentist (moved)"), event(3, "Call mom"), event(5, "Rent")];
+
+    assert_eq!(
+        titles(&merge(&base, &mine, &theirs)),
+        vec![(1, "Gym at 7"), (2, "Dentist (moved)"), (4, "Groceries"), (5, "Rent")]
+    );
+}
+
+#[test]
+fn local_version_wins_when_both_changed() {
+    let base = vec![event(1, "Gym")];
+    let mine = vec![event(1, "Gym at 7")];
+    let theirs = vec![event(1, "Gym at 8")];
+
+    assert_eq!(titles(&merge(&base, &mine, &theirs)), vec![(1, "Gym at 7")]);
+}
+
+#[test]
+fn changed_events_survive_deletion_elsewhere() {
+    let base = vec![event(1, "Gym"), event(2, "Dentist")];
+    let mine = vec![event(1, "Gym at 7")];
+    let theirs = vec![event(2, "Dentist (moved)")];
+
+    assert_eq!(
+        titles(&merge(&base, &mine, &theirs)),
+        vec![(1, "Gym at 7"), (2, "Dentist (moved)")]
+    );
+}
+
+#[test]
+fn events_added_on_both_sides_get_distinct_ids() {
+    let base = vec![event(1, "Gym")];
+    let mine = vec![event(1, "Gym"), event(2, "Groceries")];
+    let theirs = vec![event(1, "Gym"), event(2, "Rent")];
+
+    assert_eq!(
+        titles(&merge(&base, &mine, &theirs)),
+        vec![(1, "Gym"), (2, "Rent"), (3, "Groceries")]
+    );
+}
@@ -0,0 +1,74 @@
+use calendar_core::{quick_add, Recurrence};
+use chrono::NaiveTime;
+
+mod common;
+
+use common::{day, time};
+
+fn at(hour: u32, minute: u32) -> Option<NaiveTime> {
+    Some(time(hour, minute))
+}
+
+// A Sunday
+const TODAY: (i32, u32, u32) = (2026, 10, 18);
+
+fn parse(phrase: &str) -> quick_add::QuickAdd {
+    quick_add::parse(phrase, day(TODAY.0, TODAY.1, TODAY.2))
+}
+
+#[test]
+fn english_phrases() {
+    let lunch = parse("lunch with An tomorrow 12:30");
+    assert_eq!(lunch.title, "lunch with An");
+    assert_eq!((lunch.date, lunch.start, lunch.end), (Some(day(2026, 10, 19)), at(12, 30), None));
+
+    let standup = parse("standup every weekday 9am");
+    assert_eq!(standup.title, "standup");
+    assert_eq!(standup.date, Some(day(2026, 10, 19)));
+    assert_eq!(standup.start, at(9, 0));
+    assert_eq!(standup.recurrence, Some(Recurrence::Weekdays { until: None }));
+
+    let tet = parse("Tết holiday Feb 17-20");
+    assert_eq!(tet.title, "Tết holiday");
+    assert_eq!(tet.date, Some(day(2027, 2, 17)));
+    assert_eq!(tet.recurrence, Some(Recurrence::Daily { until: Some(day(2027, 2, 20)) }));
+
+    let review = parse("follow-up review next friday from 11-1pm");
+    assert_eq!(review.title, "follow-up review");
+    assert_eq!((review.date, review.start, review.end), (Some(day(2026, 10, 23)), at(11, 0), at(13, 0)));
+
+    let gym = parse("gym every monday at 6 until Dec 31");
+    assert_eq!((gym.title.as_str(), gym.date, gym.start), ("gym", Some(day(2026, 10, 19)), at(6, 0)));
+    assert_eq!(gym.recurrence, Some(Recurrence::Weekly { until: Some(day(2026, 12, 31)) }));
+}
+
+#[test]
+fn vietnamese_phrases() {
+    let meeting = parse("Họp nhóm thứ hai hằng tuần lúc 9h sáng");
+    assert_eq!(meeting.title, "Họp nhóm");
+    assert_eq!((meeting.date, meeting.start), (Some(day(2026, 10, 19)), at(9, 0)));
+    assert_eq!(meeting.recurrence, Some(Recurrence::Weekly { until: None }));
+
+    let dinner = parse("ăn tối với Mai 7 giờ rưỡi tối nay");
+    assert_eq!(dinner.title, "ăn tối với Mai");
+    assert_eq!((dinner.date, dinner.start), (Some(day(2026, 10, 18)), at(19, 30)));
+
+    let tet = parse("Nghỉ Tết từ 17/2 đến 20/2");
+    assert_eq!(tet.title, "Nghỉ Tết");
+    assert_eq!(tet.recurrence, Some(Recurrence::Daily { until: Some(day(2027, 2, 20)) }));
+
+    let class = parse("hoc tieng Anh thu 3 tuan sau 14h-15h30");
+    assert_eq!(class.title, "hoc tieng Anh");
+    assert_eq!((class.date, class.start, class.end), (Some(day(2026, 10, 20)), at(14, 0), at(15, 30)));
+
+    assert_eq!(parse("giỗ ông ngày 5 tháng 3 năm 2027").date, Some(day(2027, 3, 5)));
+    assert_eq!(parse("tập thể dục mỗi ngày 6h").recurrence, Some(Recurrence::Daily { until: None }));
+}
+
+#[test]
+fn plain_titles_stay_whole() {
+    let plain = parse("Call Mai about the 3 tickets");
+
+    assert_eq!(plain.title, "Call Mai about the 3 tickets");
+    assert!(!plain.is_planned());
+}
@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+
+use calendar_core::{
+    calendars::{self, NamedCalendar, Rgb},
+    storage::{self, LoadError, SaveError},
+    Category, Event, SavedState,
+};
+
+mod common;
+
+use common::day;
+
+/// Empty directory of its own for each test
+fn temporary_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("calendar_core-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+#[test]
+fn save_and_load() {
+    let path = temporary_dir("round-trip").join("nested").join("calendar.json");
+    let mut event = Event::new(
+        "Dentist".to_string(),
+        day(2026, 4, 2),
+        None,
+        None,
+        Category::Reminder,
+    );
+    event.id = 7;
+    event.calendar = 2;
+
+    let mut work = NamedCalendar::new(2, "Work".to_string(), Rgb(0xdc, 0x26, 0x26));
+    work.visible = false;
+
+    let state = SavedState {
+        events: vec![event],
+        show_lunar: true,
+        calendars: vec![NamedCalendar::new(1, "Personal".to_string(), calendars::COLORS[0]), work],
+        ..SavedState::default()
+    };
+    state.save(&path).unwrap();
+
+    let loaded = SavedState::load(&path).unwrap();
+    assert_eq!(loaded.events, state.events);
+    assert_eq!(loaded.calendars, state.calendars);
+    assert!(loaded.show_lunar);
+    assert!(!path.with_file_name("calendar.json.tmp").exists());
+}
+
+#[test]
+fn files_without_ids_are_upgraded() {
+    let path = temporary_dir("legacy").join("calendar.json");
+    std::fs::write(
+        &path,
+        r#"{"events": [
+            {"title": "A", "date": "2026-01-01"},
+            {"title": "B", "date": "2026-01-02", "id": 5},
+            {"title": "C", "date": "2026-01-03"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let ids: Vec<u64> = SavedState::load(&path).unwrap().events.iter().map(|event| event.id).collect();
+
+    assert_eq!(ids, vec![6, 5, 7]);
+}
+
+#[test]
+fn events_are_filed_in_known_calendars() {
+    let path = temporary_dir("calendars").join("calendar.json");
+    std::fs::write(&path, r#"{"events": [{"title": "A", "date": "2026-01-01", "id": 1}]}"#).unwrap();
+
+    let loaded = SavedState::load(&path).unwrap();
+    assert_eq!(loaded.calendars, calendars::defaults());
+    assert_eq!(loaded.events[0].calendar, loaded.calendars[0].id);
+
+    std::fs::write(
+        &path,
+        r##"{
+            "events": [
+                {"title": "A", "date": "2026-01-01", "id": 1, "calendar": 4},
+                {"title": "B", "date": "2026-01-02", "id": 2, "calendar": 9}
+            ],
+            "calendars": [
+                {"id": 3, "name": "Team", "color": "#16A34A"},
+                {"id": 4, "name": "Work", "color": "#dc2626", "visible": false}
+            ]
+        }"##,
+    )
+    .unwrap();
+
+    let loaded = SavedState::load(&path).unwrap();
+    let filed: Vec<u64> = loaded.events.iter().map(|event| event.calendar).collect();
+    assert_eq!(filed, vec![4, 3]);
+    assert_eq!(loaded.calendars[0].color, Rgb(0x16, 0xa3, 0x4a));
+    assert!(loaded.calendars[0].visible);
+    assert!(!calendars::is_visible(&loaded.calendars, 4));
+
+    let error = serde_json::from_str::<NamedCalendar>(r#"{"id": 1, "name": "X", "color": "blue"}"#).unwrap_err();
+    assert!(error.to_string().contains("not a #rrggbb color"), "{error}");
+}
+
+#[test]
+fn load_errors() {
+    let dir = temporary_dir("errors");
+
+    let missing = SavedState::load(&dir.join("missing.json")).unwrap_err();
+    assert!(missing.is_missing());
+
+    let path = dir.join("broken.json");
+    std::fs::write(&path, "{\n  \"events\": [\n    {\"title\": 12}\n  ]\n}").unwrap();
+
+    let error = SavedState::load(&path).unwrap_err();
+    assert!(matches!(error, LoadError::Format { line: 3, .. }), "{error:?}");
+    assert!(error.to_string().starts_with("invalid calendar file at line 3, column"), "{error}");
+}
+
+#[test]
+fn save_errors() {
+    let dir = temporary_dir("save-errors");
+    let blocker = dir.join("not-a-folder");
+    std::fs::write(&blocker, "").unwrap();
+
+    let error = SavedState::default().save(&blocker.join("calendar.json")).unwrap_err();
+    assert!(matches!(error, SaveError::File(_)), "{error:?}");
+}
+
+#[test]
+fn replace_keeps_changes_made_elsewhere() {
+    let path = temporary_dir("replace").join("calendar.json");
+    let state = |title: &str| {
+        let calendars = calendars::defaults();
+        let mut event = Event::new(title.to_string(), day(2026, 5, 1), None, None, Category::Todo);
+        event.id = 1;
+        event.calendar = calendars[0].id;
+
+        SavedState {
+            events: vec![event],
+            calendars,
+            ..SavedState::default()
+        }
+    };
+
+    state("Ours").replace(&path, &[]).unwrap();
+
+    let known = vec![state("Ours").events];
+    state("Theirs").save(&path).unwrap();
+
+    let error = state("Ours again").replace(&path, &known).unwrap_err();
+    assert_eq!(error, SaveError::Changed);
+    assert_eq!(SavedState::load(&path).unwrap().events, state("Theirs").events);
+
+    state("Merged").replace(&path, &[state("Theirs").events]).unwrap();
+    assert_eq!(SavedState::load(&path).unwrap().events, state("Merged").events);
+}
+
+#[test]
+fn lock_is_exclusive() {
+    let path = temporary_dir("lock").join("calendar.json");
+    let lock = storage::lock(&path).unwrap();
+
+    let waiter = std::thread::spawn({
+        let path = path.clone();
+        move || {
+            let _lock = storage::lock(&path).unwrap();
+            std::time::Instant::now()
+        }
+    });
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let released = std::time::Instant::now();
+    drop(lock);
+
+    assert!(waiter.join().unwrap() >= released);
+}
@@ -0,0 +1,34 @@
+//! Font text is drawn with, embedded so that rendering needs nothing
+//! installed, and the widths text is wrapped by.
+
+/// DejaVu Sans, which has the letters of Vietnamese
+pub(crate) const SANS: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+
+/// Family name of `SANS`, which images ask for
+pub(crate) const FAMILY: &str = "DejaVu Sans";
+
+pub(crate) struct Font {
+    face: ttf_parser::Face<'static>,
+}
+
+impl Font {
+    pub(crate) fn load() -> Font {
+        Font {
+            face: ttf_parser::Face::parse(SANS, 0).expect("the embedded font is valid"),
+        }
+    }
+
+    /// Width of `text` at `size`, characters missing from the font counting
+    /// half a size
+    pub(crate) fn width(&self, text: &str, size: f32) -> f32 {
+        let units_per_em = f32::from(self.face.units_per_em());
+
+        text.chars()
+            .map(|c| match self.face.glyph_index(c).and_then(|glyph| self.face.glyph_hor_advance(glyph)) {
+                Some(advance) => f32::from(advance) / units_per_em,
+                None => 0.5,
+            })
+            .sum::<f32>()
+            * size
+    }
+}
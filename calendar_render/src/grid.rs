@@ -0,0 +1,62 @@
+//! What a grid of days shows, already worded and colored.
+
+use calendar_core::calendars::Rgb;
+
+/// Days in columns under a title: a month as six weeks, a week as one row
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grid {
+    pub title: String,
+    /// Heading of each column, such as the names of the weekdays
+    pub columns: Vec<String>,
+    /// Cells row by row, none for the days around the range shown
+    pub rows: Vec<Vec<Option<Cell>>>,
+}
+
+/// One day
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cell {
+    /// Day of the month
+    pub label: String,
+    /// Shown across from the label, such as the lunar date
+    pub note: String,
+    /// The label is drawn in the accent color, as today is
+    pub emphasized: bool,
+    pub entries: Vec<Entry>,
+}
+
+/// Event or holiday of a day, wrapped to the width of the cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub text: String,
+    pub color: Rgb,
+    /// Dot drawn before the text, such as the color of the calendar the
+    /// event is filed in
+    pub marker: Option<Rgb>,
+}
+
+/// Colors of the grid itself, entries having their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colors {
+    pub background: Rgb,
+    pub text: Rgb,
+    /// Column headings and day labels
+    pub muted: Rgb,
+    /// Cell borders
+    pub border: Rgb,
+    pub accent: Rgb,
+    pub note: Rgb,
+}
+
+impl Default for Colors {
+    /// The light theme of the window
+    fn default() -> Self {
+        Colors {
+            background: Rgb(255, 255, 255),
+            text: Rgb(0, 0, 0),
+            muted: Rgb(153, 153, 153),
+            border: Rgb(0, 0, 0),
+            accent: Rgb(44, 138, 252),
+            note: Rgb(204, 77, 77),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Grids placed on a page, as rectangles and lines of text that every
+//! renderer draws the same way.
+
+use calendar_core::calendars::Rgb;
+
+use crate::{
+    font::Font,
+    grid::{Cell, Colors, Grid},
+};
+
+/// Sizes, in points on a page as small as A4 across; larger pages scale
+/// them up
+const SHORT_SIDE: f32 = 595.0;
+const MARGIN: f32 = 24.0;
+const PADDING: f32 = 4.0;
+const TITLE_SIZE: f32 = 18.0;
+const HEADING_SIZE: f32 = 10.0;
+const LABEL_SIZE: f32 = 10.0;
+const ENTRY_SIZE: f32 = 8.0;
+const LINE_HEIGHT: f32 = 1.25;
+const MARKER: &str = "● ";
+const ELLIPSIS: &str = "…";
+
+/// What is drawn on a page, in points from its top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    pub width: f32,
+    pub height: f32,
+    /// In drawing order
+    pub shapes: Vec<Shape>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        fill: Option<Rgb>,
+        stroke: Option<Rgb>,
+    },
+    /// One line of text, `y` being its baseline
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        color: Rgb,
+        text: String,
+    },
+}
+
+/// `grid` on a page of `width` by `height` points: the title, the column
+/// headings, then rows of equal height. Entries are wrapped to the width of
+/// their cell, and the last line that fits ends with an ellipsis when the
+/// others do not.
+pub fn lay_out(grid: &Grid, colors: &Colors, width: f32, height: f32) -> Sheet {
+    let font = Font::load();
+    let scale = width.min(height) / SHORT_SIDE;
+    let margin = MARGIN * scale;
+
+    let mut shapes = vec![Shape::Rect {
+        x: 0.0,
+        y: 0.0,
+        width,
+        height,
+        fill: Some(colors.background),
+        stroke: None,
+    }];
+
+    let title_size = TITLE_SIZE * scale;
+    shapes.push(Shape::Text {
+        x: (width - font.width(&grid.title, title_size)) / 2.0,
+        y: margin + title_size,
+        size: title_size,
+        color: colors.text,
+        text: grid.title.clone(),
+    });
+
+    let columns = grid.rows.iter().map(Vec::len).chain([grid.columns.len()]).max().unwrap_or_default().max(1);
+    let column_width = (width - 2.0 * margin) / columns as f32;
+
+    let heading_size = HEADING_SIZE * scale;
+    let headings_top = margin + title_size * 2.0;
+
+    for (column, heading) in grid.columns.iter().enumerate() {
+        let left = margin + column as f32 * column_width;
+
+        shapes.push(Shape::Text {
+            x: left + (column_width - font.width(heading, heading_size)) / 2.0,
+            y: headings_top + heading_size,
+            size: heading_size,
+            color: colors.muted,
+            text: heading.clone(),
+        });
+    }
+
+    let rows_top = headings_top + heading_size * 2.0;
+    let row_height = (height - margin - rows_top) / grid.rows.len().max(1) as f32;
+
+    for (row, cells) in grid.rows.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let (x, y) = (margin + column as f32 * column_width, rows_top + row as f32 * row_height);
+
+            shapes.push(Shape::Rect {
+                x,
+                y,
+                width: column_width,
+                height: row_height,
+                fill: Some(colors.background),
+                stroke: Some(colors.border),
+            });
+
+            if let Some(cell) = cell {
+                lay_out_cell(&mut shapes, &font, cell, colors, scale, (x, y, column_width, row_height));
+            }
+        }
+    }
+
+    Sheet { width, height, shapes }
+}
+
+/// Label, note and entries of `cell`, within `bounds`
+fn lay_out_cell(shapes: &mut Vec<Shape>, font: &Font, cell: &Cell, colors: &Colors, scale: f32, bounds: (f32, f32, f32, f32)) {
+    let (x, y, width, height) = bounds;
+    let padding = PADDING * scale;
+    let inner = width - 2.0 * padding;
+    let bottom = y + height - padding;
+
+    let label_size = LABEL_SIZE * scale;
+    let entry_size = ENTRY_SIZE * scale;
+    let line = entry_size * LINE_HEIGHT;
+
+    shapes.push(Shape::Text {
+        x: x + padding,
+        y: y + padding + label_size,
+        size: label_size,
+        color: if cell.emphasized { colors.accent } else { colors.muted },
+        text: cell.label.clone(),
+    });
+
+    if !cell.note.is_empty() {
+        shapes.push(Shape::Text {
+            x: x + width - padding - font.width(&cell.note, entry_size),
+            y: y + padding + label_size,
+            size: entry_size,
+            color: colors.note,
+            text: cell.note.clone(),
+        });
+    }
+
+    let mut baseline = y + padding + label_size * LINE_HEIGHT + line;
+    // Position and indent of the last line placed, which takes the ellipsis
+    let mut last = None;
+
+    for entry in &cell.entries {
+        let indent = entry.marker.map_or(0.0, |_| font.width(MARKER, entry_size));
+
+        for (i, text) in wrap(font, &entry.text, entry_size, inner - indent).into_iter().enumerate() {
+            if baseline > bottom {
+                if let Some((last, indent)) = last {
+                    if let Some(Shape::Text { text, .. }) = shapes.get_mut(last) {
+                        *text = ellipsize(font, text, entry_size, inner - indent);
+                    }
+                }
+
+                return;
+            }
+
+            if let (0, Some(marker)) = (i, entry.marker) {
+                shapes.push(Shape::Text {
+                    x: x + padding,
+                    y: baseline,
+                    size: entry_size,
+                    color: marker,
+                    text: MARKER.trim_end().to_string(),
+                });
+            }
+
+            shapes.push(Shape::Text {
+                x: x + padding + indent,
+                y: baseline,
+                size: entry_size,
+                color: entry.color,
+                text,
+            });
+
+            last = Some((shapes.len() - 1, indent));
+            baseline += line;
+        }
+    }
+}
+
+/// Lines of at most `width` breaking `text` between words, and within words
+/// too long for a line of their own
+fn wrap(font: &Font, text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let joined = if current.is_empty() { word.to_string() } else { format!("{current} {word}") };
+
+        if font.width(&joined, size) <= width {
+            current = joined;
+            continue;
+        }
+
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+
+        for c in word.chars() {
+            if !current.is_empty() && font.width(&format!("{current}{c}"), size) > width {
+                lines.push(std::mem::take(&mut current));
+            }
+
+            current.push(c);
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// `text` ending with an ellipsis, shortened to fit `width`
+fn ellipsize(font: &Font, text: &str, size: f32, width: f32) -> String {
+    let mut kept: String = text.to_string();
+
+    while !kept.is_empty() && font.width(&format!("{kept}{ELLIPSIS}"), size) > width {
+        kept.pop();
+    }
+
+    format!("{}{ELLIPSIS}", kept.trim_end())
+}
@@ -0,0 +1,17 @@
+//! Month and week grids rendered without a window or a GPU, as PDF pages to
+//! print and pin on the wall, and as SVG and PNG images to embed in wiki
+//! pages and reports.
+//!
+//! The caller words and colors a `Grid` the way its window shows it,
+//! `layout` places it on a page of the size asked for, and the renderers
+//! draw what was placed.
+
+mod font;
+pub mod grid;
+pub mod layout;
+pub mod pdf;
+pub mod png;
+pub mod svg;
+
+pub use grid::{Cell, Colors, Entry, Grid};
+pub use pdf::{Orientation, PageSize};
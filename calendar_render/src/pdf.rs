@@ -0,0 +1,115 @@
+//! Grids printed on a PDF page.
+
+use std::io::Cursor;
+
+use calendar_core::calendars::Rgb;
+use printpdf::{path::PaintMode, Mm, PdfDocument, Pt, Rect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    font,
+    grid::{Colors, Grid},
+    layout::{self, Shape},
+};
+
+/// Width of the cell borders, in points
+const BORDER: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PageSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+}
+
+impl PageSize {
+    pub const ALL: [PageSize; 3] = [PageSize::A4, PageSize::A3, PageSize::Letter];
+
+    /// Width and height in millimeters, upright
+    fn millimeters(self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::A3 => (297.0, 420.0),
+            PageSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+impl std::fmt::Display for PageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PageSize::A4 => "A4",
+            PageSize::A3 => "A3",
+            PageSize::Letter => "Letter",
+        })
+    }
+}
+
+impl std::str::FromStr for PageSize {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        PageSize::ALL
+            .into_iter()
+            .find(|size| size.to_string().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown page size `{name}`, expected a4, a3 or letter"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Orientation {
+    Portrait,
+    /// Months are wider than tall
+    #[default]
+    Landscape,
+}
+
+/// PDF document of `grid` on one page of `size`
+pub fn render(grid: &Grid, colors: &Colors, size: PageSize, orientation: Orientation) -> Result<Vec<u8>, printpdf::Error> {
+    let (short, long) = size.millimeters();
+    let (width, height) = match orientation {
+        Orientation::Portrait => (Mm(short), Mm(long)),
+        Orientation::Landscape => (Mm(long), Mm(short)),
+    };
+
+    let sheet = layout::lay_out(grid, colors, Pt::from(width).0, Pt::from(height).0);
+
+    let (document, page, layer) = PdfDocument::new(grid.title.clone(), width, height, "Calendar");
+    let font = document.add_external_font(Cursor::new(font::SANS))?;
+    let layer = document.get_page(page).get_layer(layer);
+
+    // PDF measures from the bottom left corner
+    let x = |x: f32| Mm::from(Pt(x));
+    let y = |y: f32| Mm::from(Pt(sheet.height - y));
+
+    layer.set_outline_thickness(BORDER);
+
+    for shape in &sheet.shapes {
+        match shape {
+            Shape::Rect { x: left, y: top, width, height, fill, stroke } => {
+                let rect = Rect::new(x(*left), y(top + height), x(left + width), y(*top));
+
+                if let Some(fill) = fill {
+                    layer.set_fill_color(color(*fill));
+                    layer.add_rect(rect.with_mode(PaintMode::Fill));
+                }
+
+                if let Some(stroke) = stroke {
+                    layer.set_outline_color(color(*stroke));
+                    layer.add_rect(rect.with_mode(PaintMode::Stroke));
+                }
+            }
+            Shape::Text { x: left, y: baseline, size, color: text_color, text } => {
+                layer.set_fill_color(color(*text_color));
+                layer.use_text(text.clone(), *size, x(*left), y(*baseline), &font);
+            }
+        }
+    }
+
+    document.save_to_bytes()
+}
+
+fn color(Rgb(r, g, b): Rgb) -> printpdf::Color {
+    printpdf::Color::Rgb(printpdf::Rgb::new(f32::from(r) / 255.0, f32::from(g) / 255.0, f32::from(b) / 255.0, None))
+}
@@ -0,0 +1,27 @@
+//! Grids rasterized to PNG images by a software renderer, drawn as their
+//! SVG image is.
+
+use resvg::{tiny_skia, usvg};
+
+use crate::{
+    font::{FAMILY, SANS},
+    grid::{Colors, Grid},
+    svg,
+};
+
+/// PNG image of `grid`, `width` by `height` pixels, or why it could not be
+/// drawn
+pub fn render(grid: &Grid, colors: &Colors, width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut options = usvg::Options {
+        font_family: FAMILY.to_string(),
+        ..Default::default()
+    };
+    options.fontdb_mut().load_font_data(SANS.to_vec());
+
+    let tree = usvg::Tree::from_str(&svg::render(grid, colors, width, height), &options).map_err(|error| error.to_string())?;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| format!("cannot draw an image of {width}×{height}"))?;
+
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(|error| error.to_string())
+}
@@ -0,0 +1,58 @@
+//! Grids drawn as SVG images.
+
+use std::fmt::Write;
+
+use calendar_core::calendars::Rgb;
+
+use crate::{
+    font::FAMILY,
+    grid::{Colors, Grid},
+    layout::{self, Shape},
+};
+
+/// Width of the cell borders and radius of their corners, as the cells of
+/// the window have them
+const BORDER: f32 = 1.0;
+const RADIUS: f32 = 1.0;
+
+/// SVG image of `grid`, `width` by `height` pixels
+pub fn render(grid: &Grid, colors: &Colors, width: u32, height: u32) -> String {
+    let sheet = layout::lay_out(grid, colors, width as f32, height as f32);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"{FAMILY}\">\n"
+    );
+
+    for shape in &sheet.shapes {
+        // Writing to a string cannot fail
+        let _ = match shape {
+            Shape::Rect { x, y, width, height, fill, stroke } => writeln!(
+                svg,
+                "<rect x=\"{x:.2}\" y=\"{y:.2}\" width=\"{width:.2}\" height=\"{height:.2}\" rx=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{BORDER}\"/>",
+                if stroke.is_some() { RADIUS } else { 0.0 },
+                paint(*fill),
+                paint(*stroke),
+            ),
+            Shape::Text { x, y, size, color, text } => writeln!(
+                svg,
+                "<text x=\"{x:.2}\" y=\"{y:.2}\" font-size=\"{size:.2}\" fill=\"{}\">{}</text>",
+                paint(Some(*color)),
+                escape(text),
+            ),
+        };
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn paint(color: Option<Rgb>) -> String {
+    match color {
+        Some(Rgb(r, g, b)) => format!("#{r:02x}{g:02x}{b:02x}"),
+        None => "none".to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
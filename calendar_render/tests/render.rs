@@ -0,0 +1,97 @@
+use calendar_core::calendars::Rgb;
+use calendar_render::{
+    layout::{self, Shape},
+    pdf, png, svg, Cell, Colors, Entry, Grid, Orientation, PageSize,
+};
+
+fn entry(text: &str) -> Entry {
+    Entry {
+        text: text.to_string(),
+        color: Rgb(51, 51, 51),
+        marker: Some(Rgb(0x3b, 0x82, 0xf6)),
+    }
+}
+
+fn week(entries: Vec<Entry>) -> Grid {
+    let day = |label: &str, entries: Vec<Entry>| {
+        Some(Cell {
+            label: label.to_string(),
+            note: "5/9".to_string(),
+            emphasized: false,
+            entries,
+        })
+    };
+
+    Grid {
+        title: "Tháng Mười 2026".to_string(),
+        columns: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].map(String::from).to_vec(),
+        rows: vec![vec![day("19", entries), day("20", Vec::new()), None, None, None, None, None]],
+    }
+}
+
+fn lines(sheet: &layout::Sheet) -> Vec<(f32, f32, &str)> {
+    sheet
+        .shapes
+        .iter()
+        .filter_map(|shape| match shape {
+            Shape::Text { x, y, text, .. } => Some((*x, *y, text.as_str())),
+            Shape::Rect { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn entries_wrap_within_their_cell() {
+    let grid = week(vec![entry("09:00 Họp nhóm về kế hoạch quý bốn với khách hàng ở Đà Nẵng"), entry("Ăn trưa")]);
+    let sheet = layout::lay_out(&grid, &Colors::default(), 842.0, 595.0);
+
+    let (left, right) = (24.0, 24.0 + (842.0 - 48.0) / 7.0);
+    let texts = lines(&sheet);
+
+    let first = texts.iter().position(|(_, _, text)| text.starts_with("09:00")).unwrap();
+    let wrapped: Vec<&str> = texts[first..].iter().map(|(_, _, text)| *text).take_while(|text| *text != "●").collect();
+
+    assert!(wrapped.len() > 1);
+    assert_eq!(wrapped.join(" "), "09:00 Họp nhóm về kế hoạch quý bốn với khách hàng ở Đà Nẵng");
+    assert!(texts.iter().any(|(_, _, text)| *text == "Ăn trưa"));
+    assert!(texts[first..first + wrapped.len()].iter().all(|(x, _, _)| (left..right).contains(x)));
+}
+
+#[test]
+fn overflowing_entries_end_with_an_ellipsis() {
+    let grid = week((0..80).map(|i| entry(&format!("Event {i}"))).collect());
+    let sheet = layout::lay_out(&grid, &Colors::default(), 842.0, 595.0);
+
+    let texts = lines(&sheet);
+    let last = texts.iter().rfind(|(_, _, text)| text.starts_with("Event")).unwrap();
+
+    assert!(last.2.ends_with('…'));
+    assert!(last.1 < 595.0);
+    assert!(!texts.iter().any(|(_, _, text)| *text == "Event 79"));
+}
+
+#[test]
+fn pages_are_pdf() {
+    let pdf = pdf::render(&week(vec![entry("Tết")]), &Colors::default(), PageSize::A4, Orientation::Landscape).unwrap();
+
+    assert!(pdf.starts_with(b"%PDF"));
+    assert_eq!("letter".parse::<PageSize>(), Ok(PageSize::Letter));
+    assert!("b5".parse::<PageSize>().is_err());
+}
+
+#[test]
+fn images_have_the_asked_size() {
+    let grid = week(vec![entry("R&D <sync>")]);
+
+    let svg = svg::render(&grid, &Colors::default(), 1200, 900);
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("width=\"1200\" height=\"900\""));
+    assert!(svg.contains(">R&amp;D &lt;sync&gt;</text>"));
+
+    let png = png::render(&grid, &Colors::default(), 640, 480).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+    // Width and height of the header chunk
+    assert_eq!(png[16..24], [0, 0, 2, 128, 0, 0, 1, 224]);
+
+    assert!(png::render(&grid, &Colors::default(), 0, 480).is_err());
+}
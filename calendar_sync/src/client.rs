@@ -0,0 +1,225 @@
+//! Requests to the server with the account's credentials, and discovery of
+//! its calendars.
+
+use std::time::Duration;
+
+use base64::Engine;
+use calendar_core::storage::{LoadError, SaveError};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::dav::{self, Multistatus};
+
+/// Server and credentials of a CalDAV account
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    /// URL of a calendar, or of the account to discover calendars from
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+/// Calendar collection found on the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarInfo {
+    pub url: String,
+    pub name: String,
+}
+
+/// Why talking to the server or syncing failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Url(String),
+    /// The server could not be reached
+    Network(String),
+    /// The server refused a request
+    Status {
+        method: &'static str,
+        url: String,
+        status: u16,
+    },
+    /// The server answered with something other than WebDAV
+    Response(String),
+    /// No calendar was found from the account URL
+    NoCalendar(String),
+    Load(LoadError),
+    Save(SaveError),
+    /// The sync state next to the calendar file could not be read or written
+    State(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Url(url) => write!(f, "invalid URL `{url}`"),
+            Error::Network(error) => write!(f, "cannot reach the server: {error}"),
+            Error::Status { method, url, status } => write!(f, "{method} {url} failed with status {status}"),
+            Error::Response(error) => write!(f, "unexpected answer from the server: {error}"),
+            Error::NoCalendar(url) => write!(f, "no calendar found at {url}"),
+            Error::Load(error) => write!(f, "cannot read the calendar: {error}"),
+            Error::Save(error) => write!(f, "cannot save the calendar: {error}"),
+            Error::State(error) => write!(f, "cannot keep the sync state: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Answer to a request, whatever its status
+pub(crate) struct Response {
+    pub status: u16,
+    pub etag: Option<String>,
+    pub body: String,
+}
+
+/// Connection to a CalDAV server
+pub struct Client {
+    agent: ureq::Agent,
+    authorization: Option<String>,
+}
+
+impl Client {
+    /// Client sending the credentials of `account`, with HTTP basic
+    /// authentication
+    pub fn new(account: &Account) -> Client {
+        let authorization = (!account.username.is_empty()).then(|| {
+            let credentials = format!("{}:{}", account.username, account.password);
+
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+
+        Client {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+            authorization,
+        }
+    }
+
+    /// Calendars reachable from `url`: the calendar itself, or those in the
+    /// calendar home of a principal or of the current user
+    pub fn discover(&self, url: &str) -> Result<Vec<CalendarInfo>, Error> {
+        let start = collection(url)?;
+        let found = self.dav("PROPFIND", &start, "0", dav::DISCOVER.to_string())?;
+        let entry = found.entries.into_iter().next().unwrap_or_default();
+
+        if entry.is_calendar {
+            return Ok(vec![CalendarInfo {
+                name: entry.display_name.unwrap_or_else(|| name(&start)),
+                url: start.to_string(),
+            }]);
+        }
+
+        let home = match (entry.calendar_home, entry.principal) {
+            (Some(home), _) => join(&start, &home)?,
+            (None, Some(principal)) => {
+                let principal = join(&start, &principal)?;
+
+                let home = self
+                    .dav("PROPFIND", &principal, "0", dav::DISCOVER.to_string())?
+                    .entries
+                    .into_iter()
+                    .find_map(|entry| entry.calendar_home)
+                    .ok_or_else(|| Error::NoCalendar(url.to_string()))?;
+
+                join(&principal, &home)?
+            }
+            // Perhaps the calendar home itself
+            (None, None) => start.clone(),
+        };
+
+        let calendars: Vec<CalendarInfo> = self
+            .dav("PROPFIND", &home, "1", dav::COLLECTIONS.to_string())?
+            .entries
+            .into_iter()
+            .filter(|entry| entry.is_calendar)
+            .map(|entry| {
+                let url = join(&home, &entry.href)?;
+
+                Ok(CalendarInfo {
+                    name: entry.display_name.filter(|name| !name.is_empty()).unwrap_or_else(|| name(&url)),
+                    url: url.to_string(),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        if calendars.is_empty() {
+            return Err(Error::NoCalendar(url.to_string()));
+        }
+
+        Ok(calendars)
+    }
+
+    /// Send a request and return the answer whatever its status, failing
+    /// only when the server cannot be reached
+    pub(crate) fn send(&self, method: &'static str, url: &Url, headers: &[(&str, &str)], body: Option<String>) -> Result<Response, Error> {
+        let mut request = self.agent.request(method, url.as_str());
+
+        if let Some(authorization) = &self.authorization {
+            request = request.set("Authorization", authorization);
+        }
+
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+
+        let result = match body {
+            Some(body) => request.send_string(&body),
+            None => request.call(),
+        };
+
+        let response = match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(error)) => return Err(Error::Network(error.to_string())),
+        };
+
+        Ok(Response {
+            status: response.status(),
+            etag: response.header("ETag").map(ToOwned::to_owned),
+            body: response.into_string().map_err(|error| Error::Network(error.to_string()))?,
+        })
+    }
+
+    /// PROPFIND or REPORT request expecting a multi-status answer
+    pub(crate) fn dav(&self, method: &'static str, url: &Url, depth: &str, body: String) -> Result<Multistatus, Error> {
+        let response = self.send(
+            method,
+            url,
+            &[("Depth", depth), ("Content-Type", "application/xml; charset=utf-8")],
+            Some(body),
+        )?;
+
+        if response.status != 207 {
+            return Err(Error::Status {
+                method,
+                url: url.to_string(),
+                status: response.status,
+            });
+        }
+
+        dav::parse(&response.body).map_err(Error::Response)
+    }
+}
+
+/// URL of a collection, with the trailing slash relative references need
+pub(crate) fn collection(url: &str) -> Result<Url, Error> {
+    let mut parsed = Url::parse(url.trim()).map_err(|_| Error::Url(url.to_string()))?;
+
+    if !parsed.path().ends_with('/') {
+        let path = format!("{}/", parsed.path());
+        parsed.set_path(&path);
+    }
+
+    Ok(parsed)
+}
+
+/// `href` from a response, relative to the URL it was asked at
+pub(crate) fn join(base: &Url, href: &str) -> Result<Url, Error> {
+    base.join(href).map_err(|_| Error::Url(href.to_string()))
+}
+
+/// Last segment of the path of `url`, naming calendars with no display name
+fn name(url: &Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .unwrap_or_default()
+        .to_string()
+}
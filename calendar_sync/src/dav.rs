@@ -0,0 +1,162 @@
+//! WebDAV request bodies and multi-status responses.
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// Where the calendars of an account are, and whether the URL is one
+pub const DISCOVER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:resourcetype/><d:displayname/><d:current-user-principal/><c:calendar-home-set/></d:prop>
+</d:propfind>"#;
+
+/// Kind and name of the collections of a calendar home
+pub const COLLECTIONS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/><d:displayname/></d:prop></d:propfind>"#;
+
+/// Current sync token of a calendar
+pub const SYNC_TOKEN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:sync-token/></d:prop></d:propfind>"#;
+
+/// ETags of every event of a calendar
+pub const LIST_EVENTS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+/// Events changed or removed since `token`, with their ETags
+pub fn sync_collection(token: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:sync-collection xmlns:d="DAV:">
+  <d:sync-token>{}</d:sync-token>
+  <d:sync-level>1</d:sync-level>
+  <d:prop><d:getetag/></d:prop>
+</d:sync-collection>"#,
+        escape(token)
+    )
+}
+
+/// ETags and contents of the events at `hrefs`
+pub fn multiget<'a>(hrefs: impl IntoIterator<Item = &'a str>) -> String {
+    let hrefs: String = hrefs
+        .into_iter()
+        .map(|href| format!("  <d:href>{}</d:href>\n", escape(href)))
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+{hrefs}</c:calendar-multiget>"#
+    )
+}
+
+/// Properties of one resource in a multi-status response. Only properties
+/// found with a success status are kept.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Entry {
+    pub href: String,
+    /// Status of the whole resource, as given for removed events in sync
+    /// reports
+    pub status: Option<u16>,
+    pub etag: Option<String>,
+    pub calendar_data: Option<String>,
+    pub display_name: Option<String>,
+    pub is_calendar: bool,
+    pub principal: Option<String>,
+    pub calendar_home: Option<String>,
+    pub sync_token: Option<String>,
+}
+
+/// Body of a 207 Multi-Status response
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Multistatus {
+    pub entries: Vec<Entry>,
+    /// New token of a sync report
+    pub sync_token: Option<String>,
+}
+
+/// Read a multi-status body
+pub fn parse(body: &str) -> Result<Multistatus, String> {
+    let document = roxmltree::Document::parse(body).map_err(|error| error.to_string())?;
+    let root = document.root_element();
+
+    if !root.has_tag_name((DAV, "multistatus")) {
+        return Err(format!("expected multistatus, found {}", root.tag_name().name()));
+    }
+
+    let mut multistatus = Multistatus::default();
+
+    for node in root.children().filter(|node| node.is_element()) {
+        if node.has_tag_name((DAV, "sync-token")) {
+            multistatus.sync_token = Some(text(node));
+        } else if node.has_tag_name((DAV, "response")) {
+            multistatus.entries.push(entry(node));
+        }
+    }
+
+    Ok(multistatus)
+}
+
+fn entry(response: roxmltree::Node) -> Entry {
+    let mut entry = Entry::default();
+
+    for node in response.children().filter(|node| node.is_element()) {
+        if node.has_tag_name((DAV, "href")) {
+            entry.href = text(node);
+        } else if node.has_tag_name((DAV, "status")) {
+            entry.status = status(&text(node));
+        } else if node.has_tag_name((DAV, "propstat")) {
+            let succeeded = child(node, DAV, "status")
+                .and_then(|status_node| status(&text(status_node)))
+                .is_some_and(|code| (200..300).contains(&code));
+
+            if let (true, Some(prop)) = (succeeded, child(node, DAV, "prop")) {
+                read_properties(&mut entry, prop);
+            }
+        }
+    }
+
+    entry
+}
+
+fn read_properties(entry: &mut Entry, prop: roxmltree::Node) {
+    for property in prop.children().filter(|node| node.is_element()) {
+        let tag = property.tag_name();
+
+        match (tag.namespace(), tag.name()) {
+            (Some(DAV), "getetag") => entry.etag = Some(text(property)),
+            (Some(DAV), "displayname") => entry.display_name = Some(text(property)),
+            (Some(DAV), "sync-token") => entry.sync_token = Some(text(property)),
+            (Some(DAV), "resourcetype") => entry.is_calendar = child(property, CALDAV, "calendar").is_some(),
+            (Some(DAV), "current-user-principal") => entry.principal = child(property, DAV, "href").map(text),
+            (Some(CALDAV), "calendar-home-set") => entry.calendar_home = child(property, DAV, "href").map(text),
+            // Servers may split the data into several text and CDATA nodes
+            (Some(CALDAV), "calendar-data") => {
+                entry.calendar_data = Some(property.descendants().filter(|node| node.is_text()).filter_map(|node| node.text()).collect());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, namespace: &str, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name((namespace, name)))
+}
+
+fn text(node: roxmltree::Node) -> String {
+    node.text().unwrap_or_default().trim().to_string()
+}
+
+/// Code of a status line such as `HTTP/1.1 404 Not Found`
+fn status(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
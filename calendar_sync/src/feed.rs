@@ -0,0 +1,77 @@
+//! Read-only calendars published as iCalendar feeds, over HTTP or as files.
+
+use std::path::Path;
+use std::time::Duration;
+
+use calendar_core::{ics, Event};
+use url::Url;
+
+/// Why a feed could not be fetched
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedError {
+    /// The server could not be reached
+    Network(String),
+    /// The server answered with an error status
+    Status(u16),
+    File(std::io::ErrorKind),
+    Parse(ics::ParseError),
+}
+
+impl std::fmt::Display for FeedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedError::Network(error) => write!(f, "cannot reach the server: {error}"),
+            FeedError::Status(status) => write!(f, "the server answered with status {status}"),
+            FeedError::File(kind) => write!(f, "cannot read the file: {}", std::io::Error::from(*kind)),
+            FeedError::Parse(error) => write!(f, "not a valid calendar: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FeedError {}
+
+/// Events of the feed at `source`: an `http`, `https` or `webcal` URL, a
+/// `file` URL or a path
+pub fn fetch(source: &str) -> Result<Vec<Event>, FeedError> {
+    let source = source.trim();
+
+    let document = match Url::parse(source) {
+        Ok(url) if matches!(url.scheme(), "http" | "https" | "webcal") => get(url)?,
+        Ok(url) if url.scheme() == "file" => {
+            let path = url.to_file_path().map_err(|()| FeedError::File(std::io::ErrorKind::InvalidInput))?;
+
+            read(&path)?
+        }
+        // Windows paths parse as URLs with the drive as scheme
+        _ => read(Path::new(source))?,
+    };
+
+    let imported = ics::parse(&document).map_err(FeedError::Parse)?;
+
+    Ok(imported.into_iter().map(|imported| imported.event).collect())
+}
+
+fn get(mut url: Url) -> Result<String, FeedError> {
+    // webcal is HTTP under another name, telling browsers to hand the link
+    // to a calendar application
+    if url.scheme() == "webcal" {
+        let https = format!("https{}", &url.as_str()["webcal".len()..]);
+        url = Url::parse(&https).map_err(|error| FeedError::Network(error.to_string()))?;
+    }
+
+    let response = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .get(url.as_str())
+        .call();
+
+    match response {
+        Ok(response) => response.into_string().map_err(|error| FeedError::Network(error.to_string())),
+        Err(ureq::Error::Status(status, _)) => Err(FeedError::Status(status)),
+        Err(ureq::Error::Transport(error)) => Err(FeedError::Network(error.to_string())),
+    }
+}
+
+fn read(path: &Path) -> Result<String, FeedError> {
+    std::fs::read_to_string(path).map_err(|error| FeedError::File(error.kind()))
+}
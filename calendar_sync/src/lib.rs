@@ -0,0 +1,19 @@
+//! Two-way synchronization of the calendar file with a CalDAV (RFC 4791)
+//! server.
+//!
+//! `Client::discover` finds the calendars of an account, and `sync` brings
+//! the events of one of them and the local events in agreement, pulling
+//! what changed on the server since the last sync token and pushing local
+//! changes with ETag preconditions so edits made elsewhere are never
+//! overwritten unseen.
+//!
+//! `feed::fetch` reads the calendars others publish as iCalendar feeds,
+//! which are only ever pulled.
+
+mod client;
+mod dav;
+pub mod feed;
+mod sync;
+
+pub use client::{Account, CalendarInfo, Client, Error};
+pub use sync::{state_path, sync, sync_file, ConflictPolicy, Resource, SyncReport, SyncState};
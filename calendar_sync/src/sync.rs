@@ -0,0 +1,423 @@
+//! Sync of the events of the calendar file with one calendar on the server.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use calendar_core::{
+    calendar::next_id,
+    calendars,
+    ics::{self, Imported},
+    storage::{self, LoadError},
+    Event, SavedState,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::client::{self, Client, Error};
+use crate::dav;
+
+/// Which side wins when an event was changed both here and on the server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    #[default]
+    PreferServer,
+    PreferLocal,
+}
+
+/// Event known to be on the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resource {
+    /// Path of the event on the server
+    pub href: String,
+    pub uid: String,
+    /// ETag of the version last seen, none when the server did not give it
+    pub etag: Option<String>,
+    /// Id of the local event
+    pub event: u64,
+    /// Event as both sides last agreed on, telling which side changed since
+    pub synced: Event,
+}
+
+/// What the previous syncs left, kept next to the calendar file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Calendar the state is about; syncing with another one starts over
+    pub calendar: String,
+    /// Token to ask the server for what changed since, none for servers
+    /// without sync reports
+    pub sync_token: Option<String>,
+    pub resources: Vec<Resource>,
+}
+
+/// What a sync did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Events added or changed here from the server
+    pub received: usize,
+    /// Events removed here as they were removed from the server
+    pub removed_here: usize,
+    /// Events added or changed on the server from here
+    pub sent: usize,
+    /// Events removed from the server as they were removed here
+    pub removed_there: usize,
+    /// Titles of events changed on both sides
+    pub conflicts: Vec<String>,
+    /// Server paths that could not be read as events
+    pub unreadable: Vec<String>,
+}
+
+/// Changes on the server since the last sync
+#[derive(Default)]
+struct Changes {
+    /// Paths with their new ETag
+    changed: Vec<(String, Option<String>)>,
+    removed: Vec<String>,
+}
+
+/// Bring `events` and the calendar at `calendar_url` in agreement.
+///
+/// Server changes are applied first, then local changes are sent with
+/// `If-Match` so that an event changed on the server in between is not
+/// overwritten: it is left for the next sync. `state` records progress as
+/// it is made, and is to be kept along with `events` even when the sync
+/// fails half-way.
+pub fn sync(client: &Client, calendar_url: &str, events: &mut Vec<Event>, state: &mut SyncState, policy: ConflictPolicy) -> Result<SyncReport, Error> {
+    let calendar = client::collection(calendar_url)?;
+
+    if state.calendar != calendar.as_str() {
+        *state = SyncState {
+            calendar: calendar.to_string(),
+            ..SyncState::default()
+        };
+    }
+
+    let mut report = SyncReport::default();
+
+    let (changes, token) = remote_changes(client, &calendar, state)?;
+    pull(client, &calendar, changes, events, state, policy, &mut report)?;
+    push(client, &calendar, events, state, &mut report)?;
+
+    state.sync_token = token;
+
+    Ok(report)
+}
+
+/// Sync the calendar file at `data_path`, holding its lock throughout so
+/// the window and the command line wait for the sync to finish
+pub fn sync_file(client: &Client, calendar_url: &str, data_path: &Path, policy: ConflictPolicy) -> Result<SyncReport, Error> {
+    let _lock = storage::lock(data_path).map_err(|error| Error::Load(LoadError::File(error.kind())))?;
+
+    let mut saved = match SavedState::read(data_path) {
+        Ok(saved) => saved,
+        Err(error) if error.is_missing() => SavedState::default(),
+        Err(error) => return Err(Error::Load(error)),
+    };
+
+    let path = state_path(data_path);
+    let mut state = read_state(&path)?;
+    let before = saved.events.clone();
+
+    let result = sync(client, calendar_url, &mut saved.events, &mut state, policy);
+
+    // Events new from the server go to the first calendar, in the copy the
+    // next sync compares with too
+    calendars::repair(&mut saved.calendars, &mut saved.events);
+
+    for resource in state.resources.iter_mut().filter(|resource| resource.synced.calendar == 0) {
+        if let Some(event) = saved.events.iter().find(|event| event.id == resource.event) {
+            resource.synced.calendar = event.calendar;
+        }
+    }
+
+    // Kept even after a failure, so what was sent is not sent twice
+    if saved.events != before {
+        saved.write(data_path).map_err(Error::Save)?;
+    }
+
+    let json = serde_json::to_string_pretty(&state).map_err(|error| Error::State(error.to_string()))?;
+    storage::write_atomic(&path, json.as_bytes()).map_err(|error| Error::State(error.to_string()))?;
+
+    result
+}
+
+/// Sync state of the calendar file at `data_path`, `calendar.caldav.json`
+/// next to `calendar.json`
+pub fn state_path(data_path: &Path) -> PathBuf {
+    data_path.with_extension("caldav.json")
+}
+
+fn read_state(path: &Path) -> Result<SyncState, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|error| Error::State(error.to_string())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(SyncState::default()),
+        Err(error) => Err(Error::State(error.to_string())),
+    }
+}
+
+/// What changed on the server, with the token to give next time
+fn remote_changes(client: &Client, calendar: &Url, state: &SyncState) -> Result<(Changes, Option<String>), Error> {
+    if let Some(token) = &state.sync_token {
+        match client.dav("REPORT", calendar, "1", dav::sync_collection(token)) {
+            Ok(report) => {
+                let mut changes = Changes::default();
+
+                for entry in report.entries {
+                    let href = path(calendar, &entry.href)?;
+
+                    if href == calendar.path() {
+                        continue;
+                    }
+
+                    if entry.status == Some(404) {
+                        changes.removed.push(href);
+                    } else {
+                        changes.changed.push((href, entry.etag));
+                    }
+                }
+
+                return Ok((changes, report.sync_token.or_else(|| Some(token.clone()))));
+            }
+            // The token is too old or the server forgot it: list everything
+            Err(Error::Status { status: 403 | 409 | 410, .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    // Asked before listing, so that changes made meanwhile come next time
+    let token = client
+        .dav("PROPFIND", calendar, "0", dav::SYNC_TOKEN.to_string())?
+        .entries
+        .into_iter()
+        .find_map(|entry| entry.sync_token);
+
+    let mut changes = Changes::default();
+    let mut listed = HashSet::new();
+
+    for entry in client.dav("REPORT", calendar, "1", dav::LIST_EVENTS.to_string())?.entries {
+        let href = path(calendar, &entry.href)?;
+
+        if href != calendar.path() {
+            listed.insert(href.clone());
+            changes.changed.push((href, entry.etag));
+        }
+    }
+
+    changes.removed = state
+        .resources
+        .iter()
+        .filter(|resource| !listed.contains(&resource.href))
+        .map(|resource| resource.href.clone())
+        .collect();
+
+    Ok((changes, token))
+}
+
+/// Apply the server changes to `events`
+fn pull(
+    client: &Client,
+    calendar: &Url,
+    changes: Changes,
+    events: &mut Vec<Event>,
+    state: &mut SyncState,
+    policy: ConflictPolicy,
+    report: &mut SyncReport,
+) -> Result<(), Error> {
+    // Events sent by the last sync come back with the ETag already known
+    let wanted: Vec<&str> = changes
+        .changed
+        .iter()
+        .filter(|(href, etag)| {
+            etag.is_none() || state.resources.iter().find(|resource| &resource.href == href).is_none_or(|resource| resource.etag != *etag)
+        })
+        .map(|(href, _)| href.as_str())
+        .collect();
+
+    if !wanted.is_empty() {
+        for entry in client.dav("REPORT", calendar, "1", dav::multiget(wanted))?.entries {
+            let href = path(calendar, &entry.href)?;
+
+            let data = match entry.calendar_data {
+                Some(data) => data,
+                // Removed since it was listed
+                None => continue,
+            };
+
+            match ics::parse(&data).ok().and_then(|imported| imported.into_iter().next()) {
+                Some(imported) => receive(href, entry.etag, imported, events, state, policy, report),
+                None => report.unreadable.push(href),
+            }
+        }
+    }
+
+    for href in changes.removed {
+        let resource = match state.resources.iter().position(|resource| resource.href == href) {
+            Some(r) => state.resources.remove(r),
+            None => continue,
+        };
+
+        // Changed here since, it is kept and sent again as a new event
+        if let Some(i) = events.iter().position(|event| event.id == resource.event && *event == resource.synced) {
+            events.remove(i);
+            report.removed_here += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply one event read from the server
+fn receive(
+    href: String,
+    etag: Option<String>,
+    imported: Imported,
+    events: &mut Vec<Event>,
+    state: &mut SyncState,
+    policy: ConflictPolicy,
+    report: &mut SyncReport,
+) {
+    let Imported { uid, event: mut remote } = imported;
+
+    let resource = match state.resources.iter_mut().find(|resource| resource.href == href) {
+        Some(resource) => resource,
+        None => {
+            remote.id = next_id(events);
+            events.push(remote.clone());
+            report.received += 1;
+
+            state.resources.push(Resource {
+                href,
+                uid,
+                etag,
+                event: remote.id,
+                synced: remote,
+            });
+
+            return;
+        }
+    };
+
+    remote.id = resource.event;
+
+    let local = events.iter().position(|event| event.id == resource.event);
+
+    // The server knows nothing of named calendars: the event stays in the
+    // one it is filed in here
+    remote.calendar = local.map_or(resource.synced.calendar, |i| events[i].calendar);
+
+    match local {
+        Some(i) if events[i] == remote => {}
+        // Changed on both sides. Kept here, the local version is sent over
+        // the server's one afterwards.
+        Some(i) if events[i] != resource.synced => {
+            report.conflicts.push(events[i].title.clone());
+
+            if policy == ConflictPolicy::PreferServer {
+                events[i] = remote.clone();
+                report.received += 1;
+            }
+        }
+        Some(i) => {
+            events[i] = remote.clone();
+            report.received += 1;
+        }
+        // Removed here but changed there: the change wins
+        None => {
+            events.push(remote.clone());
+            report.received += 1;
+        }
+    }
+
+    resource.uid = uid;
+    resource.etag = etag;
+    resource.synced = remote;
+}
+
+/// Send the local changes to the server
+fn push(client: &Client, calendar: &Url, events: &[Event], state: &mut SyncState, report: &mut SyncReport) -> Result<(), Error> {
+    let mut r = 0;
+
+    while r < state.resources.len() {
+        let resource = &state.resources[r];
+
+        if events.iter().any(|event| event.id == resource.event) {
+            r += 1;
+            continue;
+        }
+
+        let url = client::join(calendar, &resource.href)?;
+        let response = client.send("DELETE", &url, &precondition("If-Match", resource.etag.as_deref()), None)?;
+
+        match response.status {
+            200..=299 | 404 | 410 => {
+                state.resources.remove(r);
+                report.removed_there += 1;
+            }
+            // Changed on the server since, it comes back with the next sync
+            412 => {
+                report.conflicts.push(resource.synced.title.clone());
+                r += 1;
+            }
+            status => return Err(Error::Status { method: "DELETE", url: url.to_string(), status }),
+        }
+    }
+
+    for event in events {
+        match state.resources.iter_mut().find(|resource| resource.event == event.id) {
+            Some(resource) if resource.synced == *event => {}
+            Some(resource) => {
+                let url = client::join(calendar, &resource.href)?;
+                let response = put(client, &url, precondition("If-Match", resource.etag.as_deref()), ics::resource(event, &resource.uid))?;
+
+                match response.status {
+                    200..=299 => {
+                        resource.etag = response.etag;
+                        resource.synced = event.clone();
+                        report.sent += 1;
+                    }
+                    // Changed or removed on the server since it was pulled,
+                    // sorted out by the next sync
+                    404 | 412 => report.conflicts.push(event.title.clone()),
+                    status => return Err(Error::Status { method: "PUT", url: url.to_string(), status }),
+                }
+            }
+            None => {
+                let name = format!("{}-{}", Utc::now().timestamp_millis(), event.id);
+                let uid = format!("{name}@date_picker");
+                let url = client::join(calendar, &format!("{name}.ics"))?;
+                let response = put(client, &url, precondition("If-None-Match", Some("*")), ics::resource(event, &uid))?;
+
+                if !(200..300).contains(&response.status) {
+                    return Err(Error::Status { method: "PUT", url: url.to_string(), status: response.status });
+                }
+
+                state.resources.push(Resource {
+                    href: url.path().to_string(),
+                    uid,
+                    etag: response.etag,
+                    event: event.id,
+                    synced: event.clone(),
+                });
+                report.sent += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn put(client: &Client, url: &Url, mut headers: Vec<(&str, &str)>, body: String) -> Result<client::Response, Error> {
+    headers.push(("Content-Type", "text/calendar; charset=utf-8"));
+
+    client.send("PUT", url, &headers, Some(body))
+}
+
+/// Conditional request header, none when there is nothing to compare with
+fn precondition<'a>(name: &'a str, value: Option<&'a str>) -> Vec<(&'a str, &'a str)> {
+    value.map(|value| (name, value)).into_iter().collect()
+}
+
+/// Path of `href` on the server, the way resources are recorded
+fn path(calendar: &Url, href: &str) -> Result<String, Error> {
+    client::join(calendar, href).map(|url| url.path().to_string())
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use calendar_sync::feed::{self, FeedError};
+use tiny_http::{Response, Server};
+
+const FEED: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nX-WR-CALNAME:Team\r\n\
+    BEGIN:VEVENT\r\nUID:1@team\r\nDTSTART;VALUE=DATE:20260901\r\nSUMMARY:Offsite\r\nEND:VEVENT\r\n\
+    BEGIN:VEVENT\r\nUID:2@team\r\nDTSTART:20260902T080000Z\r\nDTEND:20260902T090000Z\r\nSUMMARY:Demo\r\nEND:VEVENT\r\n\
+    END:VCALENDAR\r\n";
+
+/// Server answering `/team.ics` with the feed, and anything else with 404
+fn serve() -> String {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", server.server_addr());
+
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = match request.url() {
+                "/team.ics" => Response::from_string(FEED),
+                "/broken.ics" => Response::from_string("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n"),
+                _ => Response::from_string("").with_status_code(404),
+            };
+
+            let _ = request.respond(response);
+        }
+    });
+
+    base
+}
+
+fn titles(events: &[calendar_core::Event]) -> Vec<&str> {
+    events.iter().map(|event| event.title.as_str()).collect()
+}
+
+#[test]
+fn fetches_over_http() {
+    let base = serve();
+
+    assert_eq!(titles(&feed::fetch(&format!("{base}/team.ics")).unwrap()), ["Offsite", "Demo"]);
+    assert_eq!(feed::fetch(&format!("{base}/missing.ics")), Err(FeedError::Status(404)));
+    assert!(matches!(feed::fetch(&format!("{base}/broken.ics")), Err(FeedError::Parse(_))));
+}
+
+#[test]
+fn fetches_files() {
+    let path = std::env::temp_dir().join(format!("calendar_sync-feed-{}.ics", std::process::id()));
+    std::fs::File::create(&path).unwrap().write_all(FEED.as_bytes()).unwrap();
+
+    assert_eq!(feed::fetch(path.to_str().unwrap()).unwrap().len(), 2);
+    assert_eq!(feed::fetch(&format!("file://{}", path.display())).unwrap().len(), 2);
+
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(feed::fetch(path.to_str().unwrap()), Err(FeedError::File(std::io::ErrorKind::NotFound)));
+}
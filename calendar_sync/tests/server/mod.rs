@@ -0,0 +1,287 @@
+//! CalDAV stand-in server for the tests: one user, `ann` with password
+//! `secret`, whose home holds the calendar `work` and a scheduling inbox.
+//! Events are kept in memory with a change log backing sync tokens.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use tiny_http::{Header, Method, Request, Response};
+
+pub const CALENDAR: &str = "/calendars/ann/work/";
+
+#[derive(Default)]
+struct Store {
+    /// Event paths with their ETag and contents
+    events: BTreeMap<String, (String, String)>,
+    revision: u64,
+    /// Revision each path was last changed at
+    log: Vec<(u64, String)>,
+    /// Tokens older than this are refused
+    oldest_token: u64,
+    /// Method and path of every request
+    requests: Vec<String>,
+}
+
+impl Store {
+    fn change(&mut self, path: &str, data: Option<String>) -> String {
+        self.revision += 1;
+        self.log.push((self.revision, path.to_string()));
+
+        let etag = format!("\"{}\"", self.revision);
+
+        match data {
+            Some(data) => {
+                self.events.insert(path.to_string(), (etag.clone(), data));
+            }
+            None => {
+                self.events.remove(path);
+            }
+        }
+
+        etag
+    }
+
+    fn token(&self) -> String {
+        format!("http://example.com/sync/{}", self.revision)
+    }
+}
+
+pub struct Server {
+    pub base: String,
+    store: Arc<Mutex<Store>>,
+}
+
+impl Server {
+    pub fn start() -> Server {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", server.server_addr());
+        let store = Arc::new(Mutex::new(Store::default()));
+
+        let shared = store.clone();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle(request, &shared);
+            }
+        });
+
+        Server { base, store }
+    }
+
+    pub fn calendar_url(&self) -> String {
+        format!("{}{CALENDAR}", self.base)
+    }
+
+    /// Change an event as another client would
+    pub fn put(&self, name: &str, data: &str) {
+        self.store.lock().unwrap().change(&format!("{CALENDAR}{name}"), Some(data.to_string()));
+    }
+
+    /// Remove an event as another client would
+    pub fn remove(&self, path: &str) {
+        self.store.lock().unwrap().change(path, None);
+    }
+
+    /// Paths and contents of the events on the server
+    pub fn events(&self) -> Vec<(String, String)> {
+        let store = self.store.lock().unwrap();
+
+        store.events.iter().map(|(path, (_, data))| (path.clone(), data.clone())).collect()
+    }
+
+    /// Make every sync token given so far invalid
+    pub fn forget_tokens(&self) {
+        let mut store = self.store.lock().unwrap();
+        store.oldest_token = store.revision + 1;
+    }
+
+    /// Token a sync report would give now
+    pub fn token(&self) -> String {
+        self.store.lock().unwrap().token()
+    }
+
+    /// Requests made since the last call, as `METHOD path`
+    pub fn requests(&self) -> Vec<String> {
+        std::mem::take(&mut self.store.lock().unwrap().requests)
+    }
+}
+
+fn handle(mut request: Request, store: &Mutex<Store>) {
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str().to_string())
+    };
+
+    let authorized = header("Authorization").as_deref() == Some("Basic YW5uOnNlY3JldA==");
+    let if_match = header("If-Match");
+    let if_none_match = header("If-None-Match");
+    let depth = header("Depth").unwrap_or_default();
+    let path = request.url().to_string();
+    let method = request.method().clone();
+
+    let mut store = store.lock().unwrap();
+    store.requests.push(format!("{} {path}", method.as_str()));
+
+    let response = if !authorized {
+        Response::from_string("").with_status_code(401)
+    } else {
+        match method {
+            Method::NonStandard(ref name) if name.as_str() == "PROPFIND" => propfind(&store, &path, &depth),
+            Method::NonStandard(ref name) if name.as_str() == "REPORT" && path == CALENDAR => report(&store, &body),
+            Method::Get => match store.events.get(&path) {
+                Some((etag, data)) => Response::from_string(data.clone()).with_header(etag_header(etag)),
+                None => Response::from_string("").with_status_code(404),
+            },
+            Method::Put if path.starts_with(CALENDAR) => {
+                let current = store.events.get(&path).map(|(etag, _)| etag.clone());
+
+                let refused = match (&if_match, &if_none_match) {
+                    (Some(expected), _) => current.as_ref() != Some(expected),
+                    (_, Some(_)) => current.is_some(),
+                    _ => false,
+                };
+
+                if refused {
+                    Response::from_string("").with_status_code(412)
+                } else {
+                    let etag = store.change(&path, Some(body));
+                    let status = if current.is_some() { 204 } else { 201 };
+
+                    Response::from_string("").with_status_code(status).with_header(etag_header(&etag))
+                }
+            }
+            Method::Delete => match store.events.get(&path).map(|(etag, _)| etag.clone()) {
+                None => Response::from_string("").with_status_code(404),
+                Some(current) if if_match.as_ref().is_some_and(|expected| *expected != current) => {
+                    Response::from_string("").with_status_code(412)
+                }
+                Some(_) => {
+                    store.change(&path, None);
+                    Response::from_string("").with_status_code(204)
+                }
+            },
+            _ => Response::from_string("").with_status_code(405),
+        }
+    };
+
+    let _ = request.respond(response);
+}
+
+fn propfind(store: &Store, path: &str, depth: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let responses = match path {
+        "/" => vec![response("/", "<d:resourcetype><d:collection/></d:resourcetype>\
+            <d:current-user-principal><d:href>/principals/ann/</d:href></d:current-user-principal>")],
+        "/principals/ann/" => vec![response(path, "<d:resourcetype><d:principal/></d:resourcetype>\
+            <c:calendar-home-set><d:href>/calendars/ann/</d:href></c:calendar-home-set>")],
+        "/calendars/ann/" => {
+            let mut responses = vec![response(path, "<d:resourcetype><d:collection/></d:resourcetype>")];
+
+            if depth == "1" {
+                responses.push(calendar(store));
+                responses.push(response("/calendars/ann/inbox/", "<d:resourcetype><d:collection/><c:schedule-inbox/></d:resourcetype>"));
+            }
+
+            responses
+        }
+        CALENDAR => vec![calendar(store)],
+        _ => return Response::from_string("").with_status_code(404),
+    };
+
+    multistatus(&responses.concat(), None)
+}
+
+fn report(store: &Store, body: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let with_etag = |path: &str| match store.events.get(path) {
+        Some((etag, _)) => response(path, &format!("<d:getetag>{etag}</d:getetag>")),
+        None => format!("<d:response><d:href>{path}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>"),
+    };
+
+    if body.contains("sync-collection") {
+        let since: u64 = between(body, "<d:sync-token>", "</d:sync-token>")
+            .first()
+            .and_then(|token| token.rsplit('/').next()?.parse().ok())
+            .unwrap_or(0);
+
+        if since < store.oldest_token {
+            return Response::from_string("<d:error xmlns:d=\"DAV:\"><d:valid-sync-token/></d:error>").with_status_code(403);
+        }
+
+        let mut changed: Vec<&str> = store
+            .log
+            .iter()
+            .filter(|(revision, _)| *revision > since)
+            .map(|(_, path)| path.as_str())
+            .collect();
+        changed.sort();
+        changed.dedup();
+
+        let responses: String = changed.into_iter().map(with_etag).collect();
+
+        multistatus(&responses, Some(&store.token()))
+    } else if body.contains("calendar-multiget") {
+        let responses: String = between(body, "<d:href>", "</d:href>")
+            .into_iter()
+            .map(|path| match store.events.get(path) {
+                Some((etag, data)) => response(
+                    path,
+                    &format!("<d:getetag>{etag}</d:getetag><c:calendar-data>{}</c:calendar-data>", escape(data)),
+                ),
+                None => with_etag(path),
+            })
+            .collect();
+
+        multistatus(&responses, None)
+    } else {
+        let responses: String = store.events.keys().map(|path| with_etag(path)).collect();
+
+        multistatus(&responses, None)
+    }
+}
+
+fn calendar(store: &Store) -> String {
+    response(
+        CALENDAR,
+        &format!(
+            "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+            <d:displayname>Work</d:displayname><d:sync-token>{}</d:sync-token>",
+            store.token()
+        ),
+    )
+}
+
+fn response(href: &str, properties: &str) -> String {
+    format!(
+        "<d:response><d:href>{href}</d:href><d:propstat><d:prop>{properties}</d:prop>\
+        <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"
+    )
+}
+
+fn multistatus(responses: &str, token: Option<&str>) -> Response<std::io::Cursor<Vec<u8>>> {
+    let token = token.map(|token| format!("<d:sync-token>{token}</d:sync-token>")).unwrap_or_default();
+
+    Response::from_string(format!(
+        "<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">{responses}{token}</d:multistatus>"
+    ))
+    .with_status_code(207)
+}
+
+fn etag_header(etag: &str) -> Header {
+    Header::from_bytes("ETag", etag).unwrap()
+}
+
+/// Every text between `start` and `end` in `body`
+fn between<'a>(body: &'a str, start: &str, end: &str) -> Vec<&'a str> {
+    body.split(start)
+        .skip(1)
+        .filter_map(|part| part.split_once(end).map(|(inside, _)| inside))
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,232 @@
+mod server;
+
+use calendar_core::{ics, Category, Event, EventTime};
+use calendar_sync::{sync, sync_file, state_path, Account, Client, ConflictPolicy, Error, SyncReport, SyncState};
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use server::{Server, CALENDAR};
+
+fn client() -> Client {
+    Client::new(&Account {
+        url: String::new(),
+        username: "ann".to_string(),
+        password: "secret".to_string(),
+    })
+}
+
+fn event(id: u64, title: &str, day: u32) -> Event {
+    let mut event = Event::new(title.to_string(), NaiveDate::from_ymd_opt(2026, 5, day).unwrap(), None, None, Category::Todo);
+    event.id = id;
+
+    event
+}
+
+/// Event as another client would write it
+fn remote(uid: &str, title: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Other//EN\r\nBEGIN:VEVENT\r\nUID:{uid}\r\n\
+        DTSTART;TZID=Europe/Paris:20260511T140000\r\nDTEND;TZID=Europe/Paris:20260511T150000\r\n\
+        SUMMARY:{title}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+/// Titles of the events on the server
+fn remote_titles(server: &Server) -> Vec<String> {
+    let mut titles: Vec<String> = server
+        .events()
+        .iter()
+        .flat_map(|(_, data)| ics::parse(data).unwrap())
+        .map(|imported| imported.event.title)
+        .collect();
+    titles.sort();
+
+    titles
+}
+
+fn titles(events: &[Event]) -> Vec<String> {
+    let mut titles: Vec<String> = events.iter().map(|event| event.title.clone()).collect();
+    titles.sort();
+
+    titles
+}
+
+/// Server with one event from another client, and a local calendar with
+/// one event, synced once
+fn synced() -> (Server, Vec<Event>, SyncState) {
+    let server = Server::start();
+    server.put("review.ics", &remote("review@other", "Review"));
+
+    let mut events = vec![event(1, "Planning", 4)];
+    let mut state = SyncState::default();
+    sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+    server.requests();
+
+    (server, events, state)
+}
+
+#[test]
+fn discovers_calendars() {
+    let server = Server::start();
+
+    for url in [server.base.clone(), format!("{}/principals/ann", server.base), server.calendar_url()] {
+        let calendars = client().discover(&url).unwrap();
+
+        assert_eq!(calendars.len(), 1, "from {url}");
+        assert_eq!(calendars[0].name, "Work");
+        assert_eq!(calendars[0].url, server.calendar_url());
+    }
+
+    let stranger = Client::new(&Account {
+        url: String::new(),
+        username: "ann".to_string(),
+        password: "wrong".to_string(),
+    });
+    assert!(matches!(stranger.discover(&server.base), Err(Error::Status { status: 401, .. })));
+}
+
+#[test]
+fn first_sync_pulls_and_pushes() {
+    let server = Server::start();
+    server.put("review.ics", &remote("review@other", "Review"));
+
+    let mut events = vec![event(1, "Planning", 4)];
+    let mut state = SyncState::default();
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+
+    assert_eq!((report.received, report.sent), (1, 1));
+    assert_eq!(titles(&events), ["Planning", "Review"]);
+    assert_eq!(remote_titles(&server), ["Planning", "Review"]);
+
+    let review = events.iter().find(|event| event.title == "Review").unwrap();
+    assert_eq!(review.id, 2);
+    assert_eq!(review.time.map(|time| (time.start, time.zone)), Some((NaiveTime::from_hms_opt(14, 0, 0).unwrap(), Tz::Europe__Paris)));
+
+    // Nothing changed since: the token says so, and nothing is sent again
+    server.requests();
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+    assert_eq!(report, SyncReport::default());
+    assert!(server.requests().iter().all(|request| !request.starts_with("PUT")));
+}
+
+#[test]
+fn changes_travel_both_ways() {
+    let (server, mut events, mut state) = synced();
+
+    server.put("review.ics", &remote("review@other", "Review, moved"));
+    events.iter_mut().find(|event| event.title == "Planning").unwrap().location = "Room 2".to_string();
+
+    let review = events.iter().position(|event| event.title == "Review").unwrap();
+    events[review].calendar = 5;
+    state.resources.iter_mut().find(|resource| resource.event == events[review].id).unwrap().synced.calendar = 5;
+
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+
+    assert_eq!((report.received, report.sent), (1, 1));
+    assert_eq!(titles(&events), ["Planning", "Review, moved"]);
+    assert_eq!(events[review].calendar, 5, "kept in its calendar");
+    assert!(server.events().iter().any(|(_, data)| data.contains("LOCATION:Room 2")));
+    assert!(server.requests().iter().any(|request| request == &format!("REPORT {CALENDAR}")));
+
+    // Removed on both sides
+    server.remove(&format!("{CALENDAR}review.ics"));
+    events.retain(|event| event.title != "Planning");
+
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+
+    assert_eq!((report.removed_here, report.removed_there), (1, 1));
+    assert!(events.is_empty());
+    assert!(server.events().is_empty());
+    assert!(state.resources.is_empty());
+}
+
+#[test]
+fn conflicts_follow_the_policy() {
+    for (policy, expected) in [(ConflictPolicy::PreferServer, "Review there"), (ConflictPolicy::PreferLocal, "Review here")] {
+        let (server, mut events, mut state) = synced();
+
+        server.put("review.ics", &remote("review@other", "Review there"));
+        events.iter_mut().find(|event| event.title == "Review").unwrap().title = "Review here".to_string();
+
+        let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, policy).unwrap();
+
+        assert_eq!(report.conflicts, ["Review here"]);
+        assert_eq!(titles(&events), ["Planning", expected]);
+        assert_eq!(remote_titles(&server), ["Planning", expected]);
+    }
+}
+
+#[test]
+fn changes_survive_removal_on_the_other_side() {
+    let (server, mut events, mut state) = synced();
+
+    // Changed there, removed here: comes back
+    server.put("review.ics", &remote("review@other", "Review, moved"));
+    events.retain(|event| event.title != "Review");
+
+    // Changed here, removed there: sent again
+    let planning = state.resources.iter().find(|resource| resource.synced.title == "Planning").unwrap();
+    server.remove(&planning.href.clone());
+    events.iter_mut().find(|event| event.title == "Planning").unwrap().notes = "bring slides".to_string();
+
+    sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+
+    assert_eq!(titles(&events), ["Planning", "Review, moved"]);
+    assert_eq!(remote_titles(&server), ["Planning", "Review, moved"]);
+}
+
+#[test]
+fn forgotten_token_lists_everything() {
+    let (server, mut events, mut state) = synced();
+
+    server.forget_tokens();
+    server.remove(&format!("{CALENDAR}review.ics"));
+    server.put("retro.ics", &remote("retro@other", "Retro"));
+
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferServer).unwrap();
+
+    assert_eq!((report.received, report.removed_here, report.sent), (1, 1, 0));
+    assert_eq!(titles(&events), ["Planning", "Retro"]);
+    assert!(server.requests().iter().any(|request| request.starts_with("PROPFIND")));
+}
+
+#[test]
+fn edits_racing_the_sync_are_not_overwritten() {
+    let (server, mut events, mut state) = synced();
+
+    // Changed on the server after this client asked what changed
+    server.put("review.ics", &remote("review@other", "Review there"));
+    state.sync_token = Some(server.token());
+
+    events.iter_mut().find(|event| event.title == "Review").unwrap().time = Some(EventTime {
+        start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+        zone: Tz::UTC,
+        reminder: None,
+    });
+
+    let report = sync(&client(), &server.calendar_url(), &mut events, &mut state, ConflictPolicy::PreferLocal).unwrap();
+
+    assert_eq!(report.conflicts, ["Review"]);
+    assert_eq!(remote_titles(&server), ["Planning", "Review there"]);
+}
+
+#[test]
+fn sync_file_keeps_its_state_next_to_the_calendar() {
+    let server = Server::start();
+    server.put("review.ics", &remote("review@other", "Review"));
+
+    let dir = std::env::temp_dir().join(format!("calendar_sync-file-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("calendar.json");
+
+    let report = sync_file(&client(), &server.calendar_url(), &path, ConflictPolicy::PreferServer).unwrap();
+    assert_eq!(report.received, 1);
+    assert!(state_path(&path).exists());
+
+    let saved = calendar_core::SavedState::load(&path).unwrap();
+    assert_eq!(titles(&saved.events), ["Review"]);
+
+    let report = sync_file(&client(), &server.calendar_url(), &path, ConflictPolicy::PreferServer).unwrap();
+    assert_eq!(report, SyncReport::default());
+}
@@ -0,0 +1,317 @@
+//! Local HTTP API for other tools, such as a dashboard or a chat bot, to
+//! read and change events.
+//!
+//! Requests are authenticated and parsed on a thread of their own, then
+//! handed to the window, which answers them from the events it shows and
+//! applies changes as if they were made in it.
+//!
+//! - `GET /events?from=YYYY-MM-DD&to=YYYY-MM-DD`: occurrences in the range
+//! - `POST /events`: create the event of the body
+//! - `PUT /events/{id}`: replace the event by the one of the body
+//! - `DELETE /events/{id}`
+
+use std::io::Read;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use calendar_core::{calendars, Calendar, Category, Event, NamedCalendar};
+use chrono::NaiveDate;
+use iced::futures::{channel::mpsc as channel, future, StreamExt};
+use iced::{subscription, Subscription};
+use serde::Serialize;
+use tiny_http::{Header, Method};
+
+use crate::config::Api;
+
+/// Longest a request waits for the window to answer
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Widest range of days listed at once
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Largest body read, in bytes, far more than an event takes
+const MAX_BODY: u64 = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    List { from: NaiveDate, to: NaiveDate },
+    Create(Event),
+    Update(u64, Event),
+    Delete(u64),
+}
+
+/// Status and JSON body sent back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        Response {
+            status,
+            body: serde_json::to_string(value).unwrap_or_default(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response::json(status, &serde_json::json!({ "error": message.into() }))
+    }
+}
+
+/// Request waiting for the window's answer
+#[derive(Clone)]
+pub struct Call {
+    pub request: Request,
+    reply: mpsc::Sender<Response>,
+}
+
+impl std::fmt::Debug for Call {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Call").field("request", &self.request).finish()
+    }
+}
+
+impl Call {
+    pub fn answer(self, response: Response) {
+        // The client may have given up already
+        let _ = self.reply.send(response);
+    }
+}
+
+/// What the server passes on to the window
+#[derive(Debug, Clone)]
+pub enum Incoming {
+    Call(Call),
+    /// The server could not start, it is tried again later
+    Failed(String),
+}
+
+/// Event on a day of the listed range
+#[derive(Serialize)]
+struct Occurrence<'a> {
+    on: NaiveDate,
+    event: &'a Event,
+}
+
+/// Answer `request` from `calendar`, changing it when asked to; events
+/// filed in none of `calendars` go to the first. Returns whether the
+/// calendar changed.
+pub fn apply(request: Request, calendar: &mut Calendar, calendars: &[NamedCalendar]) -> (Response, bool) {
+    let file = |mut event: Event| {
+        if calendars::find(calendars, event.calendar).is_none() {
+            event.calendar = calendars.first().map_or(0, |calendar| calendar.id);
+        }
+
+        event
+    };
+
+    match request {
+        Request::List { from, to } => {
+            let occurrences: Vec<Occurrence> = calendar
+                .occurrences(from, to)
+                .into_iter()
+                .flat_map(|(on, positions)| positions.into_iter().map(move |i| (on, i)))
+                .map(|(on, i)| Occurrence {
+                    on,
+                    event: &calendar.events()[i],
+                })
+                .collect();
+
+            (Response::json(200, &occurrences), false)
+        }
+        Request::Create(event) => {
+            let id = calendar.add(file(event));
+            let created = calendar.position(id).map(|i| &calendar.events()[i]);
+
+            (Response::json(201, &created), true)
+        }
+        Request::Update(id, event) => match calendar.position(id) {
+            Some(i) => {
+                if let Some(slot) = calendar.event_mut(i) {
+                    *slot = Event { id, ..file(event) };
+                }
+
+                calendar.reindex();
+
+                (Response::json(200, &calendar.events()[i]), true)
+            }
+            None => (Response::error(404, format!("no event {id}")), false),
+        },
+        Request::Delete(id) => match calendar.position(id) {
+            Some(i) => {
+                calendar.remove(i);
+
+                (Response { status: 204, body: String::new() }, true)
+            }
+            None => (Response::error(404, format!("no event {id}")), false),
+        },
+    }
+}
+
+enum Run {
+    Starting(Api),
+    Running(channel::UnboundedReceiver<Call>),
+    Retrying(Api),
+}
+
+/// Serve `api` on localhost, passing on the requests it receives
+pub fn serve(api: &Api) -> Subscription<Incoming> {
+    subscription::unfold(api.clone(), Run::Starting(api.clone()), |run| async move {
+        match run {
+            Run::Starting(api) => match tiny_http::Server::http(("127.0.0.1", api.port)) {
+                Ok(server) => {
+                    let (calls, receiver) = channel::unbounded();
+
+                    std::thread::spawn(move || listen(server, api.token, calls));
+
+                    (None, Run::Running(receiver))
+                }
+                Err(error) => (Some(Incoming::Failed(format!("cannot listen on port {}: {error}", api.port))), Run::Retrying(api)),
+            },
+            Run::Running(mut receiver) => match receiver.next().await {
+                Some(call) => (Some(Incoming::Call(call)), Run::Running(receiver)),
+                None => future::pending().await,
+            },
+            // The port may be held by the previous server for a moment
+            Run::Retrying(api) => {
+                async_std::task::sleep(Duration::from_secs(5)).await;
+
+                (None, Run::Starting(api))
+            }
+        }
+    })
+}
+
+/// Answer requests until the window stops listening
+fn listen(server: tiny_http::Server, token: String, calls: channel::UnboundedSender<Call>) {
+    let authorization = format!("Bearer {token}");
+
+    while !calls.is_closed() {
+        let mut request = match server.recv_timeout(Duration::from_secs(1)) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(_) => return,
+        };
+
+        let authorized = request
+            .headers()
+            .iter()
+            .any(|header| header.field.equiv("Authorization") && same_secret(header.value.as_str(), &authorization));
+
+        // The body is only read for clients holding the token, and only up
+        // to the limit
+        let response = if !authorized {
+            Response::error(401, "missing or wrong token")
+        } else if request.body_length().is_some_and(|length| length as u64 > MAX_BODY) {
+            Response::error(413, format!("the body is larger than {MAX_BODY} bytes"))
+        } else {
+            let mut body = Vec::new();
+            let read = request.as_reader().take(MAX_BODY + 1).read_to_end(&mut body);
+
+            match (read, String::from_utf8(body)) {
+                (Err(_), _) => Response::error(400, "the body could not be read"),
+                (Ok(length), _) if length as u64 > MAX_BODY => Response::error(413, format!("the body is larger than {MAX_BODY} bytes")),
+                (Ok(_), Err(_)) => Response::error(400, "the body is not UTF-8"),
+                (Ok(_), Ok(body)) => answer(&request, &body, &calls),
+            }
+        };
+
+        let mut reply = tiny_http::Response::from_string(response.body).with_status_code(response.status);
+
+        if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+            reply = reply.with_header(header);
+        }
+
+        let _ = request.respond(reply);
+    }
+}
+
+/// Whether `given` is `expected`, taking as long whichever byte differs so
+/// the time taken does not tell how much of a guess was right
+pub fn same_secret(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Pass an authorized request on to the window and wait for its answer
+fn answer(request: &tiny_http::Request, body: &str, calls: &channel::UnboundedSender<Call>) -> Response {
+    match parse(request.method(), request.url(), body) {
+        Ok(parsed) => {
+            let (reply, answer) = mpsc::channel();
+
+            match calls.unbounded_send(Call { request: parsed, reply }) {
+                Ok(()) => answer
+                    .recv_timeout(ANSWER_TIMEOUT)
+                    .unwrap_or_else(|_| Response::error(503, "the calendar did not answer")),
+                Err(_) => Response::error(503, "the calendar is closing"),
+            }
+        }
+        Err(response) => response,
+    }
+}
+
+/// Request for `method` on `url`, or the error answering it
+fn parse(method: &Method, url: &str, body: &str) -> Result<Request, Response> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    // Holidays are generated, not stored
+    let event = || match serde_json::from_str::<Event>(body) {
+        Ok(event) if Category::EDITABLE.contains(&event.category) => Ok(event),
+        Ok(event) => Err(Response::error(400, format!("events cannot be created as {}", event.category))),
+        Err(error) => Err(Response::error(400, format!("invalid event: {error}"))),
+    };
+
+    let id = |value: &str| value.parse::<u64>().map_err(|_| Response::error(404, format!("no event {value}")));
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["events"]) => {
+            let date = |name: &str| {
+                let value = query
+                    .split('&')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| Response::error(400, format!("missing `{name}`")))?;
+
+                NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| Response::error(400, format!("`{name}` must be YYYY-MM-DD")))
+            };
+
+            let (from, to) = (date("from")?, date("to")?);
+
+            if to < from || (to - from).num_days() >= MAX_RANGE_DAYS {
+                return Err(Response::error(400, format!("`to` must be from `from` to {MAX_RANGE_DAYS} days after it")));
+            }
+
+            Ok(Request::List { from, to })
+        }
+        (Method::Post, ["events"]) => Ok(Request::Create(event()?)),
+        (Method::Put, ["events", value]) => Ok(Request::Update(id(value)?, event()?)),
+        (Method::Delete, ["events", value]) => Ok(Request::Delete(id(value)?)),
+        (_, ["events"] | ["events", _]) => Err(Response::error(405, "method not allowed")),
+        _ => Err(Response::error(404, "not found")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holidays_are_not_created() {
+        let body = |category: &str| format!(r#"{{"title": "Day off", "date": "2026-09-02", "category": "{category}"}}"#);
+
+        assert!(matches!(parse(&Method::Post, "/events", &body("Reminder")), Ok(Request::Create(_))));
+
+        for (method, url) in [(Method::Post, "/events"), (Method::Put, "/events/3")] {
+            let error = parse(&method, url, &body("Holiday")).unwrap_err();
+            assert_eq!(error.status, 400, "{}", error.body);
+        }
+    }
+}
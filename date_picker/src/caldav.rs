@@ -0,0 +1,22 @@
+//! Sync with the CalDAV account of the settings.
+
+use calendar_sync::{Client, SyncReport};
+
+use crate::config::Config;
+
+/// Sync the calendar file with the CalDAV calendar of the settings, the
+/// first one found when the account URL is not a calendar itself. Blocks
+/// until done, holding the lock of the calendar file.
+pub fn sync(config: &Config) -> Result<SyncReport, String> {
+    let account = config.caldav.as_ref().ok_or("no CalDAV account in the settings")?;
+    let client = Client::new(account);
+
+    let calendar = client
+        .discover(&account.url)
+        .map_err(|error| error.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("no calendar found at {}", account.url))?;
+
+    calendar_sync::sync_file(&client, &calendar.url, &config.data_path(), config.sync_conflicts).map_err(|error| error.to_string())
+}
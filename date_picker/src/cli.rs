@@ -0,0 +1,373 @@
+//! Command line interface, for scripts to manage events without opening a
+//! window. It works on the same calendar file as the window, under its lock.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use calendar_core::{conflicts, date, holidays, storage, zone, Calendar, Category, Event, EventTime, SavedState};
+use calendar_render::{Grid, Orientation};
+
+use crate::{config::Config, print};
+
+const USAGE: &str = "\
+Usage:
+  date_picker add <title> --date YYYY-MM-DD [--time HH:MM] [--zone ZONE] [--category todo|reminder]
+  date_picker list [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+  date_picker delete <id>
+  date_picker agenda [--today | --date YYYY-MM-DD]
+  date_picker conflicts [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+  date_picker print --out FILE.pdf [--week] [--date YYYY-MM-DD] [--size a4|a3|letter] [--portrait | --landscape]
+  date_picker render --out FILE.svg|FILE.png [--week] [--date YYYY-MM-DD] [--width PIXELS] [--height PIXELS]
+  date_picker sync
+";
+
+/// Size of rendered images when none is given, in pixels
+const IMAGE_WIDTH: u32 = 1200;
+const IMAGE_HEIGHT: u32 = 900;
+
+/// Run the command in `args`, returning the exit code
+pub fn run(args: &[String]) -> i32 {
+    let (config, config_errors) = Config::load();
+
+    for error in config_errors {
+        eprintln!("warning: {error}");
+    }
+
+    match execute(args, &config) {
+        Ok(output) => {
+            print!("{output}");
+            0
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            1
+        }
+    }
+}
+
+fn execute(args: &[String], config: &Config) -> Result<String, String> {
+    let (command, rest) = args.split_first().ok_or_else(|| USAGE.to_string())?;
+    let args = Args::parse(rest)?;
+    let path = config.data_path();
+
+    match command.as_str() {
+        "add" => {
+            let [title] = args.positional(["<title>"])?;
+            let date = parse_date(&args.required("--date")?)?;
+            let event_zone = match args.option("--zone") {
+                Some(name) => name.parse::<Tz>().map_err(|_| format!("unknown time zone `{name}`"))?,
+                None => zone::local_zone(),
+            };
+            let time = match args.option("--time") {
+                Some(time) => {
+                    let start = NaiveTime::parse_from_str(&time, "%H:%M")
+                        .map_err(|_| format!("invalid time `{time}`, expected HH:MM"))?;
+
+                    Some(EventTime {
+                        start,
+                        end: start.overflowing_add_signed(Duration::minutes(i64::from(config.event_duration))).0,
+                        zone: event_zone,
+                        reminder: config.reminder,
+                    })
+                }
+                None => None,
+            };
+            let category = match args.option("--category").as_deref() {
+                None | Some("todo") => Category::Todo,
+                Some("reminder") => Category::Reminder,
+                Some(other) => return Err(format!("unknown category `{other}`, expected todo or reminder")),
+            };
+
+            let id = modify(&path, |calendar| Ok(calendar.add(Event::new(title, date, time, None, category))))?;
+
+            Ok(format!("{id}\n"))
+        }
+        "delete" => {
+            let [id] = args.positional(["<id>"])?;
+            let id: u64 = id.parse().map_err(|_| format!("invalid id `{id}`"))?;
+
+            let title = modify(&path, |calendar| {
+                let position = calendar.position(id).ok_or_else(|| format!("no event with id {id}"))?;
+
+                Ok(calendar.remove(position).map(|event| event.title).unwrap_or_default())
+            })?;
+
+            Ok(format!("Deleted {id} {title}\n"))
+        }
+        "list" | "conflicts" => {
+            let calendar = read(&path)?;
+            let from = match args.option("--from") {
+                Some(from) => parse_date(&from)?,
+                None => date::today(calendar.zone()),
+            };
+            let to = match args.option("--to") {
+                Some(to) => parse_date(&to)?,
+                None => from + Duration::days(30),
+            };
+
+            match command.as_str() {
+                "list" => Ok(agenda(&calendar, from, to)),
+                _ => Ok(conflict_report(&calendar, from, to)),
+            }
+        }
+        "agenda" => {
+            let calendar = read(&path)?;
+            let day = match args.option("--date") {
+                Some(day) if !args.flag("--today") => parse_date(&day)?,
+                Some(_) => return Err("--today and --date cannot be used together".to_string()),
+                None => date::today(calendar.zone()),
+            };
+
+            Ok(agenda(&calendar, day, day))
+        }
+        "print" => {
+            let out = std::path::PathBuf::from(args.required("--out")?);
+            let size = match args.option("--size") {
+                Some(size) => size.parse()?,
+                None => config.page_size,
+            };
+            let orientation = match (args.flag("--portrait"), args.flag("--landscape")) {
+                (true, true) => return Err("--portrait and --landscape cannot be used together".to_string()),
+                (true, false) => Orientation::Portrait,
+                (false, true) => Orientation::Landscape,
+                (false, false) => config.orientation,
+            };
+
+            let grid = grid(&args, config, read_state(&path)?)?;
+            let pdf = calendar_render::pdf::render(&grid, &config.palette().render_colors(), size, orientation)
+                .map_err(|error| format!("cannot render the page: {error}"))?;
+
+            std::fs::write(&out, pdf).map_err(|error| format!("cannot write {}: {error}", out.display()))?;
+
+            Ok(format!("Printed to {}\n", out.display()))
+        }
+        "render" => {
+            let out = std::path::PathBuf::from(args.required("--out")?);
+            let pixels = |name: &str, default: u32| match args.option(name) {
+                Some(value) => value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|pixels| (1..=10_000).contains(pixels))
+                    .ok_or_else(|| format!("invalid {name} `{value}`, expected pixels from 1 to 10000")),
+                None => Ok(default),
+            };
+            let (width, height) = (pixels("--width", IMAGE_WIDTH)?, pixels("--height", IMAGE_HEIGHT)?);
+
+            let grid = grid(&args, config, read_state(&path)?)?;
+            let colors = config.palette().render_colors();
+
+            let image = match out.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase).as_deref() {
+                Some("svg") => calendar_render::svg::render(&grid, &colors, width, height).into_bytes(),
+                Some("png") => calendar_render::png::render(&grid, &colors, width, height).map_err(|error| format!("cannot render the image: {error}"))?,
+                _ => return Err(format!("cannot tell the format of {}, expected a .svg or .png file", out.display())),
+            };
+
+            std::fs::write(&out, image).map_err(|error| format!("cannot write {}: {error}", out.display()))?;
+
+            Ok(format!("Rendered to {}\n", out.display()))
+        }
+        "sync" => {
+            let report = crate::caldav::sync(config)?;
+
+            let mut output = format!(
+                "{} received, {} removed here, {} sent, {} removed from the server\n",
+                report.received, report.removed_here, report.sent, report.removed_there
+            );
+
+            for title in report.conflicts {
+                output += &format!("conflict: {title}\n");
+            }
+
+            for href in report.unreadable {
+                output += &format!("skipped unreadable {href}\n");
+            }
+
+            Ok(output)
+        }
+        "help" | "--help" | "-h" => Ok(USAGE.to_string()),
+        other => Err(format!("unknown command `{other}`\n{USAGE}")),
+    }
+}
+
+/// Calendar file at `path`, empty when there is none yet, displayed in its
+/// saved zone
+fn read(path: &std::path::Path) -> Result<Calendar, String> {
+    let state = read_state(path)?;
+
+    Ok(Calendar::new(state.events, state.display_zone.unwrap_or_else(zone::local_zone)))
+}
+
+fn read_state(path: &std::path::Path) -> Result<SavedState, String> {
+    let _lock = storage::lock(path).map_err(|error| format!("cannot lock {}: {error}", path.display()))?;
+
+    read_locked(path)
+}
+
+fn read_locked(path: &std::path::Path) -> Result<SavedState, String> {
+    if !path.exists() {
+        return Ok(SavedState::default());
+    }
+
+    SavedState::read(path).map_err(|error| format!("cannot read {}: {error}", path.display()))
+}
+
+/// Apply `change` to the calendar file at `path`, holding its lock from
+/// reading to writing so the window or another command cannot interleave
+fn modify<T>(path: &std::path::Path, change: impl FnOnce(&mut Calendar) -> Result<T, String>) -> Result<T, String> {
+    let _lock = storage::lock(path).map_err(|error| format!("cannot lock {}: {error}", path.display()))?;
+
+    let state = read_locked(path)?;
+    let mut calendar = Calendar::new(state.events, state.display_zone.unwrap_or_else(zone::local_zone));
+    let result = change(&mut calendar)?;
+
+    let state = SavedState {
+        events: calendar.into_events(),
+        ..state
+    };
+
+    state
+        .write(path)
+        .map_err(|error| format!("cannot write {}: {error}", path.display()))?;
+
+    Ok(result)
+}
+
+/// One line per occurrence between `from` and `to`, by date and time
+fn agenda(calendar: &Calendar, from: NaiveDate, to: NaiveDate) -> String {
+    let zone = calendar.zone();
+    let mut lines: Vec<(NaiveDate, Option<NaiveTime>, String)> = Vec::new();
+
+    for (day, positions) in calendar.occurrences(from, to) {
+        for i in positions {
+            let event = &calendar.events()[i];
+            let occurrence = calendar.occurrence(i, day);
+
+            let (start, hours) = match (occurrence.start_in(zone), occurrence.end_in(zone)) {
+                (Some(start), Some(end)) => (
+                    Some(start.time()),
+                    format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+                ),
+                _ => (None, "all day".to_string()),
+            };
+
+            lines.push((day, start, format!("{:>5}  {day}  {hours:<11}  {}", event.id, event.title)));
+        }
+    }
+
+    lines.sort();
+
+    lines.into_iter().map(|(_, _, line)| line + "\n").collect()
+}
+
+/// Month of `--date`, today by default, or its week with `--week`, as the
+/// window shows it with the saved holidays and calendars
+fn grid(args: &Args, config: &Config, state: SavedState) -> Result<Grid, String> {
+    let calendar = Calendar::new(state.events, state.display_zone.unwrap_or_else(zone::local_zone));
+    let day = match args.option("--date") {
+        Some(day) => parse_date(&day)?,
+        None => date::today(calendar.zone()),
+    };
+
+    let (locale, palette, first_weekday) = (config.locale, config.palette(), config.first_weekday());
+    // The calendar file held them before they were a setting
+    let countries = match config.holiday_countries {
+        Some(_) => config.holiday_countries(),
+        None => state.holiday_countries.clone(),
+    };
+
+    if args.flag("--week") {
+        let from = day - Duration::days(i64::from(date::days_since(day.weekday(), first_weekday)));
+        let holidays = holidays::between(&countries, from, from + Duration::days(6));
+
+        Ok(print::week(from, &calendar, &state.calendars, &holidays, &Default::default(), state.show_lunar, locale, palette))
+    } else {
+        let holidays = holidays::between(&countries, date::first_of_month(day), date::last_of_month(day));
+
+        Ok(print::month(day, &calendar, &state.calendars, &holidays, &Default::default(), state.show_lunar, locale, first_weekday, palette))
+    }
+}
+
+/// One line per pair of events of the same calendar overlapping between
+/// `from` and `to`
+fn conflict_report(calendar: &Calendar, from: NaiveDate, to: NaiveDate) -> String {
+    let zone = calendar.zone();
+    let describe = |i: usize, day: NaiveDate| {
+        let event = &calendar.events()[i];
+        let occurrence = calendar.occurrence(i, day);
+
+        match (occurrence.start_in(zone), occurrence.end_in(zone)) {
+            (Some(start), Some(end)) => format!("{} {}-{} {}", event.id, start.format("%H:%M"), end.format("%H:%M"), event.title),
+            _ => format!("{} {}", event.id, event.title),
+        }
+    };
+
+    conflicts::between(calendar, from, to)
+        .into_iter()
+        .map(|conflict| {
+            let day = conflict.date;
+
+            format!("{day}  {}  overlaps  {}\n", describe(conflict.first, day), describe(conflict.second, day))
+        })
+        .collect()
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("invalid date `{value}`, expected YYYY-MM-DD"))
+}
+
+/// Positional arguments, then `--name value` options and `--name` flags
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    /// Options taking no value
+    const FLAGS: &[&str] = &["--today", "--week", "--portrait", "--landscape"];
+
+    fn parse(args: &[String]) -> Result<Args, String> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg.starts_with("--") {
+                let value = if Self::FLAGS.contains(&arg.as_str()) {
+                    None
+                } else {
+                    Some(args.next().ok_or_else(|| format!("{arg} needs a value"))?.clone())
+                };
+
+                options.push((arg.clone(), value));
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+
+        Ok(Args { positional, options })
+    }
+
+    /// Exactly the positional arguments `names`
+    fn positional<const N: usize>(&self, names: [&str; N]) -> Result<[String; N], String> {
+        self.positional
+            .clone()
+            .try_into()
+            .map_err(|_| format!("expected {}\n{USAGE}", names.join(" ")))
+    }
+
+    fn option(&self, name: &str) -> Option<String> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .and_then(|(_, value)| value.clone())
+    }
+
+    fn required(&self, name: &str) -> Result<String, String> {
+        self.option(name).ok_or_else(|| format!("{name} is required"))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(option, _)| option == name)
+    }
+}
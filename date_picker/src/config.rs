@@ -0,0 +1,394 @@
+//! User settings, kept as TOML in the config directory.
+
+use std::path::PathBuf;
+
+use calendar_core::holidays::Country;
+use calendar_render::{Orientation, PageSize};
+use calendar_sync::{Account, ConflictPolicy};
+use chrono::{NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Locale, style::{Palette, ThemeChoice}};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Calendar file, in the data directory when not set
+    pub data_file: Option<PathBuf>,
+    /// Day weeks start on, the locale's when not set
+    pub first_weekday: Option<Weekday>,
+    pub theme: ThemeChoice,
+    pub locale: Locale,
+    /// Length of new timed events, in minutes
+    pub event_duration: u32,
+    /// Minutes before the start of new timed events to be reminded at,
+    /// none for no reminder
+    pub reminder: Option<u32>,
+    /// Working hours free time is looked for in
+    pub work_start: NaiveTime,
+    pub work_end: NaiveTime,
+    /// Paper the month and week are printed on
+    pub page_size: PageSize,
+    pub orientation: Orientation,
+    pub window_width: u32,
+    pub window_height: u32,
+    /// Side kept when an event changed both here and on the CalDAV server
+    pub sync_conflicts: ConflictPolicy,
+    /// Countries whose public holidays are shown, by ISO code such as
+    /// `VN`. When not set, the ones the calendar file held before this was
+    /// a setting.
+    pub holiday_countries: Option<Vec<String>>,
+    /// Colors of the custom theme. Last, as TOML tables must follow the
+    /// plain values.
+    pub custom_palette: Option<Palette>,
+    /// CalDAV account the calendar is synced with, none to keep it local
+    pub caldav: Option<Account>,
+    /// Local HTTP API for other tools, off when not set
+    pub api: Option<Api>,
+    /// Read-only iCalendar feeds served over HTTP, off when not set
+    pub publish: Option<Publish>,
+    /// Calendars shown read-only beside this one
+    pub subscriptions: Vec<Feed>,
+    /// The config file exists but could not be read, so these are defaults
+    /// that must not be saved over the settings it holds
+    #[serde(skip)]
+    pub load_failed: bool,
+}
+
+/// Read-only calendar fetched from an iCalendar feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Feed {
+    pub name: String,
+    /// URL or path of the `.ics` file
+    pub source: String,
+    /// Minutes between fetches
+    #[serde(default = "Feed::default_refresh")]
+    pub refresh_minutes: u32,
+}
+
+impl Feed {
+    /// Feed at `source`, named after its file
+    pub fn new(source: String) -> Self {
+        let name = source
+            .trim_end_matches('/')
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(".ics")
+            .to_string();
+
+        Feed {
+            name: if name.is_empty() { source.clone() } else { name },
+            source,
+            refresh_minutes: Self::default_refresh(),
+        }
+    }
+
+    fn default_refresh() -> u32 {
+        60
+    }
+}
+
+/// Where the local HTTP API listens and the token it expects
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Api {
+    /// Port on 127.0.0.1
+    #[serde(default = "Api::default_port")]
+    pub port: u16,
+    /// Sent by clients as `Authorization: Bearer <token>`
+    pub token: String,
+}
+
+impl Api {
+    fn default_port() -> u16 {
+        8737
+    }
+}
+
+/// Where the iCalendar feeds are served
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Publish {
+    /// Address and port to listen on, this computer only by default.
+    /// Phones on the local network need one such as `0.0.0.0:8738`, which
+    /// lets anyone there read every event, notes included, unless a key
+    /// is set.
+    #[serde(default = "Publish::default_address")]
+    pub address: String,
+    /// Expected from subscribers as `?key=`, none to publish openly
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Minutes subscribers are asked to wait between fetches
+    #[serde(default = "Publish::default_refresh")]
+    pub refresh_minutes: u32,
+}
+
+impl Publish {
+    fn default_address() -> String {
+        "127.0.0.1:8738".to_string()
+    }
+
+    /// Whether only programs on this computer can reach the feeds
+    pub fn is_local(&self) -> bool {
+        match self.address.parse::<std::net::SocketAddr>() {
+            Ok(address) => address.ip().is_loopback(),
+            Err(_) => self.address.starts_with("localhost:"),
+        }
+    }
+
+    fn default_refresh() -> u32 {
+        15
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            data_file: None,
+            first_weekday: None,
+            theme: ThemeChoice::default(),
+            locale: Locale::default(),
+            event_duration: 60,
+            reminder: None,
+            work_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            work_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            page_size: PageSize::default(),
+            orientation: Orientation::default(),
+            window_width: 1200,
+            window_height: 850,
+            sync_conflicts: ConflictPolicy::default(),
+            holiday_countries: None,
+            custom_palette: None,
+            caldav: None,
+            api: None,
+            publish: None,
+            subscriptions: Vec::new(),
+            load_failed: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Read(String),
+    Parse(String),
+    Write(String),
+    NotANumber(Field),
+    EventDuration,
+    Reminder,
+    WorkingHours,
+    WindowSize,
+    DataDirectory(PathBuf),
+    RefreshInterval,
+    ApiToken,
+    /// No holiday rules for this country code
+    HolidayCountry(String),
+    /// Not saved, as the file that failed to load would be overwritten
+    Unsaved,
+}
+
+/// Setting typed as text in the settings panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    EventDuration,
+    Reminder,
+    WindowWidth,
+    WindowHeight,
+}
+
+impl ConfigError {
+    /// What went wrong, in the language of `locale`
+    pub fn message(&self, locale: Locale) -> String {
+        let strings = locale.strings();
+
+        match self {
+            ConfigError::Read(error) => format!("{} {error}", strings.settings_read_failed),
+            ConfigError::Parse(error) => format!("{} {error}", strings.settings_invalid),
+            ConfigError::Write(error) => format!("{} {error}", strings.settings_save_failed),
+            ConfigError::NotANumber(field) => {
+                let label = match field {
+                    Field::EventDuration => strings.event_duration,
+                    Field::Reminder => strings.reminder,
+                    Field::WindowWidth => strings.window_width,
+                    Field::WindowHeight => strings.window_height,
+                };
+
+                format!("{label} {}", strings.not_a_number)
+            }
+            ConfigError::EventDuration => strings.invalid_event_duration.to_string(),
+            ConfigError::Reminder => strings.invalid_reminder.to_string(),
+            ConfigError::WorkingHours => strings.invalid_working_hours.to_string(),
+            ConfigError::WindowSize => strings.invalid_window_size.to_string(),
+            ConfigError::DataDirectory(dir) => format!("{} {}", strings.missing_folder, dir.display()),
+            ConfigError::RefreshInterval => strings.invalid_refresh.to_string(),
+            ConfigError::ApiToken => strings.short_api_token.to_string(),
+            ConfigError::HolidayCountry(code) => format!("{} {code}", strings.unknown_country),
+            ConfigError::Unsaved => strings.settings_unsaved.to_string(),
+        }
+    }
+}
+
+/// In English, for the command line
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message(Locale::English))
+    }
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        let mut path = if let Some(project_dirs) =
+            directories_next::ProjectDirs::from("rs", "Iced", "CalendarApp")
+        {
+            project_dirs.config_dir().into()
+        } else {
+            std::env::current_dir().unwrap_or_default()
+        };
+
+        path.push("config.toml");
+
+        path
+    }
+
+    /// Settings from the config file, defaults when there is none. Invalid
+    /// settings are replaced by their defaults and reported, so a broken
+    /// file never keeps the calendar from starting. A file that cannot be
+    /// read at all is left untouched until it is fixed.
+    pub fn load() -> (Config, Vec<ConfigError>) {
+        let failed = |error| {
+            let config = Config {
+                load_failed: true,
+                ..Config::default()
+            };
+
+            (config, vec![error])
+        };
+
+        let contents = match std::fs::read_to_string(Self::path()) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return (Config::default(), Vec::new()),
+            Err(error) => return failed(ConfigError::Read(error.to_string())),
+        };
+
+        let mut config: Config = match toml::from_str(&contents) {
+            Ok(config) => config,
+            Err(error) => return failed(ConfigError::Parse(error.to_string())),
+        };
+
+        let errors = config.validate();
+        let defaults = Config::default();
+
+        for error in &errors {
+            match error {
+                ConfigError::EventDuration => config.event_duration = defaults.event_duration,
+                ConfigError::Reminder => config.reminder = defaults.reminder,
+                ConfigError::WorkingHours => {
+                    config.work_start = defaults.work_start;
+                    config.work_end = defaults.work_end;
+                }
+                ConfigError::WindowSize => {
+                    config.window_width = defaults.window_width;
+                    config.window_height = defaults.window_height;
+                }
+                ConfigError::DataDirectory(_) => config.data_file = None,
+                ConfigError::ApiToken => config.api = None,
+                ConfigError::HolidayCountry(_) => {
+                    for codes in &mut config.holiday_countries {
+                        codes.retain(|code| Country::from_code(code).is_some());
+                    }
+                }
+                ConfigError::RefreshInterval => {
+                    for feed in config.subscriptions.iter_mut().filter(|feed| feed.refresh_minutes == 0) {
+                        feed.refresh_minutes = Feed::default_refresh();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        (config, errors)
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        if !(1..=24 * 60).contains(&self.event_duration) {
+            errors.push(ConfigError::EventDuration);
+        }
+
+        if self.reminder.map_or(false, |minutes| minutes > 7 * 24 * 60) {
+            errors.push(ConfigError::Reminder);
+        }
+
+        if self.work_end <= self.work_start {
+            errors.push(ConfigError::WorkingHours);
+        }
+
+        if self.window_width < 400 || self.window_height < 300 {
+            errors.push(ConfigError::WindowSize);
+        }
+
+        if self.subscriptions.iter().any(|feed| feed.refresh_minutes == 0) {
+            errors.push(ConfigError::RefreshInterval);
+        }
+
+        if self.api.as_ref().is_some_and(|api| api.token.trim().len() < 16) {
+            errors.push(ConfigError::ApiToken);
+        }
+
+        for code in self.holiday_countries.iter().flatten() {
+            if Country::from_code(code).is_none() {
+                errors.push(ConfigError::HolidayCountry(code.clone()));
+            }
+        }
+
+        if let Some(dir) = self.data_file.as_ref().and_then(|file| file.parent()) {
+            if !dir.as_os_str().is_empty() && !dir.is_dir() {
+                errors.push(ConfigError::DataDirectory(dir.to_path_buf()));
+            }
+        }
+
+        errors
+    }
+
+    /// Write the settings to the config file, unless it failed to load
+    pub async fn save(self) -> Result<(), ConfigError> {
+        if self.load_failed {
+            return Err(ConfigError::Unsaved);
+        }
+
+        let contents = toml::to_string_pretty(&self).map_err(|error| ConfigError::Write(error.to_string()))?;
+        let path = Self::path();
+
+        if let Some(dir) = path.parent() {
+            async_std::fs::create_dir_all(dir)
+                .await
+                .map_err(|error| ConfigError::Write(error.to_string()))?;
+        }
+
+        async_std::fs::write(path, contents)
+            .await
+            .map_err(|error| ConfigError::Write(error.to_string()))
+    }
+
+    /// Where events are saved
+    pub fn data_path(&self) -> PathBuf {
+        self.data_file.clone().unwrap_or_else(calendar_core::storage::default_path)
+    }
+
+    /// Countries whose public holidays are shown
+    pub fn holiday_countries(&self) -> Vec<Country> {
+        self.holiday_countries
+            .iter()
+            .flatten()
+            .filter_map(|code| Country::from_code(code))
+            .collect()
+    }
+
+    pub fn first_weekday(&self) -> Weekday {
+        self.first_weekday.unwrap_or_else(|| self.locale.first_weekday())
+    }
+
+    pub fn palette(&self) -> Palette {
+        Palette::of(self.theme, self.custom_palette)
+    }
+}
@@ -0,0 +1,49 @@
+//! Errors kept after the window is closed, in `calendar.log` next to the
+//! calendar file. The log is rotated when it grows past `MAX_SIZE`, the
+//! `KEPT` previous ones being renamed `calendar.log.1` and so on.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+const MAX_SIZE: u64 = 256 * 1024;
+const KEPT: usize = 3;
+
+/// Append `message` to the log next to the calendar file at `data_path`.
+/// Failing to log is not worth bothering the user with, so errors are
+/// ignored.
+pub fn write(data_path: &Path, message: &str) {
+    let path = data_path.with_file_name("calendar.log");
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    if fs::metadata(&path).map_or(false, |metadata| metadata.len() > MAX_SIZE) {
+        rotate(&path);
+    }
+
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{} {message}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+    }
+}
+
+fn rotate(path: &Path) {
+    let _ = fs::remove_file(numbered(path, KEPT));
+
+    for n in (1..KEPT).rev() {
+        let _ = fs::rename(numbered(path, n), numbered(path, n + 1));
+    }
+
+    let _ = fs::rename(path, numbered(path, 1));
+}
+
+/// `calendar.log.<n>`
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+
+    name.into()
+}
@@ -0,0 +1,250 @@
+//! Events in the month grid, the timelines and the details popover.
+
+use calendar_core::{calendars, Event, NamedCalendar, Recurrence};
+use chrono::{Datelike, Duration};
+use chrono_tz::Tz;
+use iced::{
+    theme,
+    widget::{button, column, container, pick_list, row, text, text_input},
+    Alignment, Color, Element, Length,
+};
+
+use crate::{delete_icon, edit_icon, i18n::Locale, style::{self, Palette}};
+
+#[derive(Debug, Clone)]
+pub enum EventMessage {
+    Edit,
+    TitleEdited(String),
+    FinishEdition,
+    Delete,
+    ShowDetails,
+    DescriptionEdited(String),
+    LocationEdited(String),
+    UrlEdited(String),
+    NotesEdited(String),
+    /// Move the event to another named calendar
+    CalendarSelected(u64),
+    /// Move the event by this many minutes, whole days for all-day events
+    Moved(i64),
+}
+
+/// Minutes in a day, moving all-day events by one
+const DAY: i64 = 24 * 60;
+
+pub fn text_input_id(i: usize) -> text_input::Id {
+    text_input::Id::new(format!("event-{i}"))
+}
+
+/// Apply the edits of `message`; the others are handled by the
+/// application
+pub fn update(event: &mut Event, message: EventMessage) {
+    match message {
+        EventMessage::TitleEdited(new_title) => {
+            event.title = new_title;
+        }
+        EventMessage::DescriptionEdited(description) => {
+            event.description = description;
+        }
+        EventMessage::LocationEdited(location) => {
+            event.location = location;
+        }
+        EventMessage::UrlEdited(url) => {
+            event.url = url;
+        }
+        EventMessage::NotesEdited(notes) => {
+            event.notes = notes;
+        }
+        EventMessage::CalendarSelected(calendar) => {
+            event.calendar = calendar;
+        }
+        EventMessage::Moved(minutes) => {
+            let shift = Duration::minutes(minutes);
+
+            match &mut event.time {
+                // Wall-clock hours move in the zone the event was planned in
+                Some(time) => {
+                    if let Some(start) = event.date.and_time(time.start).checked_add_signed(shift) {
+                        event.date = start.date();
+                        time.start = start.time();
+                        time.end = time.end.overflowing_add_signed(shift).0;
+                    }
+                }
+                None => {
+                    if let Some(date) = event.date.checked_add_signed(Duration::days(minutes / DAY)) {
+                        event.date = date;
+                    }
+                }
+            }
+
+            if matches!(event.recurrence, Some(Recurrence::LunarYearly { .. })) {
+                event.recurrence = Some(Recurrence::lunar_yearly(event.date));
+            }
+        }
+        EventMessage::Edit | EventMessage::FinishEdition | EventMessage::Delete | EventMessage::ShowDetails => {}
+    }
+}
+
+/// Event at position `i`, as its title marked with the color of its
+/// calendar among `calendars`, and a warning when `conflicting` with
+/// another event, or, while `editing`, a title input
+#[allow(clippy::too_many_arguments)]
+pub fn view<'a>(event: &Event, i: usize, editing: bool, highlighted: bool, conflicting: bool, calendars: &[NamedCalendar], zone: Tz, locale: Locale, palette: Palette) -> Element<'a, EventMessage> {
+    let title = match event.start_in(zone) {
+        Some(start) => format!("{} {}", start.format("%H:%M"), event.title),
+        None => event.title.clone(),
+    };
+
+    if editing {
+        let text_input = text_input(
+            locale.strings().edit_placeholder,
+            &event.title,
+            EventMessage::TitleEdited,
+        ).id(text_input_id(i))
+        .on_submit(EventMessage::FinishEdition)
+        .padding(2);
+
+        return row![
+            text_input,
+            button(row![delete_icon()].spacing(2))
+                .on_press(EventMessage::Delete)
+                .padding(2)
+                .style(theme::Button::Destructive)
+        ]
+        .spacing(2)
+        .align_items(Alignment::Center)
+        .into();
+    }
+
+    let content = row![
+        text("●").size(12).style(calendar_color(event, calendars, palette)),
+        text(if conflicting { "⚠" } else { "" }).size(12).style(palette.danger),
+        button(text(title).size(16).style(palette.category(event.category)))
+            .on_press(EventMessage::ShowDetails)
+            .padding(0)
+            .width(Length::Fill)
+            .style(theme::Button::Text),
+        button(edit_icon())
+            .on_press(EventMessage::Edit)
+            .padding(5)
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    if highlighted {
+        container(content)
+            .style(style::highlight(palette))
+            .into()
+    } else {
+        content.into()
+    }
+}
+
+/// Color of the calendar `event` is filed in
+fn calendar_color(event: &Event, calendars: &[NamedCalendar], palette: Palette) -> Color {
+    calendars::find(calendars, event.calendar).map_or(palette.muted, |calendar| style::calendar_color(calendar.color))
+}
+
+/// Popover content listing every field of the event, the calendar it is
+/// filed in among `calendars` and the titles of the events it `overlaps`
+pub fn details_view<'a>(event: &Event, calendars: &[NamedCalendar], overlaps: &[String], zone: Tz, locale: Locale, palette: Palette) -> Element<'a, EventMessage> {
+    let strings = locale.strings();
+
+    let field = |label: &str, placeholder: &str, value: &str, on_change: fn(String) -> EventMessage| {
+        column![
+            text(label.to_string()).size(14).style(palette.muted),
+            text_input(placeholder, value, on_change).padding(5),
+        ]
+        .spacing(2)
+    };
+
+    let date = locale.format_date(event.date);
+
+    let when = match (event.time, event.start_in(zone), event.end_in(zone)) {
+        (Some(time), Some(start), Some(end)) if time.zone != zone => format!(
+            "{} {}-{} {} ({} {}-{} {})",
+            date,
+            time.start.format("%H:%M"),
+            time.end.format("%H:%M"),
+            time.zone.name(),
+            locale.weekday_name(start.weekday()),
+            start.format("%H:%M"),
+            end.format("%H:%M"),
+            zone.name(),
+        ),
+        (Some(time), _, _) => format!(
+            "{} {}-{} {}",
+            date,
+            time.start.format("%H:%M"),
+            time.end.format("%H:%M"),
+            time.zone.name(),
+        ),
+        _ => format!("{} ({})", date, strings.all_day),
+    };
+
+    let when = match event.time.and_then(|time| time.reminder) {
+        Some(minutes) => format!("{when} · {} {minutes} {}", strings.reminder_short, strings.minutes_before),
+        None => when,
+    };
+
+    let category = locale.category_name(event.category);
+
+    let repeats = match event.recurrence {
+        Some(recurrence) => text(format!("{} · {}", category, locale.describe_recurrence(recurrence, event.date))).size(14),
+        None => text(category).size(14),
+    };
+
+    let filed_in = row![
+        text(strings.calendar).size(14).style(palette.muted),
+        pick_list(
+            calendars.to_vec(),
+            calendars::find(calendars, event.calendar).cloned(),
+            |calendar| EventMessage::CalendarSelected(calendar.id),
+        ),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let shift = |label: &str, minutes: i64| {
+        button(text(label.to_string()).size(14))
+            .style(theme::Button::Text)
+            .padding(2)
+            .on_press(EventMessage::Moved(minutes))
+    };
+
+    let moves = if event.time.is_some() {
+        row![
+            text(strings.move_event).size(14).style(palette.muted),
+            shift(strings.earlier_day, -DAY),
+            shift("−30′", -30),
+            shift("+30′", 30),
+            shift(strings.later_day, DAY),
+        ]
+    } else {
+        row![
+            text(strings.move_event).size(14).style(palette.muted),
+            shift(strings.earlier_day, -DAY),
+            shift(strings.later_day, DAY),
+        ]
+    };
+
+    let overlaps = match overlaps {
+        [] => text(""),
+        titles => text(format!("⚠ {}: {}", strings.overlaps_with, titles.join(", "))).size(14).style(palette.danger),
+    };
+
+    column![
+        text(format!("{}: {when}", strings.date)).size(16),
+        overlaps,
+        repeats,
+        moves.spacing(5).align_items(Alignment::Center),
+        filed_in,
+        field(strings.description, strings.description_placeholder, &event.description, EventMessage::DescriptionEdited),
+        field(strings.location, strings.location_placeholder, &event.location, EventMessage::LocationEdited),
+        field(strings.link, "https://...", &event.url, EventMessage::UrlEdited),
+        field(strings.notes, strings.notes_placeholder, &event.notes, EventMessage::NotesEdited),
+    ]
+    .spacing(10)
+    .into()
+}
@@ -0,0 +1,120 @@
+//! Subscribed calendars, fetched again at their refresh interval and shown
+//! read-only beside the calendar's own events.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use calendar_core::{Calendar, Event};
+use chrono::{DateTime, Local, NaiveDate};
+use iced::{
+    subscription, theme,
+    widget::{button, column, row, text, text_input, Column},
+    Alignment, Element, Length, Subscription,
+};
+
+use crate::{config::Feed, delete_icon, i18n::Locale, style::Palette, Message};
+
+/// What is known of a subscribed feed
+#[derive(Debug, Default)]
+pub struct FeedState {
+    /// Events of the latest successful fetch, kept when a later one fails
+    pub calendar: Calendar,
+    pub fetched_at: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+/// Events of `feed` now, then again every `refresh_minutes`, with the
+/// source they come from
+pub fn watch(feed: &Feed) -> Subscription<(String, Result<Vec<Event>, String>)> {
+    let source = feed.source.clone();
+    let interval = Duration::from_secs(u64::from(feed.refresh_minutes.max(1)) * 60);
+
+    subscription::unfold((source.clone(), interval), true, move |first| {
+        let source = source.clone();
+
+        async move {
+            if !first {
+                async_std::task::sleep(interval).await;
+            }
+
+            let fetched = source.clone();
+            let result = async_std::task::spawn_blocking(move || calendar_sync::feed::fetch(&fetched).map_err(|error| error.to_string())).await;
+
+            (Some((source, result)), false)
+        }
+    })
+}
+
+/// Subscribed events between `from` and `to`, as their titles by day, timed
+/// ones starting with the hour in the calendar's zone
+pub fn by_day(feeds: &BTreeMap<String, FeedState>, from: NaiveDate, to: NaiveDate) -> BTreeMap<NaiveDate, Vec<String>> {
+    let mut lines: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+
+    for feed in feeds.values() {
+        let calendar = &feed.calendar;
+
+        for (day, positions) in calendar.occurrences(from, to) {
+            for i in positions {
+                let event = &calendar.events()[i];
+
+                let line = match event.start_in(calendar.zone()) {
+                    Some(start) => format!("{} {}", start.format("%H:%M"), event.title),
+                    None => event.title.clone(),
+                };
+
+                lines.entry(day).or_default().push(line);
+            }
+        }
+    }
+
+    lines
+}
+
+/// Subscribed feeds with how their last fetch went, and the field to
+/// subscribe to another
+pub fn view<'a>(subscriptions: &[Feed], feeds: &BTreeMap<String, FeedState>, new_source: &str, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let listed = subscriptions.iter().fold(Column::new().spacing(2), |listed, subscription| {
+        let state = feeds.get(&subscription.source);
+
+        let status = match state {
+            Some(FeedState { error: Some(error), .. }) => text(format!("{} {error}", strings.fetch_failed)).style(palette.danger),
+            Some(FeedState { fetched_at: Some(at), .. }) => {
+                text(format!("{} {}", strings.updated_at, at.format("%H:%M"))).style(palette.muted)
+            }
+            _ => text(strings.fetching).style(palette.muted),
+        };
+
+        listed.push(
+            row![
+                text(subscription.name.clone()).size(14).style(palette.subscribed),
+                status.size(14).width(Length::Fill),
+                text(format!("{} {} {}", strings.every, subscription.refresh_minutes, strings.minutes_short)).size(14).style(palette.muted),
+                button(delete_icon())
+                    .style(theme::Button::Text)
+                    .on_press(Message::Unsubscribe(subscription.source.clone())),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        )
+    });
+
+    column![
+        listed,
+        row![
+            text(strings.subscriptions).size(14),
+            text_input(strings.feed_placeholder, new_source, Message::FeedSourceChanged)
+                .on_submit(Message::Subscribe)
+                .padding(5),
+            button(strings.subscribe)
+                .style(theme::Button::Text)
+                .on_press(Message::Subscribe),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(5)
+    .padding(5)
+    .into()
+}
@@ -0,0 +1,564 @@
+//! Translations of the interface and locale-aware date formatting.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use calendar_core::{holidays::Country, quick_add::QuickAdd, Category, Recurrence};
+use calendar_render::Orientation;
+use calendar_sync::SyncReport;
+
+use crate::style::ThemeChoice;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    Vietnamese,
+}
+
+/// Every string of the interface, in one language
+pub struct Strings {
+    pub create_event: &'static str,
+    pub create_new_event: &'static str,
+    pub title_placeholder: &'static str,
+    pub edit_placeholder: &'static str,
+    pub set_date: &'static str,
+    pub set_time: &'static str,
+    pub date: &'static str,
+    pub time: &'static str,
+    pub all_day: &'static str,
+    pub every_year_lunar: &'static str,
+    pub cancel: &'static str,
+    pub ok: &'static str,
+    pub delete: &'static str,
+    pub close: &'static str,
+    pub description: &'static str,
+    pub description_placeholder: &'static str,
+    pub location: &'static str,
+    pub location_placeholder: &'static str,
+    pub link: &'static str,
+    pub notes: &'static str,
+    pub notes_placeholder: &'static str,
+    pub prev_month: &'static str,
+    pub next_month: &'static str,
+    pub prev_week: &'static str,
+    pub next_week: &'static str,
+    pub prev_day: &'static str,
+    pub next_day: &'static str,
+    pub month: &'static str,
+    pub week: &'static str,
+    pub day: &'static str,
+    pub search_placeholder: &'static str,
+    pub no_matching_events: &'static str,
+    pub time_zone: &'static str,
+    pub second_zone: &'static str,
+    pub lunar_dates: &'static str,
+    pub holidays: &'static str,
+    pub export_ics: &'static str,
+    pub export_pdf: &'static str,
+    pub printed_page: &'static str,
+    pub exporting: &'static str,
+    pub exported_to: &'static str,
+    pub export_failed: &'static str,
+    pub language: &'static str,
+    pub theme: &'static str,
+    pub settings: &'static str,
+    pub data_file: &'static str,
+    pub first_weekday: &'static str,
+    pub locale_default: &'static str,
+    pub event_duration: &'static str,
+    pub reminder: &'static str,
+    pub no_reminder: &'static str,
+    pub minutes_before: &'static str,
+    pub reminder_short: &'static str,
+    pub window_width: &'static str,
+    pub window_height: &'static str,
+    pub restart_needed: &'static str,
+    pub feeds_served_on: &'static str,
+    pub feeds_on_network: &'static str,
+    pub feeds_open_to_network: &'static str,
+    pub settings_read_failed: &'static str,
+    pub settings_invalid: &'static str,
+    pub settings_save_failed: &'static str,
+    pub settings_unsaved: &'static str,
+    pub not_a_number: &'static str,
+    pub invalid_event_duration: &'static str,
+    pub invalid_reminder: &'static str,
+    pub invalid_working_hours: &'static str,
+    pub invalid_window_size: &'static str,
+    pub missing_folder: &'static str,
+    pub invalid_refresh: &'static str,
+    pub short_api_token: &'static str,
+    pub unknown_country: &'static str,
+    pub reload: &'static str,
+    pub save: &'static str,
+    pub loading: &'static str,
+    pub conflict_title: &'static str,
+    pub conflict_body: &'static str,
+    pub events_here: &'static str,
+    pub events_on_disk: &'static str,
+    pub keep_mine: &'static str,
+    pub take_theirs: &'static str,
+    pub merge: &'static str,
+    pub all_saved: &'static str,
+    pub unsaved_changes: &'static str,
+    pub saving: &'static str,
+    pub save_failed: &'static str,
+    pub not_saved: &'static str,
+    pub load_failed: &'static str,
+    pub changes_kept: &'static str,
+    pub retry: &'static str,
+    pub close_unsaved: &'static str,
+    pub sync: &'static str,
+    pub syncing: &'static str,
+    pub sync_failed: &'static str,
+    pub subscriptions: &'static str,
+    pub subscribe: &'static str,
+    pub feed_placeholder: &'static str,
+    pub fetching: &'static str,
+    pub updated_at: &'static str,
+    pub fetch_failed: &'static str,
+    pub every: &'static str,
+    pub minutes_short: &'static str,
+    pub find_time: &'static str,
+    pub new_event: &'static str,
+    pub days_ahead: &'static str,
+    pub weekends: &'static str,
+    pub find: &'static str,
+    pub clear: &'static str,
+    pub no_free_slots: &'static str,
+    pub free_slots_found: &'static str,
+    pub move_event: &'static str,
+    pub earlier_day: &'static str,
+    pub later_day: &'static str,
+    pub overlaps_with: &'static str,
+    pub conflicts_found: &'static str,
+    pub show: &'static str,
+    pub hide: &'static str,
+    pub calendars: &'static str,
+    pub calendar: &'static str,
+    pub calendar_placeholder: &'static str,
+    pub add_calendar: &'static str,
+    months: [&'static str; 12],
+    weekdays: [&'static str; 7],
+}
+
+const ENGLISH: Strings = Strings {
+    create_event: "Create event",
+    create_new_event: "Create a new event",
+    title_placeholder: "What needs to be done?",
+    edit_placeholder: "Describe your event...",
+    set_date: "Set Date",
+    set_time: "Set Time",
+    date: "Date",
+    time: "Time",
+    all_day: "All day",
+    every_year_lunar: "Every year on lunar",
+    cancel: "Cancel",
+    ok: "Ok",
+    delete: "Delete",
+    close: "Close",
+    description: "Description",
+    description_placeholder: "What is it about?",
+    location: "Location",
+    location_placeholder: "Where?",
+    link: "Link",
+    notes: "Notes",
+    notes_placeholder: "Anything else...",
+    prev_month: "prev month",
+    next_month: "next month",
+    prev_week: "prev week",
+    next_week: "next week",
+    prev_day: "prev day",
+    next_day: "next day",
+    month: "Month",
+    week: "Week",
+    day: "Day",
+    search_placeholder: "Search events...",
+    no_matching_events: "No matching events",
+    time_zone: "Time zone",
+    second_zone: "Second zone",
+    lunar_dates: "Lunar dates",
+    holidays: "Holidays",
+    export_ics: "Export .ics",
+    export_pdf: "Export PDF",
+    printed_page: "Printed page",
+    exporting: "Exporting...",
+    exported_to: "Exported to",
+    export_failed: "Export failed",
+    language: "Language",
+    theme: "Theme",
+    settings: "Settings",
+    data_file: "Calendar file",
+    first_weekday: "First day of the week",
+    locale_default: "Language default",
+    event_duration: "Default event duration (minutes)",
+    reminder: "Reminder (minutes before)",
+    no_reminder: "None",
+    minutes_before: "min before",
+    reminder_short: "Reminder",
+    window_width: "Window width",
+    window_height: "Window height",
+    restart_needed: "The calendar file and window size apply after a restart",
+    feeds_served_on: "Calendar feeds are served on",
+    feeds_on_network: "Devices on the network holding the key can read every event, notes included",
+    feeds_open_to_network: "Anyone on the network can read every event, notes included: set a key or listen on 127.0.0.1",
+    settings_read_failed: "Could not read the settings:",
+    settings_invalid: "Invalid settings file:",
+    settings_save_failed: "Could not save the settings:",
+    settings_unsaved: "Changes are not saved until the settings file is fixed and reloaded",
+    not_a_number: "must be a whole number",
+    invalid_event_duration: "Event duration must be between 1 and 1440 minutes",
+    invalid_reminder: "Reminders can be set at most 10080 minutes (a week) ahead",
+    invalid_working_hours: "Working hours must end after they start",
+    invalid_window_size: "Window size must be at least 400×300",
+    missing_folder: "Folder does not exist:",
+    invalid_refresh: "Subscriptions must be refreshed at most once a minute",
+    short_api_token: "The API needs a token of at least 16 characters, it is off until then",
+    unknown_country: "No public holidays are known for the country code",
+    reload: "Reload from file",
+    save: "Save",
+    loading: "Loading...",
+    conflict_title: "Calendar changed on disk",
+    conflict_body: "The calendar file was changed by another program while you had unsaved changes.",
+    events_here: "events here",
+    events_on_disk: "events on disk",
+    keep_mine: "Keep mine",
+    take_theirs: "Take theirs",
+    merge: "Merge",
+    all_saved: "All changes saved",
+    unsaved_changes: "Unsaved changes",
+    saving: "Saving...",
+    save_failed: "Could not save the calendar:",
+    not_saved: "Not saved",
+    load_failed: "Could not load the calendar:",
+    changes_kept: "Changes are kept here until the file can be read",
+    retry: "Retry",
+    close_unsaved: "Close without saving",
+    sync: "Sync",
+    syncing: "Syncing...",
+    sync_failed: "Sync failed:",
+    subscriptions: "Subscriptions",
+    subscribe: "Subscribe",
+    feed_placeholder: "URL or file of an .ics calendar",
+    fetching: "Fetching...",
+    updated_at: "Updated at",
+    fetch_failed: "Could not fetch:",
+    every: "every",
+    minutes_short: "min",
+    find_time: "Find a time",
+    new_event: "New event",
+    days_ahead: "days",
+    weekends: "Weekends",
+    find: "Find",
+    clear: "Clear",
+    no_free_slots: "No free time found",
+    free_slots_found: "free slots, pick one in the week view",
+    move_event: "Move",
+    earlier_day: "− 1 day",
+    later_day: "+ 1 day",
+    overlaps_with: "Overlaps",
+    conflicts_found: "overlapping events shown",
+    show: "Show",
+    hide: "Hide",
+    calendars: "Calendars",
+    calendar: "Calendar",
+    calendar_placeholder: "New calendar",
+    add_calendar: "Add",
+    months: [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+};
+
+const VIETNAMESE: Strings = Strings {
+    create_event: "Tạo sự kiện",
+    create_new_event: "Tạo sự kiện mới",
+    title_placeholder: "Bạn cần làm gì?",
+    edit_placeholder: "Mô tả sự kiện...",
+    set_date: "Chọn ngày",
+    set_time: "Chọn giờ",
+    date: "Ngày",
+    time: "Giờ",
+    all_day: "Cả ngày",
+    every_year_lunar: "Hằng năm vào ngày âm lịch",
+    cancel: "Hủy",
+    ok: "Đồng ý",
+    delete: "Xóa",
+    close: "Đóng",
+    description: "Mô tả",
+    description_placeholder: "Sự kiện về việc gì?",
+    location: "Địa điểm",
+    location_placeholder: "Ở đâu?",
+    link: "Liên kết",
+    notes: "Ghi chú",
+    notes_placeholder: "Điều gì khác...",
+    prev_month: "tháng trước",
+    next_month: "tháng sau",
+    prev_week: "tuần trước",
+    next_week: "tuần sau",
+    prev_day: "hôm trước",
+    next_day: "hôm sau",
+    month: "Tháng",
+    week: "Tuần",
+    day: "Ngày",
+    search_placeholder: "Tìm sự kiện...",
+    no_matching_events: "Không có sự kiện phù hợp",
+    time_zone: "Múi giờ",
+    second_zone: "Múi giờ phụ",
+    lunar_dates: "Ngày âm lịch",
+    holidays: "Ngày lễ",
+    export_ics: "Xuất .ics",
+    export_pdf: "Xuất PDF",
+    printed_page: "Trang in",
+    exporting: "Đang xuất...",
+    exported_to: "Đã xuất ra",
+    export_failed: "Xuất thất bại",
+    language: "Ngôn ngữ",
+    theme: "Giao diện",
+    settings: "Cài đặt",
+    data_file: "Tệp lịch",
+    first_weekday: "Ngày đầu tuần",
+    locale_default: "Theo ngôn ngữ",
+    event_duration: "Thời lượng sự kiện mặc định (phút)",
+    reminder: "Nhắc trước (phút)",
+    no_reminder: "Không nhắc",
+    minutes_before: "phút trước",
+    reminder_short: "Nhắc",
+    window_width: "Chiều rộng cửa sổ",
+    window_height: "Chiều cao cửa sổ",
+    restart_needed: "Tệp lịch và kích thước cửa sổ có hiệu lực sau khi khởi động lại",
+    feeds_served_on: "Nguồn lịch được phát tại",
+    feeds_on_network: "Thiết bị trong mạng có khóa có thể đọc mọi sự kiện, kể cả ghi chú",
+    feeds_open_to_network: "Mọi người trong mạng có thể đọc mọi sự kiện, kể cả ghi chú: hãy đặt khóa hoặc chỉ phát tại 127.0.0.1",
+    settings_read_failed: "Không thể đọc cài đặt:",
+    settings_invalid: "Tệp cài đặt không hợp lệ:",
+    settings_save_failed: "Không thể lưu cài đặt:",
+    settings_unsaved: "Thay đổi chưa được lưu cho đến khi tệp cài đặt được sửa và tải lại",
+    not_a_number: "phải là số nguyên",
+    invalid_event_duration: "Thời lượng sự kiện phải từ 1 đến 1440 phút",
+    invalid_reminder: "Chỉ có thể nhắc trước tối đa 10080 phút (một tuần)",
+    invalid_working_hours: "Giờ làm việc phải kết thúc sau khi bắt đầu",
+    invalid_window_size: "Cửa sổ phải có kích thước tối thiểu 400×300",
+    missing_folder: "Thư mục không tồn tại:",
+    invalid_refresh: "Không thể làm mới lịch đăng ký nhiều hơn một lần mỗi phút",
+    short_api_token: "API cần mã truy cập dài ít nhất 16 ký tự, API tắt cho đến khi có",
+    unknown_country: "Không có dữ liệu ngày lễ cho mã quốc gia",
+    reload: "Tải lại từ tệp",
+    save: "Lưu",
+    loading: "Đang tải...",
+    conflict_title: "Lịch đã thay đổi trên đĩa",
+    conflict_body: "Tệp lịch đã bị chương trình khác thay đổi trong khi bạn có thay đổi chưa lưu.",
+    events_here: "sự kiện tại đây",
+    events_on_disk: "sự kiện trên đĩa",
+    keep_mine: "Giữ bản của tôi",
+    take_theirs: "Dùng bản trên đĩa",
+    merge: "Gộp",
+    all_saved: "Đã lưu mọi thay đổi",
+    unsaved_changes: "Có thay đổi chưa lưu",
+    saving: "Đang lưu...",
+    save_failed: "Không thể lưu lịch:",
+    not_saved: "Chưa lưu",
+    load_failed: "Không thể tải lịch:",
+    changes_kept: "Các thay đổi được giữ tại đây cho đến khi đọc được tệp",
+    retry: "Thử lại",
+    close_unsaved: "Đóng mà không lưu",
+    sync: "Đồng bộ",
+    syncing: "Đang đồng bộ...",
+    sync_failed: "Đồng bộ thất bại:",
+    subscriptions: "Lịch đăng ký",
+    subscribe: "Đăng ký",
+    feed_placeholder: "URL hoặc tệp lịch .ics",
+    fetching: "Đang tải...",
+    updated_at: "Cập nhật lúc",
+    fetch_failed: "Không thể tải:",
+    every: "mỗi",
+    minutes_short: "phút",
+    find_time: "Tìm giờ trống",
+    new_event: "Sự kiện mới",
+    days_ahead: "ngày tới",
+    weekends: "Cuối tuần",
+    find: "Tìm",
+    clear: "Xóa",
+    no_free_slots: "Không tìm thấy giờ trống",
+    free_slots_found: "khoảng trống, chọn một trong lịch tuần",
+    move_event: "Dời",
+    earlier_day: "− 1 ngày",
+    later_day: "+ 1 ngày",
+    overlaps_with: "Trùng giờ với",
+    conflicts_found: "chỗ trùng giờ đang hiển thị",
+    show: "Xem",
+    hide: "Ẩn",
+    calendars: "Lịch",
+    calendar: "Lịch",
+    calendar_placeholder: "Lịch mới",
+    add_calendar: "Thêm",
+    months: [
+        "Tháng Một", "Tháng Hai", "Tháng Ba", "Tháng Tư", "Tháng Năm", "Tháng Sáu",
+        "Tháng Bảy", "Tháng Tám", "Tháng Chín", "Tháng Mười", "Tháng Mười Một", "Tháng Mười Hai",
+    ],
+    weekdays: ["T2", "T3", "T4", "T5", "T6", "T7", "CN"],
+};
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::English, Locale::Vietnamese];
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::Vietnamese => &VIETNAMESE,
+        }
+    }
+
+    /// Name of the month of `date`
+    pub fn month_name(self, date: NaiveDate) -> &'static str {
+        self.strings().months[date.month0() as usize]
+    }
+
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        self.strings().weekdays[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Day weeks start on, unless set otherwise
+    pub fn first_weekday(self) -> Weekday {
+        match self {
+            Locale::English => Weekday::Sun,
+            Locale::Vietnamese => Weekday::Mon,
+        }
+    }
+
+    /// format date Oct 18, 2026 or 18/10/2026
+    pub fn format_date(self, date: NaiveDate) -> String {
+        match self {
+            Locale::English => date.format("%b %-d, %Y").to_string(),
+            Locale::Vietnamese => date.format("%d/%m/%Y").to_string(),
+        }
+    }
+
+    pub fn category_name(self, category: Category) -> &'static str {
+        match (self, category) {
+            (Locale::English, Category::Todo) => "Todo",
+            (Locale::English, Category::Reminder) => "Reminder",
+            (Locale::English, Category::Holiday) => "Holiday",
+            (Locale::Vietnamese, Category::Todo) => "Việc cần làm",
+            (Locale::Vietnamese, Category::Reminder) => "Nhắc nhở",
+            (Locale::Vietnamese, Category::Holiday) => "Ngày lễ",
+        }
+    }
+
+    /// How an event first happening on `start` repeats
+    pub fn describe_recurrence(self, recurrence: Recurrence, start: NaiveDate) -> String {
+        let rule = match (self, recurrence) {
+            (_, Recurrence::LunarYearly { month, day }) => return format!("{} {day}/{month}", self.strings().every_year_lunar),
+            (Locale::English, Recurrence::Daily { .. }) => "Every day".to_string(),
+            (Locale::English, Recurrence::Weekdays { .. }) => "Every weekday".to_string(),
+            (Locale::English, Recurrence::Weekly { .. }) => format!("Every {}", self.weekday_name(start.weekday())),
+            (Locale::Vietnamese, Recurrence::Daily { .. }) => "Hằng ngày".to_string(),
+            (Locale::Vietnamese, Recurrence::Weekdays { .. }) => "Thứ hai đến thứ sáu".to_string(),
+            (Locale::Vietnamese, Recurrence::Weekly { .. }) => format!("Hằng tuần vào {}", self.weekday_name(start.weekday())),
+        };
+
+        match (self, recurrence.until()) {
+            (Locale::English, Some(until)) => format!("{rule} until {}", self.format_date(until)),
+            (Locale::Vietnamese, Some(until)) => format!("{rule} đến {}", self.format_date(until)),
+            (_, None) => rule,
+        }
+    }
+
+    /// Date, time and repeat rule read from a typed phrase, in one line
+    pub fn describe_quick_add(self, parsed: &QuickAdd) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(date) = parsed.date {
+            parts.push(self.format_date(date));
+        }
+
+        match (parsed.start, parsed.end) {
+            (Some(start), Some(end)) => parts.push(format!("{}–{}", start.format("%H:%M"), end.format("%H:%M"))),
+            (Some(start), None) => parts.push(start.format("%H:%M").to_string()),
+            _ => {}
+        }
+
+        if let (Some(recurrence), Some(date)) = (parsed.recurrence, parsed.date) {
+            parts.push(self.describe_recurrence(recurrence, date));
+        }
+
+        parts.join(" · ")
+    }
+
+    /// Outcome of a CalDAV sync, in one line
+    pub fn describe_sync(self, report: &SyncReport) -> String {
+        let removed = report.removed_here + report.removed_there;
+
+        match self {
+            Locale::English => format!(
+                "Synced: {} received, {} sent, {removed} removed, {} conflicts",
+                report.received,
+                report.sent,
+                report.conflicts.len()
+            ),
+            Locale::Vietnamese => format!(
+                "Đã đồng bộ: nhận {}, gửi {}, xóa {removed}, xung đột {}",
+                report.received,
+                report.sent,
+                report.conflicts.len()
+            ),
+        }
+    }
+
+    pub fn theme_name(self, theme: ThemeChoice) -> &'static str {
+        match (self, theme) {
+            (Locale::English, ThemeChoice::Light) => "Light",
+            (Locale::English, ThemeChoice::Dark) => "Dark",
+            (Locale::English, ThemeChoice::HighContrast) => "High contrast",
+            (Locale::English, ThemeChoice::Custom) => "Custom",
+            (Locale::Vietnamese, ThemeChoice::Light) => "Sáng",
+            (Locale::Vietnamese, ThemeChoice::Dark) => "Tối",
+            (Locale::Vietnamese, ThemeChoice::HighContrast) => "Tương phản cao",
+            (Locale::Vietnamese, ThemeChoice::Custom) => "Tùy chỉnh",
+        }
+    }
+
+    pub fn orientation_name(self, orientation: Orientation) -> &'static str {
+        match (self, orientation) {
+            (Locale::English, Orientation::Portrait) => "Portrait",
+            (Locale::English, Orientation::Landscape) => "Landscape",
+            (Locale::Vietnamese, Orientation::Portrait) => "Dọc",
+            (Locale::Vietnamese, Orientation::Landscape) => "Ngang",
+        }
+    }
+
+    pub fn country_name(self, country: Country) -> &'static str {
+        match (self, country) {
+            (Locale::English, Country::Vietnam) => "Vietnam",
+            (Locale::English, Country::UnitedStates) => "United States",
+            (Locale::English, Country::Germany) => "Germany",
+            (Locale::Vietnamese, Country::Vietnam) => "Việt Nam",
+            (Locale::Vietnamese, Country::UnitedStates) => "Hoa Kỳ",
+            (Locale::Vietnamese, Country::Germany) => "Đức",
+        }
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Locale::English => "English",
+            Locale::Vietnamese => "Tiếng Việt",
+        })
+    }
+}
+
+/// A value listed under a translated label, for pick lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Labeled<T> {
+    pub value: T,
+    pub label: &'static str,
+}
+
+impl<T> std::fmt::Display for Labeled<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label)
+    }
+}
@@ -1,560 +1,1474 @@
-use chrono::{prelude::*, format::Fixed};
+use chrono::{prelude::*, Duration};
+use chrono_tz::Tz;
 use iced::{
     alignment,
-    widget::{column, container, row, text, Button, Column, Container, Row, Text, button, horizontal_space, text_input, scrollable},
-    Application, Background, Color, Command, Element, Length, Settings, color, theme, Alignment, Theme, Font,
+    widget::{column, container, row, text, Button, Column, Container, Row, Text, button, horizontal_space, text_input, scrollable, pick_list, checkbox},
+    Application, Command, Element, Length, Settings, Subscription, theme, Alignment, Theme, Font,
 };
 
 use iced_aw::{Card, Modal};
 use iced_aw::{date_picker::Date as DateModal, DatePicker};
-
-use iced::window;
+use iced_aw::{time_picker::Time as TimeModal, TimePicker};
+
+use iced::{subscription, window};
+
+mod api;
+mod caldav;
+mod cli;
+mod config;
+mod error_log;
+mod event_view;
+mod feeds;
+mod i18n;
+mod print;
+mod publish;
+mod settings;
+mod sidebar;
+mod slots;
+mod style;
+mod timeline;
+mod watcher;
+mod writer;
+
+use calendar_core::{
+    calendars, conflicts, date,
+    free_time::Slot,
+    holidays::{self, Holiday},
+    ics, lunar, merge, quick_add,
+    storage::{LoadError, SaveError},
+    zone, Calendar, Category, Event, EventTime, NamedCalendar, Recurrence, SavedState,
+};
+use calendar_sync::SyncReport;
+use config::{Config, ConfigError};
+use event_view::EventMessage;
+use i18n::{Labeled, Locale};
+use settings::{SettingsForm, SettingsMessage};
+use style::{Palette, ThemeChoice};
 
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
 
 static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 
 pub fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
+    let (config, config_errors) = Config::load();
+
     CalendarApp::run(Settings {
         window: window::Settings {
-            size: (1200, 850),
+            size: (config.window_width, config.window_height),
             ..window::Settings::default()
         },
-        ..Settings::default()
+        // Closing waits for the calendar file to be written
+        exit_on_close_request: false,
+        ..Settings::with_flags((config, config_errors))
     })
 }
 
 #[derive(Debug)]
 enum CalendarApp {
-    Loading,
+    /// Reading the calendar file, with the settings it was found from
+    Loading(Config, Vec<ConfigError>),
     Loaded(State),
 }
 
 #[derive(Debug, Default)]
 struct State {
-    date: Date,
-    events: Vec<Event>,
+    date: NaiveDate,
+    calendar: Calendar,
+    /// Named calendars the events are filed in
+    calendars: Vec<NamedCalendar>,
+    /// Events as last read from the calendar file or known to be written
+    /// to it, to tell changes made elsewhere from ours
+    on_disk: Vec<Event>,
+    /// Events handed to the writer and not yet known to be written, oldest
+    /// first, to tell our own writes from changes made elsewhere when the
+    /// file is read back
+    writing: Vec<Vec<Event>>,
+    /// Events found on disk while ours had unsaved changes, until the user
+    /// picks which to keep
+    conflict: Option<Vec<Event>>,
     show_modal: bool,
     show_picker: bool,
-    saving: bool,
+    /// Changes not yet handed to the writer, made before it started or
+    /// during a conflict
     dirty: bool,
+    writer: Option<writer::Writer>,
+    /// Server of the iCalendar feeds, once it listens
+    publisher: Option<publish::Publisher>,
+    save_status: SaveStatus,
+    /// Why the calendar file could not be read. Saving waits until it can,
+    /// so the file is not replaced by what the window holds.
+    load_error: Option<LoadError>,
+    /// The window was asked to close but the last changes could not be
+    /// saved, so closing is offered without them
+    close_failed: bool,
     input_value: String,
     picked_date: DateModal,
+    selected_event: Option<usize>,
+    /// Position of the event whose title is being edited
+    editing: Option<usize>,
+    search_query: String,
+    highlighted_event: Option<usize>,
+    view_mode: ViewMode,
+    secondary_zone: Option<Tz>,
+    show_time_picker: bool,
+    picked_time: Option<NaiveTime>,
+    event_zone: Option<Tz>,
+    repeat_lunar: bool,
+    show_lunar: bool,
+    picked_category: Category,
+    /// Calendar new events are filed in, the first one when it is gone
+    picked_calendar: u64,
+    /// Name typed for a new calendar
+    calendar_name: String,
+    export_status: Option<String>,
+    /// Outcome of the latest CalDAV sync
+    sync_status: Option<String>,
+    /// A sync is running; saving waits for it, as it rewrites the file
+    syncing: bool,
+    /// The calendar file chosen in the settings is being read; saving
+    /// waits, as what the window holds belongs to the previous one
+    loading: bool,
+    /// Subscribed calendars by source
+    feeds: std::collections::BTreeMap<String, feeds::FeedState>,
+    /// Source typed to subscribe to
+    feed_source: String,
+    /// Search for free time, whose slots the week view offers
+    slot_finder: slots::SlotFinder,
+    /// Overlapping events of the range shown are listed
+    show_conflicts: bool,
+    config: Config,
+    /// Problems found in the config file, or met saving it
+    config_errors: Vec<ConfigError>,
+    settings: Option<SettingsForm>,
 }
 
-
-#[derive(Debug, Clone)]
-enum Message {
-    Loaded(Result<SavedState, LoadError>),
-    Saved(Result<(), SaveError>),
-    TitleInputChanged(String),
-    CreateEvent,
-    OpenModal,
-    CloseModal,
-    ChooseDate,
-    SubmitDate(DateModal),
-    CancelDate,
-    EventMessage(usize, EventMessage),
-    NextMonth,
-    PrevMonth,
+/// What became of the latest changes
+#[derive(Debug, Clone, Default)]
+enum SaveStatus {
+    #[default]
+    Saved,
+    /// Waiting for edits to pause
+    Pending,
+    Saving,
+    Failed(SaveError),
 }
 
-
-#[derive(Debug, Clone, Default, PartialEq)]
-struct Date {
-    year: i32,
-    month: u32,
-    day: u32,
+/// Way out of a conflict between the window and the calendar file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resolution {
+    KeepMine,
+    TakeTheirs,
+    Merge,
 }
 
-impl Date {
-    pub fn today() -> Self {
-        let today: DateTime<Local> = Local::now();
-        Self {
-            year: today.year(),
-            month: today.month(),
-            day: today.day(),
-        }
-    }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ViewMode {
+    #[default]
+    Month,
+    Week,
+    Day,
+}
 
-    /// Check leap year
-    fn check_leap_year(&self, year: i32) -> bool {
-        let mod4 = year % 4 == 0;
-        let mod100 = year % 100 == 0;
-        let mod400 = year % 400 == 0;
 
-        mod4 && (!mod100 || mod400)
+impl State {
+    /// Time zone the calendar is displayed in
+    fn zone(&self) -> Tz {
+        self.calendar.zone()
     }
 
-    /// Check how many days of the current month
-    fn number_days_month(&self, month: u32, year: i32) -> u32 {
-        match month {
-            4 | 6 | 9 | 11 => 30,
-            2 => {
-                if self.check_leap_year(year) {
-                    29
-                } else {
-                    28
-                }
-            }
-            _ => 31,
-        }
-    }
+    /// Replace the events, by the ones of the calendar file or a merge
+    fn take_events(&mut self, events: Vec<Event>) {
+        self.calendar = Calendar::new(events, self.zone());
 
-    /// Check how many days of the last month
-    fn number_days_pred_month(&self, month: u32, year: i32) -> u32 {
-        if month == 1 {
-            self.number_days_month(12, year - 1)
-        } else {
-            self.number_days_month(month - 1, year)
-        }
-    }
+        // Positions may no longer match
+        self.selected_event = None;
+        self.editing = None;
+        self.highlighted_event = None;
 
-    /// Check how many days of the next month
-    fn number_days_next_month(&self, month: u32, year: i32) -> u32 {
-        if month == 12 {
-            self.number_days_month(1, year + 1)
-        } else {
-            self.number_days_month(month + 1, year)
-        }
+        self.publish();
     }
 
-    /// format date 2022-12-05
-    pub fn format_date(&self) -> String {
-        let dt = Utc.ymd(self.year, self.month, self.day);
-        dt.format("%Y-%m-%d").to_string()
+    /// First day of the week shown, or of the week of the day shown
+    fn week_start(&self) -> NaiveDate {
+        self.date - Duration::days(i64::from(date::days_since(self.date.weekday(), self.config.first_weekday())))
     }
 
+    /// Event the create dialog makes of what it holds, if it has a title.
+    /// What the title says of the date and time wins over the pickers.
+    fn new_event(&self) -> Option<Event> {
+        let parsed = quick_add::parse(&self.input_value, date::today(self.zone()));
 
-    pub const fn from_ymd(year: i32, month: u32, day: u32) -> Self {
-        Self { year, month, day }
-    }
-}
-
+        let picked_date = parsed.date.or_else(|| NaiveDate::from_ymd_opt(
+            self.picked_date.year,
+            self.picked_date.month,
+            self.picked_date.day,
+        ));
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Event {
-    title: String,
-    date: String,
+        let time = parsed.start.or(self.picked_time).map(|start| EventTime {
+            start,
+            end: parsed.end.unwrap_or_else(|| start.overflowing_add_signed(Duration::minutes(i64::from(self.config.event_duration))).0),
+            zone: self.event_zone.unwrap_or_else(|| self.zone()),
+            reminder: self.config.reminder,
+        });
 
+        let title = if parsed.title.is_empty() { self.input_value.clone() } else { parsed.title };
 
-    #[serde(skip)]
-    state: EventState,
-}
+        picked_date.filter(|_| !self.input_value.is_empty()).map(|picked_date| {
+            let recurrence = self.repeat_lunar.then(|| Recurrence::lunar_yearly(picked_date)).or(parsed.recurrence);
 
-#[derive(Debug, Clone)]
-pub enum EventState {
-    Idle,
-    Editing,
-}
+            let mut event = Event::new(title, picked_date, time, recurrence, self.picked_category);
+            event.calendar = picked_calendar(&self.calendars, self.picked_calendar).map_or(0, |calendar| calendar.id);
 
-impl Default for EventState {
-    fn default() -> Self {
-        Self::Idle
+            event
+        })
     }
-}
 
+    /// Give the feeds the events as they are now
+    fn publish(&self) {
+        if let Some(publisher) = &self.publisher {
+            publisher.update(self.calendar.events(), &self.calendars);
+        }
+    }
 
-#[derive(Debug, Clone)]
-pub enum EventMessage {
-    Edit,
-    TitleEdited(String),
-    FinishEdition,
-    Delete,
-}
+    /// Hand the events and display settings to the writer, after a change
+    /// to any of them
+    fn persist(&mut self) {
+        // Subscribers see changes before they are saved
+        self.publish();
+
+        // Saving during a conflict would overwrite the changes the user has
+        // yet to choose from, after a failed load the unread file, during a
+        // sync what it receives, and while another file is read that file
+        match &self.writer {
+            Some(writer) if self.conflict.is_none() && self.load_error.is_none() && !self.syncing && !self.loading => {
+                // The file holds what was read last, or one of our writes
+                let known = std::iter::once(self.on_disk.clone()).chain(self.writing.iter().cloned()).collect();
+
+                writer.write(
+                    SavedState {
+                        events: self.calendar.events().to_vec(),
+                        display_zone: Some(self.zone()),
+                        secondary_zone: self.secondary_zone,
+                        show_lunar: self.show_lunar,
+                        holiday_countries: Vec::new(),
+                        calendars: self.calendars.clone(),
+                    },
+                    self.config.data_path(),
+                    known,
+                );
 
-impl Event {
-    fn text_input_id(i: usize) -> text_input::Id {
-        text_input::Id::new(format!("event-{i}"))
+                self.writing.push(self.calendar.events().to_vec());
+                self.dirty = false;
+                self.save_status = SaveStatus::Pending;
+            }
+            _ => {
+                self.dirty = true;
+            }
+        }
     }
 
-    fn new(title: String, date: Date) -> Self {
-        Event {
-            title,
-            date: date.format_date(),
-            state: EventState::Idle,
+    /// The writer is done with `events`: written when `saved`, else lost.
+    /// The writes queued before were replaced by it.
+    fn settle(&mut self, events: Vec<Event>, saved: bool) {
+        // Already settled by a sync, or discarded
+        let Some(last) = self.writing.iter().rposition(|writing| *writing == events) else {
+            return;
+        };
+
+        self.writing.drain(..=last);
+
+        if saved {
+            self.on_disk = events;
         }
     }
 
-    fn update(&mut self, message: EventMessage) {
-        match message {
-            EventMessage::Edit => {
-                self.state = EventState::Editing;
-            }
-            EventMessage::TitleEdited(new_title) => {
-                self.title = new_title;
+    /// Show what was read from the calendar file, at startup or once the
+    /// settings chose another one, or why it could not be read
+    fn open(&mut self, loaded: Result<SavedState, LoadError>) -> Command<Message> {
+        self.loading = false;
+        self.writing.clear();
+        self.conflict = None;
+        self.dirty = false;
+        self.save_status = SaveStatus::Saved;
+
+        let saved = match loaded {
+            Ok(saved) => {
+                self.load_error = None;
+                saved
             }
-            EventMessage::FinishEdition => {
-                if !self.title.is_empty() {
-                    self.state = EventState::Idle;
+            Err(error) => {
+                // No file yet is a first start, not an error
+                self.load_error = (!error.is_missing()).then(|| {
+                    error_log::write(&self.config.data_path(), &format!("load failed: {error}"));
+                    error
+                });
+
+                SavedState {
+                    calendars: calendars::defaults(),
+                    ..SavedState::default()
                 }
             }
-            EventMessage::Delete => {}
-           
-        }
-    }
+        };
 
-    fn view(&self, i: usize) -> Element<EventMessage> {
-        let title = self.title.as_str();
+        let display_zone = saved.display_zone.unwrap_or_else(zone::local_zone);
 
-        match &self.state {
-            EventState::Idle => {
-                row![
-                    text(title).size(16).width(Length::Fill),
-                    button(edit_icon())
-                        .on_press(EventMessage::Edit)
-                        .padding(5)
-                        .width(Length::Fill)
-                        .style(theme::Button::Text)
-                ]
-                // .width(Length::Fill)
-                .spacing(5)
-                .align_items(Alignment::Center)
-                .into()
-            }
-            EventState::Editing => {
-                let text_input = text_input(
-                    "Describe your event...",
-                    &self.title,
-                    EventMessage::TitleEdited,
-                ).id(Self::text_input_id(i))
-                .on_submit(EventMessage::FinishEdition)
-                .padding(2);
-                
-                row![
-                    text_input,
-                    button(row![delete_icon()].spacing(2))
-                        .on_press(EventMessage::Delete)
-                        .padding(2)
-                        .style(theme::Button::Destructive)
-                ]
-                .spacing(2)
-                .align_items(Alignment::Center)
-                .into()
+        self.calendar = Calendar::new(Vec::new(), display_zone);
+        self.calendars = saved.calendars;
+        self.take_events(saved.events);
+        self.on_disk = self.calendar.events().to_vec();
+        self.date = date::today(display_zone);
+        self.secondary_zone = saved.secondary_zone;
+        self.show_lunar = saved.show_lunar;
 
-            }
+        // Holidays were chosen in the calendar file before they were a
+        // setting
+        if self.config.holiday_countries.is_none() && !saved.holiday_countries.is_empty() {
+            self.config.holiday_countries = Some(saved.holiday_countries.iter().map(|country| country.code().to_string()).collect());
+
+            return Command::perform(self.config.clone().save(), Message::SettingsSaved);
         }
 
+        Command::none()
     }
 }
 
 
+#[derive(Debug, Clone)]
+enum Message {
+    Loaded(Result<SavedState, LoadError>),
+    Writer(writer::Event),
+    CloseRequested,
+    /// The writer is done with what it held when closing was asked
+    Flushed(Result<(), SaveError>),
+    /// Close although the last changes could not be saved
+    CloseUnsaved,
+    RetryLoad,
+    RetrySave,
+    FileChanged(Result<SavedState, LoadError>),
+    ResolveConflict(Resolution),
+    TitleInputChanged(String),
+    CreateEvent,
+    OpenModal,
+    CloseModal,
+    ChooseDate,
+    SubmitDate(DateModal),
+    CancelDate,
+    EventMessage(usize, EventMessage),
+    CloseDetails,
+    SearchChanged(String),
+    SearchResultSelected(usize),
+    ChooseTime,
+    SubmitTime(TimeModal),
+    CancelTime,
+    ClearTime,
+    EventZoneSelected(Tz),
+    ViewModeSelected(ViewMode),
+    DisplayZoneSelected(Tz),
+    SecondaryZoneSelected(Tz),
+    ClearSecondaryZone,
+    RepeatLunarToggled(bool),
+    ShowLunarToggled(bool),
+    CategorySelected(Category),
+    CalendarPicked(u64),
+    CalendarToggled(u64, bool),
+    /// Give the calendar the next of the preset colors
+    CalendarRecolored(u64),
+    CalendarNameChanged(String),
+    AddCalendar,
+    RemoveCalendar(u64),
+    ExportIcs,
+    /// Print the month shown, or the week
+    ExportPdf,
+    Exported(Result<std::path::PathBuf, SaveError>),
+    Sync,
+    Synced(Result<SyncReport, String>),
+    FeedFetched(String, Result<Vec<Event>, String>),
+    FeedSourceChanged(String),
+    Subscribe,
+    Unsubscribe(String),
+    Slots(slots::SlotMessage),
+    /// Create the event of the slot finder on the slot
+    SlotChosen(Slot),
+    ConflictsToggled,
+    /// Show the day of the overlap, the first event highlighted
+    ConflictSelected(conflicts::Conflict),
+    Api(api::Incoming),
+    Publish(publish::Status),
+    LocaleSelected(Locale),
+    ThemeSelected(ThemeChoice),
+    OpenSettings,
+    CloseSettings,
+    Settings(SettingsMessage),
+    SaveSettings,
+    ReloadSettings,
+    SettingsSaved(Result<(), ConfigError>),
+    ShiftDays(i64),
+    NextMonth,
+    PrevMonth,
+}
+
+
 impl Application for CalendarApp {
     type Message = Message;
     type Theme = Theme;
     type Executor = iced::executor::Default;
-    type Flags = ();
+    type Flags = (Config, Vec<ConfigError>);
+
+    fn new((config, config_errors): Self::Flags) -> (CalendarApp, Command<Message>) {
+        let path = config.data_path();
 
-    fn new(_flags: ()) -> (CalendarApp, Command<Message>) {
         (
-            CalendarApp::Loading,
-            Command::perform(SavedState::load(), Message::Loaded),
+            CalendarApp::Loading(config, config_errors),
+            Command::perform(load(path), Message::Loaded),
         )
     }
     
 
-    fn title(&self) -> String {
-        let dirty = match self {
-            CalendarApp::Loading => false,
-            CalendarApp::Loaded(state) => state.dirty,
-        };
+    fn theme(&self) -> Theme {
+        match self {
+            CalendarApp::Loading(config, _) => config.palette().theme(),
+            CalendarApp::Loaded(state) => state.config.palette().theme(),
+        }
+    }
 
-        format!("Todos{} - Iced", if dirty { "***" } else { "" })
+    fn title(&self) -> String {
+        String::from("Todos - Iced")
     }
 
     fn update(&mut self, message: Message) -> Command<Message>{
         match self {
-            CalendarApp::Loading => {
+            CalendarApp::Loading(config, config_errors) => {
+                let config = config.clone();
+                let config_errors = config_errors.clone();
+
                 match message {
-                    Message::Loaded(Ok(state)) => {
-                        *self = CalendarApp::Loaded(State {
-                            events: state.events,
-                            date: Date::today(),
-                            picked_date: DateModal::today(),
-                            ..State::default()
-                        });
-                    }
-                    Message::Loaded(Err(_)) => {
-                        *self = CalendarApp::Loaded(State{
-                            date: Date::today(),
+                    Message::Loaded(loaded) => {
+                        let mut state = State {
                             picked_date: DateModal::today(),
+                            config,
+                            config_errors,
                             ..State::default()
-                        });            
+                        };
+                        let command = state.open(loaded);
+
+                        *self = CalendarApp::Loaded(state);
+
+                        command
                     }
-                    _ => {}
+                    _ => Command::none(),
                 }
-
-                Command::none()
             }
             CalendarApp::Loaded(state) => {
-                let mut saved = false;
+                match message {
+                    Message::NextMonth => {
+                        if let Some(date) = date::next_month(state.date) {
+                            state.date = date;
+                        }
+
+                        Command::none()
+                    }
+                    Message::TitleInputChanged(value) => {
+                        state.input_value = value;
+                        
+                        Command::none()
+                    },
+                    Message::CreateEvent => {
+                        if let Some(event) = state.new_event() {
+                            state.calendar.add(event);
+                            state.input_value.clear();
+                            state.repeat_lunar = false;
+                            state.persist();
+                        }
+                        //create event 
+                        state.show_modal = false;
+
+                        Command::none()
+                    },
+                    Message::OpenModal => {
+                        state.show_modal = true;
+
+                        Command::none()
+                    },
+                    Message::CloseModal => {
+                        state.show_modal = false;
+
+                        Command::none()
+                    },
+                    Message::ChooseDate => {
+                        state.show_picker = true;
+
+                        Command::none()
+                    },
+                    Message::SubmitDate(picked_date) => {
+                        state.picked_date = picked_date;
+                        state.show_picker = false;
+
+                        Command::none()
+                    },
+                    Message::CancelDate => {
+                        state.show_picker = false;
+                        Command::none()
+                    },
+                    Message::ChooseTime => {
+                        state.show_time_picker = true;
+
+                        Command::none()
+                    },
+                    Message::SubmitTime(picked_time) => {
+                        state.picked_time = Some(picked_time.into());
+                        state.show_time_picker = false;
+
+                        Command::none()
+                    },
+                    Message::CancelTime => {
+                        state.show_time_picker = false;
+
+                        Command::none()
+                    },
+                    Message::ClearTime => {
+                        state.picked_time = None;
+
+                        Command::none()
+                    },
+                    Message::EventZoneSelected(zone) => {
+                        state.event_zone = Some(zone);
+
+                        Command::none()
+                    },
+                    Message::ViewModeSelected(view_mode) => {
+                        state.view_mode = view_mode;
+
+                        Command::none()
+                    },
+                    Message::DisplayZoneSelected(zone) => {
+                        state.calendar.set_zone(zone);
+
+                        for feed in state.feeds.values_mut() {
+                            feed.calendar.set_zone(zone);
+                        }
+
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::SecondaryZoneSelected(zone) => {
+                        state.secondary_zone = Some(zone);
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::ClearSecondaryZone => {
+                        state.secondary_zone = None;
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::RepeatLunarToggled(repeat_lunar) => {
+                        state.repeat_lunar = repeat_lunar;
+
+                        Command::none()
+                    },
+                    Message::ShowLunarToggled(show_lunar) => {
+                        state.show_lunar = show_lunar;
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::CategorySelected(category) => {
+                        state.picked_category = category;
+
+                        Command::none()
+                    },
+                    Message::CalendarPicked(id) => {
+                        state.picked_calendar = id;
+
+                        Command::none()
+                    },
+                    Message::CalendarToggled(id, visible) => {
+                        if let Some(calendar) = state.calendars.iter_mut().find(|calendar| calendar.id == id) {
+                            calendar.visible = visible;
+                            state.persist();
+                        }
+
+                        Command::none()
+                    },
+                    Message::CalendarRecolored(id) => {
+                        if let Some(calendar) = state.calendars.iter_mut().find(|calendar| calendar.id == id) {
+                            let next = calendars::COLORS
+                                .iter()
+                                .position(|&color| color == calendar.color)
+                                .map_or(0, |i| (i + 1) % calendars::COLORS.len());
+
+                            calendar.color = calendars::COLORS[next];
+                            state.persist();
+                        }
+
+                        Command::none()
+                    },
+                    Message::CalendarNameChanged(name) => {
+                        state.calendar_name = name;
+
+                        Command::none()
+                    },
+                    Message::AddCalendar => {
+                        let name = state.calendar_name.trim().to_string();
+
+                        if !name.is_empty() {
+                            let calendar = NamedCalendar::new(calendars::next_id(&state.calendars), name, calendars::next_color(&state.calendars));
+
+                            state.calendars.push(calendar);
+                            state.calendar_name.clear();
+                            state.persist();
+                        }
+
+                        Command::none()
+                    },
+                    Message::RemoveCalendar(id) => {
+                        // Its events move to the first calendar left
+                        if let Some(moved_to) = calendars::remove(&mut state.calendars, id) {
+                            for i in 0..state.calendar.events().len() {
+                                if let Some(event) = state.calendar.event_mut(i).filter(|event| event.calendar == id) {
+                                    event.calendar = moved_to;
+                                }
+                            }
+
+                            state.persist();
+                        }
+
+                        Command::none()
+                    },
+                    Message::ExportIcs => {
+                        let year = state.date.year();
+                        let holidays = NaiveDate::from_ymd_opt(year, 1, 1)
+                            .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+                            .map(|(from, to)| holidays::between(&state.config.holiday_countries(), from, to))
+                            .unwrap_or_default();
+
+                        state.export_status = Some(state.config.locale.strings().exporting.to_string());
+
+                        Command::perform(
+                            export(ics::export(state.calendar.events(), &holidays).into_bytes(), state.config.data_path().with_file_name("calendar.ics")),
+                            Message::Exported,
+                        )
+                    },
+                    Message::ExportPdf => {
+                        let config = &state.config;
+
+                        let (from, to) = match state.view_mode {
+                            ViewMode::Month => (date::first_of_month(state.date), date::last_of_month(state.date)),
+                            ViewMode::Week | ViewMode::Day => (state.week_start(), state.week_start() + Duration::days(6)),
+                        };
+
+                        let holidays = holidays::between(&config.holiday_countries(), from, to);
+                        let subscribed = feeds::by_day(&state.feeds, from, to);
+
+                        let (grid, name) = match state.view_mode {
+                            ViewMode::Month => (
+                                print::month(state.date, &state.calendar, &state.calendars, &holidays, &subscribed, state.show_lunar, config.locale, config.first_weekday(), config.palette()),
+                                format!("calendar-{}.pdf", from.format("%Y-%m")),
+                            ),
+                            ViewMode::Week | ViewMode::Day => (
+                                print::week(from, &state.calendar, &state.calendars, &holidays, &subscribed, state.show_lunar, config.locale, config.palette()),
+                                format!("week-{from}.pdf"),
+                            ),
+                        };
+
+                        state.export_status = Some(config.locale.strings().exporting.to_string());
+
+                        Command::perform(export_pdf(grid, config.clone(), config.data_path().with_file_name(name)), Message::Exported)
+                    },
+                    Message::Exported(result) => {
+                        let strings = state.config.locale.strings();
+
+                        state.export_status = Some(match result {
+                            Ok(path) => format!("{} {}", strings.exported_to, path.display()),
+                            Err(error) => {
+                                error_log::write(&state.config.data_path(), &format!("export failed: {error}"));
+
+                                format!("{} {error}", strings.export_failed)
+                            }
+                        });
+
+                        Command::none()
+                    },
+                    Message::Sync if !state.syncing => {
+                        // Changes waiting to be saved go first, so they reach
+                        // the server
+                        if state.dirty {
+                            state.persist();
+                        }
+
+                        state.syncing = true;
+                        state.sync_status = Some(state.config.locale.strings().syncing.to_string());
+
+                        Command::perform(sync(state.writer.clone(), state.config.clone()), Message::Synced)
+                    },
+                    Message::Sync => Command::none(),
+                    Message::Synced(result) => {
+                        let locale = state.config.locale;
+
+                        state.syncing = false;
+
+                        // The sync wrote what was waiting before it began
+                        if result.is_ok() {
+                            if let Some(written) = state.writing.pop() {
+                                state.writing.clear();
+                                state.on_disk = written;
+                            }
+                        }
+
+                        state.sync_status = Some(match result {
+                            Ok(report) => locale.describe_sync(&report),
+                            Err(error) => {
+                                error_log::write(&state.config.data_path(), &format!("sync failed: {error}"));
+
+                                format!("{} {error}", locale.strings().sync_failed)
+                            }
+                        });
+
+                        // What the sync received, then the edits made
+                        // meanwhile, go through the usual reload
+                        Command::perform(load(state.config.data_path()), Message::FileChanged)
+                    },
+                    Message::FeedFetched(source, result) => {
+                        let zone = state.zone();
+                        let feed = state.feeds.entry(source.clone()).or_default();
+
+                        match result {
+                            Ok(events) => {
+                                feed.calendar = Calendar::new(events, zone);
+                                feed.fetched_at = Some(Local::now());
+                                feed.error = None;
+                            }
+                            Err(error) => {
+                                error_log::write(&state.config.data_path(), &format!("fetching {source} failed: {error}"));
+                                feed.error = Some(error);
+                            }
+                        }
+
+                        Command::none()
+                    },
+                    Message::FeedSourceChanged(source) => {
+                        state.feed_source = source;
+
+                        Command::none()
+                    },
+                    Message::Subscribe => {
+                        let source = state.feed_source.trim().to_string();
+
+                        if source.is_empty() || state.config.subscriptions.iter().any(|feed| feed.source == source) {
+                            Command::none()
+                        } else {
+                            state.config.subscriptions.push(config::Feed::new(source));
+                            state.feed_source.clear();
+
+                            Command::perform(state.config.clone().save(), Message::SettingsSaved)
+                        }
+                    },
+                    Message::Unsubscribe(source) => {
+                        state.config.subscriptions.retain(|feed| feed.source != source);
+                        state.feeds.remove(&source);
+
+                        Command::perform(state.config.clone().save(), Message::SettingsSaved)
+                    },
+                    Message::Slots(slots::SlotMessage::Find) => {
+                        let zone = state.zone();
+                        let today = date::today(zone);
+                        let days_off = holidays::between(&state.config.holiday_countries(), today, today + Duration::days(366))
+                            .into_iter()
+                            .map(|holiday| holiday.date)
+                            .collect();
+
+                        state.slot_finder.find(&state.calendar, &state.calendars, today, Utc::now().with_timezone(&zone).naive_local(), days_off, &state.config);
+
+                        // The week of the first slot shows them
+                        if let Some(slot) = state.slot_finder.slots.first() {
+                            state.date = slot.date;
+                            state.view_mode = ViewMode::Week;
+                        }
+
+                        Command::none()
+                    },
+                    Message::Slots(message) => {
+                        state.slot_finder.update(message);
+
+                        Command::none()
+                    },
+                    Message::SlotChosen(slot) => {
+                        let title = match state.slot_finder.title.trim() {
+                            "" => state.config.locale.strings().new_event.to_string(),
+                            title => title.to_string(),
+                        };
+
+                        let time = EventTime {
+                            start: slot.start,
+                            end: slot.end,
+                            zone: state.zone(),
+                            reminder: state.config.reminder,
+                        };
+
+                        let mut event = Event::new(title, slot.date, Some(time), None, state.picked_category);
+                        event.calendar = picked_calendar(&state.calendars, state.picked_calendar).map_or(0, |calendar| calendar.id);
+
+                        state.calendar.add(event);
+                        state.slot_finder.title.clear();
+                        state.slot_finder.update(slots::SlotMessage::Clear);
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::Api(api::Incoming::Call(call)) => {
+                        // Positions after a removed event shift
+                        let removes = matches!(call.request, api::Request::Delete(_));
+                        let (response, changed) = api::apply(call.request.clone(), &mut state.calendar, &state.calendars);
+
+                        if changed {
+                            if removes {
+                                state.selected_event = None;
+                                state.editing = None;
+                                state.highlighted_event = None;
+                            }
+
+                            state.persist();
+                        }
+
+                        call.answer(response);
+
+                        Command::none()
+                    },
+                    Message::Api(api::Incoming::Failed(error)) => {
+                        error_log::write(&state.config.data_path(), &format!("API: {error}"));
+
+                        Command::none()
+                    },
+                    Message::Publish(publish::Status::Ready(publisher)) => {
+                        state.publisher = Some(publisher);
+                        state.publish();
+
+                        Command::none()
+                    },
+                    Message::Publish(publish::Status::Failed(error)) => {
+                        error_log::write(&state.config.data_path(), &format!("feeds: {error}"));
+
+                        Command::none()
+                    },
+                    Message::LocaleSelected(locale) => {
+                        state.config.locale = locale;
+
+                        Command::perform(state.config.clone().save(), Message::SettingsSaved)
+                    },
+                    Message::ThemeSelected(theme) => {
+                        state.config.theme = theme;
+
+                        Command::perform(state.config.clone().save(), Message::SettingsSaved)
+                    },
+                    Message::OpenSettings => {
+                        state.settings = Some(SettingsForm {
+                            errors: state.config_errors.clone(),
+                            ..SettingsForm::new(&state.config)
+                        });
+
+                        Command::none()
+                    },
+                    Message::CloseSettings => {
+                        state.settings = None;
+
+                        Command::none()
+                    },
+                    Message::Settings(settings_message) => {
+                        if let Some(form) = &mut state.settings {
+                            form.update(settings_message);
+                        }
+
+                        Command::none()
+                    },
+                    Message::SaveSettings => {
+                        match state.settings.as_ref().map(|form| form.apply(&state.config)) {
+                            Some(Ok(config)) => {
+                                let moved = config.data_path() != state.config.data_path();
+
+                                // Changes so far go to the file being left
+                                if moved && state.dirty {
+                                    state.persist();
+                                }
+
+                                state.config = config;
+                                state.settings = None;
+
+                                let save = Command::perform(state.config.clone().save(), Message::SettingsSaved);
+
+                                if !moved {
+                                    return save;
+                                }
+
+                                // What the window holds describes the old
+                                // file; nothing is saved until the new one
+                                // is read, as the watcher only reports
+                                // later changes
+                                state.loading = true;
+                                state.on_disk.clear();
+                                state.writing.clear();
+                                state.conflict = None;
+
+                                Command::batch([
+                                    save,
+                                    Command::perform(reopen(state.writer.clone(), state.config.data_path()), Message::Loaded),
+                                ])
+                            }
+                            Some(Err(errors)) => {
+                                if let Some(form) = &mut state.settings {
+                                    form.errors = errors;
+                                }
+
+                                Command::none()
+                            }
+                            None => Command::none(),
+                        }
+                    },
+                    Message::ReloadSettings => {
+                        let (config, config_errors) = Config::load();
+
+                        state.settings = Some(SettingsForm {
+                            errors: config_errors.clone(),
+                            ..SettingsForm::new(&config)
+                        });
+                        state.config = config;
+                        state.config_errors = config_errors;
+
+                        Command::none()
+                    },
+                    Message::SettingsSaved(result) => {
+                        match result {
+                            // The file that failed to load stays reported
+                            // until it is fixed and reloaded
+                            Err(ConfigError::Unsaved) => {
+                                if !state.config_errors.contains(&ConfigError::Unsaved) {
+                                    state.config_errors.push(ConfigError::Unsaved);
+                                }
+                            }
+                            result => state.config_errors = result.err().into_iter().collect(),
+                        }
+
+                        Command::none()
+                    },
+                    Message::ShiftDays(days) => {
+                        if let Some(date) = state.date.checked_add_signed(Duration::days(days)) {
+                            state.date = date;
+                        }
+
+                        Command::none()
+                    },
+                    Message::EventMessage(i, EventMessage::Delete) => {
+                        state.calendar.remove(i);
+                        state.selected_event = None;
+                        state.editing = None;
+                        state.highlighted_event = None;
+                        state.persist();
+
+                        Command::none()
+                    },
+                    Message::EventMessage(i, EventMessage::ShowDetails) => {
+                        state.selected_event = Some(i);
+
+                        Command::none()
+                    },
+                    Message::CloseDetails => {
+                        state.selected_event = None;
+
+                        Command::none()
+                    },
+                    Message::SearchChanged(query) => {
+                        if query.is_empty() {
+                            state.highlighted_event = None;
+                        }
+                        state.search_query = query;
+
+                        Command::none()
+                    },
+                    Message::SearchResultSelected(i) => {
+                        if let Some(event) = state.calendar.events().get(i) {
+                            state.date = event.date_in(state.zone());
+                            state.highlighted_event = Some(i);
+                        }
+
+                        Command::none()
+                    },
+                    Message::EventMessage(i, EventMessage::Edit) => {
+                        state.editing = Some(i);
+
+                        let id = event_view::text_input_id(i);
+
+                        Command::batch(vec![
+                            text_input::focus(id.clone()),
+                            text_input::select_all(id),
+                        ])
+                    },
+                    Message::EventMessage(i, EventMessage::FinishEdition) => {
+                        if state.calendar.events().get(i).map_or(true, |event| !event.title.is_empty()) {
+                            state.editing = None;
+                        }
+
+                        Command::none()
+                    },
+                    Message::EventMessage(i, EventMessage::Moved(minutes)) => {
+                        if let Some(event) = state.calendar.event_mut(i) {
+                            event_view::update(event, EventMessage::Moved(minutes));
+                            state.calendar.reindex();
+                            state.persist();
+                        }
+
+                        Command::none()
+                    },
+                    Message::ConflictsToggled => {
+                        state.show_conflicts = !state.show_conflicts;
+
+                        Command::none()
+                    },
+                    Message::ConflictSelected(conflict) => {
+                        state.date = conflict.date;
+                        state.highlighted_event = Some(conflict.first);
 
-                let command = match message {
-                    Message::NextMonth => {
-                        if state.date.month == 12 {
-                            state.date.month = 1;
-                            state.date.year = state.date.year + 1;
-                        } else {
-                            state.date.month = state.date.month + 1;
+                        Command::none()
+                    },
+                    Message::EventMessage(i, event_message) => {
+                        if let Some(event) = state.calendar.event_mut(i) {
+                            event_view::update(event, event_message);
+                            state.persist();
                         }
 
                         Command::none()
                     }
-                    Message::TitleInputChanged(value) => {
-                        state.input_value = value;
-                        
-                        Command::none()
-                    },
-                    Message::CreateEvent => {
-                        if !state.input_value.is_empty() {
-                            state.events.push(
-                                Event::new(
-                                    state.input_value.clone(), 
-                                    Date::from_ymd(state.picked_date.year, state.picked_date.month, state.picked_date.day)
-                                )
-                            );
-                            state.input_value.clear();
+                    Message::PrevMonth => {
+                        if let Some(date) = date::prev_month(state.date) {
+                            state.date = date;
                         }
-                        //create event 
-                        state.show_modal = false;
 
                         Command::none()
                     },
-                    Message::OpenModal => {
-                        state.show_modal = true;
+                    Message::Writer(writer::Event::Ready(writer)) => {
+                        state.writer = Some(writer);
+
+                        if state.dirty {
+                            state.persist();
+                        }
 
                         Command::none()
                     },
-                    Message::CloseModal => {
-                        state.show_modal = false;
+                    Message::Writer(writer::Event::Saving) => {
+                        state.save_status = SaveStatus::Saving;
 
                         Command::none()
                     },
-                    Message::ChooseDate => {
-                        state.show_picker = true;
-                        print!("check : {}", state.show_picker);
-                        // state.show_modal = false;
+                    Message::Writer(writer::Event::Saved(saved)) => {
+                        state.settle(saved.events, true);
+
+                        // Newer changes may be waiting already
+                        state.save_status = if state.writing.is_empty() && !state.dirty {
+                            SaveStatus::Saved
+                        } else {
+                            SaveStatus::Pending
+                        };
+
                         Command::none()
                     },
-                    Message::SubmitDate(picked_date) => {
-                        state.picked_date = picked_date;
-                        state.show_picker = false;
+                    Message::Writer(writer::Event::Failed(lost, error)) => {
+                        state.settle(lost.events, false);
+                        state.save_status = SaveStatus::Failed(error);
 
                         Command::none()
                     },
-                    Message::CancelDate => {
-                        state.show_picker = false;
-                        Command::none()
+                    Message::CloseRequested => match state.writer.clone() {
+                        Some(writer) => {
+                            if state.dirty {
+                                state.persist();
+                            }
+
+                            Command::perform(flush(writer), Message::Flushed)
+                        }
+                        None => window::close(),
                     },
-                    Message::EventMessage(i, EventMessage::Delete) => {
-                        state.events.remove(i);
+                    Message::Flushed(Ok(())) => window::close(),
+                    Message::Flushed(Err(error)) => {
+                        // Stay open with the retry banner rather than lose
+                        // the changes unasked
+                        error_log::write(&state.config.data_path(), &format!("not closed, save failed: {error}"));
+                        state.save_status = SaveStatus::Failed(error);
+                        state.close_failed = true;
 
                         Command::none()
                     },
-                    Message::EventMessage(i, event_message) => {
-                        if let Some(event) = state.events.get_mut(i) {
-                            let should_focus = matches!(event_message, EventMessage::Edit);
+                    Message::CloseUnsaved => window::close(),
+                    // The file chosen in the settings is being read already
+                    Message::FileChanged(_) if state.loading => Command::none(),
+                    Message::FileChanged(Ok(saved)) => {
+                        let theirs = Calendar::new(saved.events, state.zone()).into_events();
 
-                            event.update(event_message);
+                        state.load_error = None;
 
-                            if should_focus {
-                                let id = Event::text_input_id(i);
+                        let unsaved = state.dirty || !state.writing.is_empty() || state.calendar.events() != state.on_disk.as_slice();
 
-                                Command::batch(vec![
-                                    text_input::focus(id.clone()),
-                                    text_input::select_all(id),
-                                ])
-                            } else {
-                                Command::none()
+                        // Calendars changed elsewhere, unless ours are yet
+                        // to be written
+                        if !unsaved {
+                            state.calendars = saved.calendars;
+                            state.publish();
+                        }
+
+                        // Neither the file as we know it, nor one of our
+                        // writes read back before the writer reported it
+                        let elsewhere = theirs != state.on_disk && !state.writing.contains(&theirs);
+
+                        if elsewhere && !unsaved {
+                            state.take_events(theirs.clone());
+                            state.on_disk = theirs;
+                        } else if elsewhere {
+                            // A write still waiting would replace their
+                            // changes before the user picks what to keep;
+                            // one already started finds them and fails
+                            if let Some(writer) = &state.writer {
+                                writer.discard();
                             }
-                        } else {
-                            Command::none()
+
+                            state.writing.clear();
+                            state.dirty = true;
+                            state.conflict = Some(theirs);
                         }
-                    }
-                    Message::PrevMonth => {
-                        if state.date.month == 1 {
-                            state.date.month = 12;
-                            state.date.year = state.date.year - 1;
-                        } else {
-                            state.date.month = state.date.month - 1;
+
+                        // Changes held while the file could not be read
+                        if state.dirty {
+                            state.persist();
                         }
 
                         Command::none()
                     },
-                    Message::Saved(_) => {
-                        state.saving = false;
-                        saved = true;
+                    // Deleted: the next save writes it again
+                    Message::FileChanged(Err(error)) if error.is_missing() => Command::none(),
+                    Message::FileChanged(Err(error)) => {
+                        error_log::write(&state.config.data_path(), &format!("load failed: {error}"));
+                        state.load_error = Some(error);
 
                         Command::none()
                     },
-                    Message::Loaded(_) => {
-                        Command::none()
-                    }
-                };
-
-                if !saved {
-                    state.dirty = true;
-                }
-
-                let save = if state.dirty && !state.saving {
-                    state.dirty = false;
-                    state.saving = true;
+                    Message::RetryLoad => Command::perform(load(state.config.data_path()), Message::FileChanged),
+                    Message::RetrySave => {
+                        state.persist();
 
-                    Command::perform(
-                        SavedState {
-                            events: state.events.clone(),
+                        Command::none()
+                    },
+                    Message::ResolveConflict(resolution) => {
+                        if let Some(theirs) = state.conflict.take() {
+                            match resolution {
+                                Resolution::KeepMine => {
+                                    state.on_disk = theirs;
+                                    state.persist();
+                                }
+                                Resolution::TakeTheirs => {
+                                    state.take_events(theirs.clone());
+                                    state.on_disk = theirs;
+                                    state.dirty = false;
+
+                                    if state.writing.is_empty() {
+                                        state.save_status = SaveStatus::Saved;
+                                    }
+                                }
+                                Resolution::Merge => {
+                                    let merged = merge::merge(&state.on_disk, state.calendar.events(), &theirs);
+
+                                    state.on_disk = theirs;
+                                    state.take_events(merged);
+                                    state.persist();
+                                }
+                            }
                         }
-                        .save(),
-                        Message::Saved,
-                    )
-                } else {
-                    Command::none()
-                };
 
-                Command::batch(vec![command, save])
+                        Command::none()
+                    },
+                    Message::Loaded(loaded) => state.open(loaded),
+                }
             }
         }
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        match self {
+            CalendarApp::Loading(..) => Subscription::none(),
+            CalendarApp::Loaded(state) => Subscription::batch(
+                [
+                    watcher::changes(state.config.data_path()).map(Message::FileChanged),
+                    writer::run().map(Message::Writer),
+                    subscription::events_with(|event, _status| match event {
+                        iced::Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+                        _ => None,
+                    }),
+                ]
+                .into_iter()
+                .chain(state.config.subscriptions.iter().map(|feed| {
+                    feeds::watch(feed).map(|(source, result)| Message::FeedFetched(source, result))
+                }))
+                .chain(state.config.api.iter().map(|api| api::serve(api).map(Message::Api)))
+                .chain(state.config.publish.iter().map(|publish| publish::serve(publish).map(Message::Publish))),
+            ),
+        }
+    }
+
     fn view(&self) -> Element<Message> {
         match self {
-            CalendarApp::Loading => loading_message(),
-            CalendarApp::Loaded(State 
+            CalendarApp::Loading(..) => loading_message(),
+            CalendarApp::Loaded(state @ State
                 { 
                     date, 
-                    events, 
+                    calendar,
+                    calendars,
                     show_modal,
                     show_picker,  
                     input_value,
-                    picked_date,  
+                    picked_date,
+                    selected_event,
+                    editing,
+                    search_query,
+                    highlighted_event,
+                    view_mode,
+                    secondary_zone,
+                    show_time_picker,
+                    picked_time,
+                    event_zone,
+                    repeat_lunar,
+                    show_lunar,
+                    picked_category,
+                    picked_calendar,
+                    calendar_name,
+                    export_status,
+                    sync_status,
+                    syncing,
+                    feeds,
+                    feed_source,
+                    slot_finder,
+                    show_conflicts,
+                    config,
+                    config_errors,
+                    settings,
+                    conflict,
+                    save_status,
+                    load_error,
+                    close_failed,
                     .. 
                 }
             ) => {
-                let dt = Utc.ymd(date.year, date.month, date.day);
-
-                let month_start_day = Utc.ymd(date.year, date.month, 1).weekday().num_days_from_sunday();
-
-                let weekdays = dt.weekday().num_days_from_monday();
+                let locale = config.locale;
+                let strings = locale.strings();
+                let palette = config.palette();
+                let first_weekday = config.first_weekday();
 
-                let days = date.number_days_month(date.month, date.year);
-
-                let pred_month_days = date.number_days_pred_month(date.month, date.year);
-
-                let next_month_days = date.number_days_next_month(date.month, date.year);
-
-                let months_text = vec!["January","February","March","April","May","June","July","August","September","October","November","December",];
-
-                let days_text = vec!["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
-
-                let month_text: Text = text(months_text[(date.month as usize) - 1])
+                let month_text: Text = text(locale.month_name(*date))
                     .size(32)
-                    .style(Color::from([0.6, 0.6, 0.6]))
+                    .style(palette.muted)
                     .width(Length::Fill)
                     .horizontal_alignment(alignment::Horizontal::Center);
 
-                let year_text: Text = text(date.year.to_string())
+                let year_text: Text = text(date.year().to_string())
                     .size(32)
-                    .style(Color::from([0.6, 0.6, 0.6]))
+                    .style(palette.muted)
                     .width(Length::Fill)
                     .horizontal_alignment(alignment::Horizontal::Center);
 
-                let header = view_controls(month_text, year_text, *show_modal, *show_picker, input_value.to_string(), *picked_date );
-
-                // Create a header for the weekdays name
-                let mut weekday = Row::new();
-
-                for day in days_text.iter() {
-                    // Use the Text widget to display the day
-                    let text = Text::new(day.to_string())
-                        .width(Length::Fill)
-                        .horizontal_alignment(alignment::Horizontal::Center)
-                        .style(Color::from([0.5, 0.5, 0.5]));
-                    // Wrap the Text widget in a Container with a background color and padding
-                    let container = Container::new(text)
-                        .width(Length::Fill)
-                        .height(Length::Fixed(30.0))
-                        .center_x()
-                        .center_y()
-                        .padding(5)
-                        .style(theme::Container::Custom(Box::new(MyContainerStyle)));
-
-                    weekday = weekday.push(container);
-                }
+                let search_results = if search_query.is_empty() {
+                    Vec::new()
+                } else {
+                    calendar.search(search_query)
+                        .into_iter()
+                        .map(|i| (i, calendar.events()[i].date, calendar.events()[i].title.clone()))
+                        .collect::<Vec<_>>()
+                };
 
-                let mut day_current_month = Column::new();
+                let parsed = quick_add::parse(input_value, date::today(calendar.zone()));
+                let preview = parsed.is_planned().then(|| locale.describe_quick_add(&parsed));
+
+                // Events the one being created would overlap
+                let overlaps: Vec<String> = state
+                    .new_event()
+                    .map(|event| conflicts::overlapping(calendar, &event, None))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|i| calendar.events()[i].title.clone())
+                    .collect();
+
+                let header = view_controls(
+                    month_text,
+                    year_text,
+                    *show_modal,
+                    *show_picker,
+                    input_value.to_string(),
+                    preview,
+                    overlaps,
+                    *picked_date,
+                    *show_time_picker,
+                    *picked_time,
+                    event_zone.unwrap_or(calendar.zone()),
+                    *repeat_lunar,
+                    *picked_category,
+                    calendars.clone(),
+                    self::picked_calendar(calendars, *picked_calendar).cloned(),
+                    *view_mode,
+                    search_query.to_string(),
+                    search_results,
+                    locale,
+                    palette,
+                );
+
+                let zone = calendar.zone();
+
+                let (from, to) = match view_mode {
+                    ViewMode::Month => (date::first_of_month(*date), date::last_of_month(*date)),
+                    ViewMode::Week => (state.week_start(), state.week_start() + Duration::days(6)),
+                    ViewMode::Day => (*date, *date),
+                };
 
-                let mut day_count = 0;
+                // Overlaps between events of the calendars shown
+                let shown_conflicts: Vec<conflicts::Conflict> = conflicts::between(calendar, from, to)
+                    .into_iter()
+                    .filter(|conflict| calendars::is_visible(calendars, calendar.events()[conflict.first].calendar))
+                    .collect();
+                let conflicting: std::collections::HashSet<usize> = shown_conflicts
+                    .iter()
+                    .flat_map(|conflict| [conflict.first, conflict.second])
+                    .collect();
 
-                for _ in 0..6 {
-                    let mut week = Row::new();
+                let holidays = holidays::between(&config.holiday_countries(), from, to);
 
-                    for weekday_num in 0..7 {
-                        if (month_start_day == weekday_num || day_count >= 1) && day_count < days
-                        {
-                            day_count += 1;
+                let body = match view_mode {
+                    ViewMode::Month => {
+                        let subscribed = feeds::by_day(feeds, from, to);
 
-                            let date2 = Date::from_ymd(date.year, date.month, day_count);
-                            
-                            let event_this_day = events.iter()
-                                .filter(|event| event.date == date2.format_date())
-                                .collect::<Vec<_>>();
+                        view_month(*date, calendar, calendars, *editing, *highlighted_event, &conflicting, *show_lunar, &holidays, &subscribed, locale, first_weekday, palette)
+                    }
+                    ViewMode::Week => timeline::view(calendar, calendars, from, 7, *secondary_zone, *editing, *highlighted_event, &conflicting, &holidays, &slot_finder.slots, locale, palette),
+                    ViewMode::Day => timeline::view(calendar, calendars, from, 1, *secondary_zone, *editing, *highlighted_event, &conflicting, &holidays, &slot_finder.slots, locale, palette),
+                };
 
-                            let events_day: Element<_> = scrollable(column(
-                                event_this_day
-                                .iter()
-                                .enumerate()
-                                .map(|(i, event)| {
-                                    event.view(i).map(move |message| {
-                                        Message::EventMessage(i, message)
-                                    })
-                                }).collect(),
+                // Settings that could not be read are shown until fixed, the
+                // calendar running on defaults meanwhile
+                let config_errors = config_errors.iter().fold(Column::new().padding(5), |errors, error| {
+                    errors.push(text(error.message(locale)).size(14).style(palette.danger))
+                });
+
+                let content = column![
+                    config_errors,
+                    error_banner(load_error.as_ref(), save_status, *close_failed, locale, palette),
+                    header,
+                    view_display_controls(
+                        *view_mode,
+                        zone,
+                        *secondary_zone,
+                        *show_lunar,
+                        save_status_view(save_status, locale, palette),
+                        export_status.clone(),
+                        // Only offered with an account, and once at a time
+                        config.caldav.is_some().then_some(!*syncing),
+                        sync_status.clone(),
+                        config.theme,
+                        locale,
+                    ),
+                    feeds::view(&config.subscriptions, feeds, feed_source, locale, palette),
+                    slots::view(slot_finder, config, locale, palette),
+                    conflicts_view(calendar, &shown_conflicts, *show_conflicts, locale, palette),
+                    row![sidebar::view(calendars, calendar_name, locale, palette), body],
+                ];
+
+                let form = settings.clone();
+
+                let content = Modal::new(settings.is_some(), content, move || {
+                    match &form {
+                        Some(form) => settings::view(form, locale, palette),
+                        None => column![].into(),
+                    }
+                })
+                .backdrop(Message::CloseSettings)
+                .on_esc(Message::CloseSettings);
+
+                let selected = selected_event.and_then(|i| calendar.events().get(i).map(|event| (i, event.clone())));
+                let filed_in = calendars.clone();
+                let selected_overlaps: Vec<String> = selected
+                    .as_ref()
+                    .map(|(i, event)| conflicts::overlapping(calendar, event, Some(*i)))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|i| calendar.events()[i].title.clone())
+                    .collect();
+
+                let content = Modal::new(selected.is_some(), content, move || {
+                    match &selected {
+                        Some((i, event)) => {
+                            let i = *i;
+
+                            Card::new(
+                                Text::new(event.title.clone()),
+                                event_view::details_view(event, &filed_in, &selected_overlaps, zone, locale, palette).map(move |message| Message::EventMessage(i, message)),
                             )
-                            .spacing(2)
-                            ).style(theme::Scrollable::Custom(Box::new(MyScrollable)))
-                            .into();
-
-                            let day_element = if date2 == Date::today() {
-                                text(date2.day.to_string())
-                                .size(30)
-                                .style(Color::from([44.0/255.0, 138.0/255.0, 252.0/255.0]))
-                            } else {
-                                text(date2.day.to_string())
-                                .size(16)
-                                .style(Color::from([0.6, 0.6, 0.6]))
-                            };
-
-                            let day_event = Container::new(column![
-                                row![
-                                    day_element
-                                ]
-                                .width(Length::Fill),
-                                row![
-                                    events_day
-                                ]
-                                .width(Length::Fill)
-                            ])
-                            .width(Length::Fill)
-                            .height(Length::Fixed(120.0))
-
-                            .center_x()
-                            .padding(5)
-                            .style(theme::Container::Custom(Box::new(MyContainerStyle)));
-                            week = week.push(day_event);
-                        } else {
-                            week = week.push(Container::new("")
-                            .width(Length::Fill)
-                            .height(Length::Fixed(120.0))
-                            .center_x()
-                            .padding(5)
-                            .style(theme::Container::Custom(Box::new(MyContainerStyle))));
+                            .foot(
+                                Row::new()
+                                    .spacing(10)
+                                    .padding(5)
+                                    .width(Length::Fill)
+                                    .push(
+                                        Button::new(Text::new(strings.delete).horizontal_alignment(alignment::Horizontal::Center))
+                                            .width(Length::Fill)
+                                            .style(theme::Button::Destructive)
+                                            .on_press(Message::EventMessage(i, EventMessage::Delete)),
+                                    )
+                                    .push(
+                                        Button::new(Text::new(strings.close).horizontal_alignment(alignment::Horizontal::Center))
+                                            .width(Length::Fill)
+                                            .on_press(Message::CloseDetails),
+                                    )
+                            )
+                            .max_width(400.0)
+                            .on_close(Message::CloseDetails)
+                            .into()
                         }
+                        None => column![].into(),
                     }
+                })
+                .backdrop(Message::CloseDetails)
+                .on_esc(Message::CloseDetails);
 
-                    day_current_month = day_current_month.push(week);
-                }
+                let counts = conflict.as_ref().map(|theirs| (calendar.events().len(), theirs.len()));
 
-                let content= column![header, weekday, day_current_month];
+                let content = Modal::new(conflict.is_some(), content, move || {
+                    let (mine, theirs) = counts.unwrap_or_default();
+
+                    let choice = |label: &'static str, resolution| {
+                        Button::new(Text::new(label).horizontal_alignment(alignment::Horizontal::Center))
+                            .width(Length::Fill)
+                            .on_press(Message::ResolveConflict(resolution))
+                    };
+
+                    Card::new(
+                        Text::new(strings.conflict_title),
+                        column![
+                            text(strings.conflict_body),
+                            text(format!("{mine} {} · {theirs} {}", strings.events_here, strings.events_on_disk)).size(14).style(palette.muted),
+                        ]
+                        .spacing(10),
+                    )
+                    .foot(
+                        row![
+                            choice(strings.keep_mine, Resolution::KeepMine),
+                            choice(strings.take_theirs, Resolution::TakeTheirs),
+                            choice(strings.merge, Resolution::Merge),
+                        ]
+                        .spacing(10)
+                        .padding(5)
+                        .width(Length::Fill),
+                    )
+                    .max_width(450.0)
+                    .into()
+                });
 
                 container(content).center_x().into()
             }
@@ -562,40 +1476,330 @@ impl Application for CalendarApp {
     }
 }
 
-fn view_controls<'a>(month_text: Text<'a>, year_text: Text<'a>, show_modal: bool, show_picker: bool, input_value: String, picked_date: DateModal) -> Element<'a, Message> {
+/// Month grid of `date`, weeks starting on `first_weekday`, with the events
+/// of subscribed calendars read-only under the holidays. Events of
+/// calendars hidden in `calendars` are left out, `conflicting` ones marked.
+#[allow(clippy::too_many_arguments)]
+fn view_month<'a>(date: NaiveDate, calendar: &Calendar, calendars: &[NamedCalendar], editing: Option<usize>, highlighted_event: Option<usize>, conflicting: &std::collections::HashSet<usize>, show_lunar: bool, holidays: &[Holiday], subscribed: &std::collections::BTreeMap<NaiveDate, Vec<String>>, locale: Locale, first_weekday: Weekday, palette: Palette) -> Element<'a, Message> {
+    let month_start = date::first_of_month(date);
+
+    let zone = calendar.zone();
+
+    let month_start_day = date::days_since(month_start.weekday(), first_weekday);
+
+    let days = date::days_in_month(date);
+
+    let today = date::today(zone);
+
+    // Create a header for the weekdays name
+    let mut weekday = Row::new();
+
+    for day in std::iter::successors(Some(first_weekday), |day| Some(day.succ())).take(7) {
+        // Use the Text widget to display the day
+        let text = Text::new(locale.weekday_name(day))
+            .width(Length::Fill)
+            .horizontal_alignment(alignment::Horizontal::Center)
+            .style(palette.muted);
+        // Wrap the Text widget in a Container with a background color and padding
+        let container = Container::new(text)
+            .width(Length::Fill)
+            .height(Length::Fixed(30.0))
+            .center_x()
+            .center_y()
+            .padding(5)
+            .style(style::cell(palette));
+
+        weekday = weekday.push(container);
+    }
+
+    // Positions of this month's events, by day of month
+    let mut events_by_day: Vec<Vec<usize>> = vec![Vec::new(); days as usize + 1];
+
+    for (event_date, positions) in calendar.occurrences(month_start, date::last_of_month(date)) {
+        if let Some(day) = events_by_day.get_mut(event_date.day() as usize) {
+            *day = positions
+                .into_iter()
+                .filter(|&i| calendars::is_visible(calendars, calendar.events()[i].calendar))
+                .collect();
+        }
+    }
+
+    let mut day_current_month = Column::new();
+
+    let mut day_count = 0;
+
+    for _ in 0..6 {
+        let mut week = Row::new();
+
+        for weekday_num in 0..7 {
+            if (month_start_day == weekday_num || day_count >= 1) && day_count < days
+            {
+                day_count += 1;
+
+                let date2 = month_start + Duration::days(i64::from(day_count - 1));
+
+                let holidays_day = holidays
+                    .iter()
+                    .filter(|holiday| holiday.date == date2)
+                    .map(|holiday| holiday_view(holiday, &palette));
+
+                let subscribed_day = subscribed
+                    .get(&date2)
+                    .into_iter()
+                    .flatten()
+                    .map(|line| subscribed_view(line, &palette));
+
+                let events_day: Element<_> = scrollable(column(
+                    holidays_day.chain(subscribed_day).chain(
+                        events_by_day[day_count as usize]
+                        .iter()
+                        .map(|&i| {
+                            event_view::view(&calendar.events()[i], i, editing == Some(i), highlighted_event == Some(i), conflicting.contains(&i), calendars, zone, locale, palette).map(move |message| {
+                                Message::EventMessage(i, message)
+                            })
+                        })
+                    ).collect(),
+                )
+                .spacing(2)
+                ).style(style::event_list(palette))
+                .into();
+
+                let day_element = if date2 == today {
+                    text(date2.day().to_string())
+                    .size(30)
+                    .style(palette.accent)
+                } else {
+                    text(date2.day().to_string())
+                    .size(16)
+                    .style(palette.muted)
+                };
+
+                let lunar_element = if show_lunar {
+                    let lunar = lunar::from_solar(date2, lunar::VIETNAM);
+
+                    text(lunar.to_string())
+                        .size(14)
+                        .style(palette.lunar)
+                } else {
+                    text("")
+                };
+
+                let day_event = Container::new(column![
+                    row![
+                        day_element,
+                        horizontal_space(Length::Fill),
+                        lunar_element,
+                    ]
+                    .width(Length::Fill),
+                    row![
+                        events_day
+                    ]
+                    .width(Length::Fill)
+                ])
+                .width(Length::Fill)
+                .height(Length::Fixed(120.0))
+
+                .center_x()
+                .padding(5)
+                .style(style::cell(palette));
+                week = week.push(day_event);
+            } else {
+                week = week.push(Container::new("")
+                .width(Length::Fill)
+                .height(Length::Fixed(120.0))
+                .center_x()
+                .padding(5)
+                .style(style::cell(palette)));
+            }
+        }
+
+        day_current_month = day_current_month.push(week);
+    }
+
+    column![weekday, day_current_month].into()
+}
+
+/// How many events overlap in the range shown and, when `expanded`, each
+/// pair, which shows its day when pressed
+fn conflicts_view<'a>(calendar: &Calendar, conflicts: &[conflicts::Conflict], expanded: bool, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    if conflicts.is_empty() {
+        return column![].into();
+    }
+
+    let summary = row![
+        text(format!("⚠ {} {}", conflicts.len(), strings.conflicts_found)).size(14).style(palette.danger),
+        button(if expanded { strings.hide } else { strings.show })
+            .style(theme::Button::Text)
+            .on_press(Message::ConflictsToggled),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let pairs = conflicts.iter().filter(|_| expanded).map(|conflict| {
+        let title = |i: usize| calendar.events()[i].title.as_str();
+
+        button(text(format!("{}  {} · {}", locale.format_date(conflict.date), title(conflict.first), title(conflict.second))).size(14))
+            .style(theme::Button::Text)
+            .padding(2)
+            .on_press(Message::ConflictSelected(*conflict))
+            .into()
+    });
+
+    column![summary, column(pairs.collect()).spacing(2)].padding(5).into()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn view_controls<'a>(
+    month_text: Text<'a>,
+    year_text: Text<'a>,
+    show_modal: bool,
+    show_picker: bool,
+    input_value: String,
+    preview: Option<String>,
+    overlaps: Vec<String>,
+    picked_date: DateModal,
+    show_time_picker: bool,
+    picked_time: Option<NaiveTime>,
+    event_zone: Tz,
+    repeat_lunar: bool,
+    picked_category: Category,
+    calendars: Vec<NamedCalendar>,
+    picked_calendar: Option<NamedCalendar>,
+    view_mode: ViewMode,
+    search_query: String,
+    search_results: Vec<(usize, NaiveDate, String)>,
+    locale: Locale,
+    palette: Palette,
+) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let search_input = text_input(strings.search_placeholder, &search_query, Message::SearchChanged)
+        .padding(5)
+        .width(Length::Fixed(250.0));
+
+    let results: Element<_> = if search_query.is_empty() {
+        column![].into()
+    } else if search_results.is_empty() {
+        text(strings.no_matching_events)
+            .size(14)
+            .style(palette.muted)
+            .into()
+    } else {
+        scrollable(column(
+            search_results
+                .into_iter()
+                .map(|(i, date, title)| {
+                    button(text(format!("{}  {title}", locale.format_date(date))).size(14))
+                        .style(theme::Button::Text)
+                        .padding(2)
+                        .on_press(Message::SearchResultSelected(i))
+                        .into()
+                })
+                .collect(),
+        ))
+        .height(Length::Fixed(120.0))
+        .into()
+    };
+
+    let picked_lunar = NaiveDate::from_ymd_opt(picked_date.year, picked_date.month, picked_date.day)
+        .map(|date| lunar::from_solar(date, lunar::VIETNAM).to_string())
+        .unwrap_or_default();
+
     let create_event_btn = Container::new(
         Row::new()
             .spacing(10)
             .align_items(Alignment::Center)
-            .push(Button::new(Text::new("Create event")).on_press(Message::OpenModal)));
+            .push(Button::new(Text::new(strings.create_event)).on_press(Message::OpenModal)));
 
     column![
         row![
             // horizontal_space(Length::Fill),
             row![
                 Modal::new(show_modal, create_event_btn, move ||  {
+                        // What the title is read as, before the event is created
+                        let preview: Element<Message> = match &preview {
+                            Some(preview) => text(preview.clone()).size(14).style(palette.muted).into(),
+                            None => column![].into(),
+                        };
+
+                        let overlaps: Element<Message> = match overlaps.as_slice() {
+                            [] => column![].into(),
+                            titles => text(format!("⚠ {}: {}", strings.overlaps_with, titles.join(", "))).size(14).style(palette.danger).into(),
+                        };
+
                         Card::new(
-                            Text::new("Create a new event"),
+                            Text::new(strings.create_new_event),
                             column![
                                 text_input(
-                                    "What needs to be done?",
+                                    strings.title_placeholder,
                                     &input_value,
                                     Message::TitleInputChanged,
                                 )
                                 .id(INPUT_ID.clone())
                                 .on_submit(Message::CreateEvent),
+                                preview,
+                                overlaps,
                                 row![
                                     DatePicker::new(
                                         show_picker,
                                         picked_date,
-                                        button("Set Date").style(theme::Button::Text).on_press(Message::ChooseDate),
+                                        button(strings.set_date).style(theme::Button::Text).on_press(Message::ChooseDate),
                                         Message::CancelDate,
                                         Message::SubmitDate,
                                     ),
-                                    text(format!("Date: {}", picked_date))
+                                    text(format!(
+                                        "{}: {}",
+                                        strings.date,
+                                        NaiveDate::from_ymd_opt(picked_date.year, picked_date.month, picked_date.day)
+                                            .map(|date| locale.format_date(date))
+                                            .unwrap_or_default(),
+                                    ))
                                 ]
                                 .align_items(alignment::Alignment::Center)
-                                .spacing(10)
+                                .spacing(10),
+                                row![
+                                    TimePicker::new(
+                                        show_time_picker,
+                                        picked_time.map_or_else(|| TimeModal::now_hm(true), TimeModal::from),
+                                        button(strings.set_time).style(theme::Button::Text).on_press(Message::ChooseTime),
+                                        Message::CancelTime,
+                                        Message::SubmitTime,
+                                    )
+                                    .use_24h(),
+                                    text(match picked_time {
+                                        Some(time) => format!("{}: {}", strings.time, time.format("%H:%M")),
+                                        None => strings.all_day.to_string(),
+                                    }),
+                                    horizontal_space(Length::Fill),
+                                    button(strings.all_day).style(theme::Button::Text).on_press(Message::ClearTime),
+                                ]
+                                .align_items(alignment::Alignment::Center)
+                                .spacing(10),
+                                row![
+                                    pick_list(
+                                        Category::EDITABLE
+                                            .iter()
+                                            .map(|&value| Labeled { value, label: locale.category_name(value) })
+                                            .collect::<Vec<_>>(),
+                                        Some(Labeled { value: picked_category, label: locale.category_name(picked_category) }),
+                                        |picked| Message::CategorySelected(picked.value),
+                                    ),
+                                    pick_list(zone::all(), Some(event_zone), Message::EventZoneSelected),
+                                ]
+                                .spacing(10),
+                                row![
+                                    text(strings.calendar),
+                                    pick_list(calendars.clone(), picked_calendar.clone(), |picked| Message::CalendarPicked(picked.id)),
+                                ]
+                                .align_items(alignment::Alignment::Center)
+                                .spacing(10),
+                                checkbox(
+                                    format!("{} {}", strings.every_year_lunar, picked_lunar),
+                                    repeat_lunar,
+                                    Message::RepeatLunarToggled,
+                                ),
                             ]
                             .spacing(10)
                         )
@@ -605,12 +1809,12 @@ fn view_controls<'a>(month_text: Text<'a>, year_text: Text<'a>, show_modal: bool
                                 .padding(5)
                                 .width(Length::Fill)
                                 .push(
-                                    Button::new(Text::new("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                                    Button::new(Text::new(strings.cancel).horizontal_alignment(alignment::Horizontal::Center))
                                         .width(Length::Fill)
                                         .on_press(Message::CloseModal),
                                 )
                                 .push(
-                                    Button::new(Text::new("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                                    Button::new(Text::new(strings.ok).horizontal_alignment(alignment::Horizontal::Center))
                                         .width(Length::Fill)
                                         .on_press(Message::CreateEvent),
                                 )
@@ -633,54 +1837,226 @@ fn view_controls<'a>(month_text: Text<'a>, year_text: Text<'a>, show_modal: bool
             .width(Length::Fill)
             .align_items(Alignment::Center),
             horizontal_space(Length::Fill),
+            search_input,
         ]
         .width(Length::Fill)
         .align_items(Alignment::Center),
 
-        row![
-            button("prev month")
-                .style(theme::Button::Text)
-                .on_press(Message::PrevMonth),
-            horizontal_space(Length::Fill),
-            button("next month")
-                .style(theme::Button::Text)
-                .on_press(Message::NextMonth),
-        ]
+        results,
+
+        match view_mode {
+            ViewMode::Month => row![
+                button(strings.prev_month)
+                    .style(theme::Button::Text)
+                    .on_press(Message::PrevMonth),
+                horizontal_space(Length::Fill),
+                button(strings.next_month)
+                    .style(theme::Button::Text)
+                    .on_press(Message::NextMonth),
+            ],
+            ViewMode::Week => row![
+                button(strings.prev_week)
+                    .style(theme::Button::Text)
+                    .on_press(Message::ShiftDays(-7)),
+                horizontal_space(Length::Fill),
+                button(strings.next_week)
+                    .style(theme::Button::Text)
+                    .on_press(Message::ShiftDays(7)),
+            ],
+            ViewMode::Day => row![
+                button(strings.prev_day)
+                    .style(theme::Button::Text)
+                    .on_press(Message::ShiftDays(-1)),
+                horizontal_space(Length::Fill),
+                button(strings.next_day)
+                    .style(theme::Button::Text)
+                    .on_press(Message::ShiftDays(1)),
+            ],
+        }
         .align_items(Alignment::Center)
         .width(Length::Fill)
     ].into()
 }
 
-// #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-// pub enum Filter {
-//     Holiday,
-//     Todo,
-//     Reminder,
-// }
-
-// impl Default for Filter {
-//     fn default() -> Self {
-//         Filter::All
-//     }
-// }
-
-// impl Filter {
-//     fn matches(&self, task: &Task) -> bool {
-//         match self {
-//             Filter::All => true,
-//             Filter::Active => !task.completed,
-//             Filter::Completed => task.completed,
-//         }
-//     }
-// }
+/// Calendar `id` new events are filed in, or the first one once it is gone
+fn picked_calendar(calendars: &[NamedCalendar], id: u64) -> Option<&NamedCalendar> {
+    calendars::find(calendars, id).or_else(|| calendars.first())
+}
+
+/// Whether the latest changes are on disk
+fn save_status_view<'a>(status: &SaveStatus, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    match status {
+        SaveStatus::Saved => text(strings.all_saved).size(14).style(palette.muted),
+        SaveStatus::Pending => text(strings.unsaved_changes).size(14).style(palette.muted),
+        SaveStatus::Saving => text(strings.saving).size(14).style(palette.muted),
+        SaveStatus::Failed(_) => text(strings.not_saved).size(14).style(palette.danger),
+    }
+    .into()
+}
+
+/// Errors reading or writing the calendar file, shown until an attempt
+/// succeeds
+fn error_banner<'a>(load_error: Option<&LoadError>, save_status: &SaveStatus, close_failed: bool, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let line = |message: String, retry: Message| {
+        row![
+            text(message).size(14).style(palette.danger).width(Length::Fill),
+            button(strings.retry).style(theme::Button::Text).on_press(retry),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    };
+
+    let mut banner = Column::new().padding(5);
+
+    if let Some(error) = load_error {
+        banner = banner.push(line(format!("{} {error}. {}", strings.load_failed, strings.changes_kept), Message::RetryLoad));
+    }
+
+    if let SaveStatus::Failed(error) = save_status {
+        banner = banner.push(line(format!("{} {error}", strings.save_failed), Message::RetrySave));
+
+        // Asked to close: closing anyway loses the changes
+        if close_failed {
+            banner = banner.push(
+                button(strings.close_unsaved)
+                    .style(theme::Button::Destructive)
+                    .on_press(Message::CloseUnsaved),
+            );
+        }
+    }
+
+    banner.into()
+}
+
+/// Read-only line for a public holiday
+fn holiday_view<'a>(holiday: &Holiday, palette: &Palette) -> Element<'a, Message> {
+    text(holiday.name)
+        .size(14)
+        .style(palette.category(Category::Holiday))
+        .into()
+}
+
+/// Read-only line for an event of a subscribed calendar
+fn subscribed_view<'a>(line: &str, palette: &Palette) -> Element<'a, Message> {
+    text(line.to_string())
+        .size(14)
+        .style(palette.subscribed)
+        .into()
+}
+
+/// View switcher, the time zones the calendar is displayed in, the
+/// lunar calendar overlay, save status, theme, language, the
+/// export buttons and, when `can_sync` is set, the sync button, enabled when
+/// it holds true
+#[allow(clippy::too_many_arguments)]
+fn view_display_controls<'a>(
+    view_mode: ViewMode,
+    display_zone: Tz,
+    secondary_zone: Option<Tz>,
+    show_lunar: bool,
+    save_status: Element<'a, Message>,
+    export_status: Option<String>,
+    can_sync: Option<bool>,
+    sync_status: Option<String>,
+    theme: ThemeChoice,
+    locale: Locale,
+) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let mode_button = |label: &'static str, mode: ViewMode| {
+        button(label)
+            .style(if mode == view_mode { theme::Button::Primary } else { theme::Button::Text })
+            .on_press(Message::ViewModeSelected(mode))
+    };
+
+    let mut secondary = row![
+        text(strings.second_zone).size(14),
+        pick_list(zone::all(), secondary_zone, Message::SecondaryZoneSelected),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    if secondary_zone.is_some() {
+        secondary = secondary.push(
+            button(delete_icon())
+                .style(theme::Button::Text)
+                .on_press(Message::ClearSecondaryZone),
+        );
+    }
+
+    let sync_button: Element<'a, Message> = match can_sync {
+        Some(enabled) => {
+            let sync = button(strings.sync).style(theme::Button::Text);
+
+            row![
+                text(sync_status.unwrap_or_default()).size(14),
+                if enabled { sync.on_press(Message::Sync) } else { sync },
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+        }
+        None => horizontal_space(Length::Shrink).into(),
+    };
 
+    column![
+        row![
+            mode_button(strings.month, ViewMode::Month),
+            mode_button(strings.week, ViewMode::Week),
+            mode_button(strings.day, ViewMode::Day),
+            horizontal_space(Length::Fill),
+            text(strings.time_zone).size(14),
+            pick_list(zone::all(), Some(display_zone), Message::DisplayZoneSelected),
+            secondary,
+            checkbox(strings.lunar_dates, show_lunar, Message::ShowLunarToggled),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+        row![
+            horizontal_space(Length::Fill),
+            text(strings.theme).size(14),
+            pick_list(
+                ThemeChoice::ALL
+                    .iter()
+                    .map(|&value| Labeled { value, label: locale.theme_name(value) })
+                    .collect::<Vec<_>>(),
+                Some(Labeled { value: theme, label: locale.theme_name(theme) }),
+                |picked| Message::ThemeSelected(picked.value),
+            ),
+            text(strings.language).size(14),
+            pick_list(Locale::ALL, Some(locale), Message::LocaleSelected),
+            save_status,
+            text(export_status.unwrap_or_default()).size(14),
+            button(strings.export_ics)
+                .style(theme::Button::Text)
+                .on_press(Message::ExportIcs),
+            button(strings.export_pdf)
+                .style(theme::Button::Text)
+                .on_press(Message::ExportPdf),
+            sync_button,
+            button(strings.settings)
+                .style(theme::Button::Text)
+                .on_press(Message::OpenSettings),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(5)
+    .padding(5)
+    .into()
+}
 
 ////////////////////////////////////////////////////////////////
 
 
 fn loading_message<'a>() -> Element<'a, Message> {
     container(
-        text("Loading...")
+        // The saved locale is not known until loading is done
+        text(Locale::default().strings().loading)
             .horizontal_alignment(alignment::Horizontal::Center)
             .size(50),
     )
@@ -691,55 +2067,6 @@ fn loading_message<'a>() -> Element<'a, Message> {
 }
 
 
-struct MyContainerStyle;
-
-impl container::StyleSheet for MyContainerStyle {
-  type Style = iced::Theme;
-  fn appearance(&self, style: &iced::Theme) -> container::Appearance {
-    container::Appearance {
-        border_width: 1.0,
-        border_radius: 1.0,
-        border_color: Color::BLACK,
-        ..Default::default()
-    }
-  }
-}
-
-struct MyScrollable;
-
-impl scrollable::StyleSheet for MyScrollable {
-    type Style = iced::Theme;
-    fn active(&self, style: &Self::Style) -> scrollable::Scrollbar {
-        scrollable::Scrollbar { 
-            background: Color::TRANSPARENT.into(), 
-            border_radius: 2.0, 
-            border_width: 0.0, 
-            border_color: Color::TRANSPARENT, 
-            scroller: scrollable::Scroller {
-                color: Color::TRANSPARENT,
-                border_radius: 2.0,
-                border_width: 0.0,
-                border_color: Color::TRANSPARENT,
-            },
-        }
-    }
-
-    fn hovered(&self, style: &Self::Style) -> scrollable::Scrollbar {
-        scrollable::Scrollbar { 
-            background: Color::TRANSPARENT.into(), 
-            border_radius: 2.0, 
-            border_width: 0.0, 
-            border_color: Color::TRANSPARENT, 
-            scroller: scrollable::Scroller {
-                color: Color::TRANSPARENT,
-                border_radius: 2.0,
-                border_width: 0.0,
-                border_color: Color::TRANSPARENT,
-            },
-        }
-    }
-}
-
 // Fonts
 const ICONS: Font = Font::External {
     name: "Icons",
@@ -763,85 +2090,68 @@ fn delete_icon() -> Text<'static> {
 }
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SavedState {
-    events: Vec<Event>,
-}
-
-#[derive(Debug, Clone)]
-enum LoadError {
-    File,
-    Format,
-}
-
-#[derive(Debug, Clone)]
-enum SaveError {
-    File,
-    Write,
-    Format,
-}
-
-
 #[cfg(not(target_arch = "wasm32"))]
-impl SavedState {
-    fn path() -> std::path::PathBuf {
-        let mut path = if let Some(project_dirs) =
-            directories_next::ProjectDirs::from("rs", "Iced", "CalendarApp")
-        {
-            project_dirs.data_dir().into()
-        } else {
-            std::env::current_dir().unwrap_or_default()
-        };
-
-        path.push("calendar.json");
-
-        path
-    }
+/// Write `contents` to `path`, next to the calendar file
+async fn export(contents: Vec<u8>, path: std::path::PathBuf) -> Result<std::path::PathBuf, SaveError> {
+    use async_std::prelude::*;
 
-    async fn load() -> Result<SavedState, LoadError> {
-        use async_std::prelude::*;
-
-        let mut contents = String::new();
-
-        let mut file = async_std::fs::File::open(Self::path())
+    if let Some(dir) = path.parent() {
+        async_std::fs::create_dir_all(dir)
             .await
-            .map_err(|_| LoadError::File)?;
+            .map_err(|error| SaveError::File(error.kind()))?;
+    }
 
-        file.read_to_string(&mut contents)
-            .await
-            .map_err(|_| LoadError::File)?;
+    let mut file = async_std::fs::File::create(&path)
+        .await
+        .map_err(|error| SaveError::File(error.kind()))?;
 
-        serde_json::from_str(&contents).map_err(|_| LoadError::Format)
-    }
+    file.write_all(&contents)
+        .await
+        .map_err(|error| SaveError::Write(error.kind()))?;
 
-    async fn save(self) -> Result<(), SaveError> {
-        use async_std::prelude::*;
+    Ok(path)
+}
 
-        let json = serde_json::to_string_pretty(&self)
-            .map_err(|_| SaveError::Format)?;
+#[cfg(not(target_arch = "wasm32"))]
+/// Print `grid` on the page of the settings, to `path`
+async fn export_pdf(grid: calendar_render::Grid, config: Config, path: std::path::PathBuf) -> Result<std::path::PathBuf, SaveError> {
+    let pdf = calendar_render::pdf::render(&grid, &config.palette().render_colors(), config.page_size, config.orientation)
+        .map_err(|error| SaveError::Format(error.to_string()))?;
 
-        let path = Self::path();
+    export(pdf, path).await
+}
 
-        if let Some(dir) = path.parent() {
-            async_std::fs::create_dir_all(dir)
-                .await
-                .map_err(|_| SaveError::File)?;
+#[cfg(not(target_arch = "wasm32"))]
+/// Sync with the CalDAV account of the settings, once the writer has saved
+/// what it holds
+async fn sync(writer: Option<writer::Writer>, config: Config) -> Result<SyncReport, String> {
+    async_std::task::spawn_blocking(move || {
+        if let Some(writer) = writer {
+            writer.flush().map_err(|error| error.to_string())?;
         }
 
-        {
-            let mut file = async_std::fs::File::create(path)
-                .await
-                .map_err(|_| SaveError::File)?;
+        caldav::sync(&config)
+    })
+    .await
+}
 
-            file.write_all(json.as_bytes())
-                .await
-                .map_err(|_| SaveError::Write)?;
-        }
+async fn load(path: std::path::PathBuf) -> Result<SavedState, LoadError> {
+    async_std::task::spawn_blocking(move || SavedState::load(&path)).await
+}
 
-        // This is a simple way to save at most once every couple seconds
-        async_std::task::sleep(std::time::Duration::from_secs(2)).await;
+/// Write what the writer holds now, off the window's thread, as the file
+/// can stay locked by the command line or a sync for a while
+async fn flush(writer: writer::Writer) -> Result<(), SaveError> {
+    async_std::task::spawn_blocking(move || writer.flush()).await
+}
 
-        Ok(())
+/// Read the calendar file at `path`, once the writer has saved what it
+/// holds to the one it replaces
+async fn reopen(writer: Option<writer::Writer>, path: std::path::PathBuf) -> Result<SavedState, LoadError> {
+    if let Some(writer) = writer {
+        // A failure is reported by the writer
+        let _ = flush(writer).await;
     }
-}
 
+    load(path).await
+}
@@ -0,0 +1,134 @@
+//! Month and week grids worded and colored as the window shows them, to
+//! be rendered without it.
+
+use std::collections::BTreeMap;
+
+use calendar_core::{calendars, date, holidays::Holiday, lunar, Calendar, Category, NamedCalendar};
+use calendar_render::{Cell, Entry, Grid};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::{
+    i18n::Locale,
+    style::{self, Palette},
+};
+
+/// Month of `date` as six weeks starting on `first_weekday`, like the
+/// month grid. Events of calendars hidden in `calendars` are left out.
+#[allow(clippy::too_many_arguments)]
+pub fn month(
+    date: NaiveDate,
+    calendar: &Calendar,
+    calendars: &[NamedCalendar],
+    holidays: &[Holiday],
+    subscribed: &BTreeMap<NaiveDate, Vec<String>>,
+    show_lunar: bool,
+    locale: Locale,
+    first_weekday: Weekday,
+    palette: Palette,
+) -> Grid {
+    let first = date::first_of_month(date);
+    let start = first - Duration::days(i64::from(date::days_since(first.weekday(), first_weekday)));
+    let mut cells = cells(first, date::last_of_month(date), calendar, calendars, holidays, subscribed, show_lunar, palette);
+
+    Grid {
+        title: format!("{} {}", locale.month_name(date), date.year()),
+        columns: weekdays(first_weekday, locale),
+        rows: (0..6)
+            .map(|week| (0..7).map(|day| cells.remove(&(start + Duration::days(week * 7 + day)))).collect())
+            .collect(),
+    }
+}
+
+/// The seven days from `from`, in one row
+#[allow(clippy::too_many_arguments)]
+pub fn week(
+    from: NaiveDate,
+    calendar: &Calendar,
+    calendars: &[NamedCalendar],
+    holidays: &[Holiday],
+    subscribed: &BTreeMap<NaiveDate, Vec<String>>,
+    show_lunar: bool,
+    locale: Locale,
+    palette: Palette,
+) -> Grid {
+    let to = from + Duration::days(6);
+    let mut cells = cells(from, to, calendar, calendars, holidays, subscribed, show_lunar, palette);
+
+    Grid {
+        title: format!("{} – {}", locale.format_date(from), locale.format_date(to)),
+        columns: weekdays(from.weekday(), locale),
+        rows: vec![(0..7).map(|day| cells.remove(&(from + Duration::days(day)))).collect()],
+    }
+}
+
+fn weekdays(first: Weekday, locale: Locale) -> Vec<String> {
+    std::iter::successors(Some(first), |day| Some(day.succ()))
+        .take(7)
+        .map(|day| locale.weekday_name(day).to_string())
+        .collect()
+}
+
+/// Every day from `from` to `to`: holidays, then events of subscribed
+/// calendars, then the events, marked with the color of their calendar
+#[allow(clippy::too_many_arguments)]
+fn cells(
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar: &Calendar,
+    calendars: &[NamedCalendar],
+    holidays: &[Holiday],
+    subscribed: &BTreeMap<NaiveDate, Vec<String>>,
+    show_lunar: bool,
+    palette: Palette,
+) -> BTreeMap<NaiveDate, Cell> {
+    let zone = calendar.zone();
+    let today = date::today(zone);
+    let mut occurrences = calendar.occurrences(from, to);
+
+    let line = |text: String, color| Entry { text, color: style::rgb(color), marker: None };
+
+    std::iter::successors(Some(from), |day| day.succ_opt())
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            let holidays = holidays
+                .iter()
+                .filter(|holiday| holiday.date == day)
+                .map(|holiday| line(holiday.name.to_string(), palette.category(Category::Holiday)));
+
+            let subscribed = subscribed
+                .get(&day)
+                .into_iter()
+                .flatten()
+                .map(|title| line(title.clone(), palette.subscribed));
+
+            let events = occurrences
+                .remove(&day)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|&i| calendars::is_visible(calendars, calendar.events()[i].calendar))
+                .map(|i| {
+                    let event = &calendar.events()[i];
+
+                    let text = match event.start_in(zone) {
+                        Some(start) => format!("{} {}", start.format("%H:%M"), event.title),
+                        None => event.title.clone(),
+                    };
+
+                    Entry {
+                        text,
+                        color: style::rgb(palette.category(event.category)),
+                        marker: Some(calendars::find(calendars, event.calendar).map_or(style::rgb(palette.muted), |calendar| calendar.color)),
+                    }
+                });
+
+            let cell = Cell {
+                label: day.day().to_string(),
+                note: if show_lunar { lunar::from_solar(day, lunar::VIETNAM).to_string() } else { String::new() },
+                emphasized: day == today,
+                entries: holidays.chain(subscribed).chain(events).collect(),
+            };
+
+            (day, cell)
+        })
+        .collect()
+}
@@ -0,0 +1,188 @@
+//! Read-only iCalendar feeds of the calendar, served over HTTP for phones
+//! and other programs on the network to subscribe to with `webcal://`.
+//!
+//! - `/calendar.ics`: every event
+//! - `/calendars/{id}.ics`: the events of one named calendar
+//!
+//! Both take `?category=Todo,Reminder` to keep some categories only, and
+//! `?key=` when the settings ask for one. Holidays are generated rather
+//! than stored, so feeds have none.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use calendar_core::{calendars, ics, Category, Event, NamedCalendar};
+use iced::futures::future;
+use iced::{subscription, Subscription};
+use tiny_http::{Header, Method};
+
+use crate::{api, config::Publish};
+
+/// Events and calendars the feeds are made from
+#[derive(Default)]
+struct Snapshot {
+    events: Vec<Event>,
+    calendars: Vec<NamedCalendar>,
+}
+
+/// Handle giving the server the events as the window has them
+#[derive(Clone)]
+pub struct Publisher {
+    snapshot: Arc<RwLock<Snapshot>>,
+}
+
+impl std::fmt::Debug for Publisher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Publisher")
+    }
+}
+
+impl Publisher {
+    /// Serve `events` filed in `calendars` from the next request on
+    pub fn update(&self, events: &[Event], calendars: &[NamedCalendar]) {
+        if let Ok(mut snapshot) = self.snapshot.write() {
+            snapshot.events = events.to_vec();
+            snapshot.calendars = calendars.to_vec();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Status {
+    /// The server is listening, waiting for the events
+    Ready(Publisher),
+    /// The server could not start, it is tried again later
+    Failed(String),
+}
+
+/// Stops the server thread when the subscription is dropped
+struct Running(Arc<AtomicBool>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+enum Run {
+    Starting(Publish),
+    Serving(Running),
+    Retrying(Publish),
+}
+
+/// Serve the feeds on the address of `publish`
+pub fn serve(publish: &Publish) -> Subscription<Status> {
+    subscription::unfold(publish.clone(), Run::Starting(publish.clone()), |run| async move {
+        match run {
+            Run::Starting(publish) => match tiny_http::Server::http(publish.address.as_str()) {
+                Ok(server) => {
+                    let publisher = Publisher {
+                        snapshot: Arc::default(),
+                    };
+                    let stop = Arc::new(AtomicBool::new(false));
+
+                    let (snapshot, stopped) = (publisher.snapshot.clone(), stop.clone());
+                    std::thread::spawn(move || listen(server, publish, snapshot, stopped));
+
+                    (Some(Status::Ready(publisher)), Run::Serving(Running(stop)))
+                }
+                Err(error) => (
+                    Some(Status::Failed(format!("cannot listen on {}: {error}", publish.address))),
+                    Run::Retrying(publish),
+                ),
+            },
+            // Held until the subscription is dropped, stopping the server
+            Run::Serving(_running) => future::pending().await,
+            // The address may be held by the previous server for a moment
+            Run::Retrying(publish) => {
+                async_std::task::sleep(Duration::from_secs(5)).await;
+
+                (None, Run::Starting(publish))
+            }
+        }
+    })
+}
+
+fn listen(server: tiny_http::Server, publish: Publish, snapshot: Arc<RwLock<Snapshot>>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        let request = match server.recv_timeout(Duration::from_secs(1)) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(_) => return,
+        };
+
+        let answer = match request.method() {
+            Method::Get | Method::Head => match snapshot.read() {
+                Ok(snapshot) => document(request.url(), &publish, &snapshot),
+                Err(_) => Err((500, "the calendar is unavailable")),
+            },
+            _ => Err((405, "feeds are read-only")),
+        };
+
+        let response = match answer {
+            Ok(document) => {
+                let mut response = tiny_http::Response::from_string(document);
+
+                if let Ok(header) = Header::from_bytes("Content-Type", "text/calendar; charset=utf-8") {
+                    response = response.with_header(header);
+                }
+
+                response
+            }
+            Err((status, reason)) => tiny_http::Response::from_string(reason).with_status_code(status),
+        };
+
+        let _ = request.respond(response);
+    }
+}
+
+/// Feed at `url`, or the status and reason it cannot be served
+fn document(url: &str, publish: &Publish, snapshot: &Snapshot) -> Result<String, (u16, &'static str)> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let parameter = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+
+    if publish.key.as_deref().is_some_and(|key| !parameter("key").is_some_and(|given| api::same_secret(given, key))) {
+        return Err((403, "wrong key"));
+    }
+
+    let categories = match parameter("category") {
+        Some(names) => names
+            .split(',')
+            .map(|name| Category::EDITABLE.iter().copied().find(|category| category.to_string().eq_ignore_ascii_case(name)))
+            .collect::<Option<Vec<Category>>>()
+            .ok_or((400, "the category must be Todo or Reminder"))?,
+        None => Category::EDITABLE.to_vec(),
+    };
+
+    let (name, calendar) = match path {
+        "/calendar.ics" => ("Calendar".to_string(), None),
+        _ => {
+            let id = path
+                .strip_prefix("/calendars/")
+                .and_then(|file| file.strip_suffix(".ics"))
+                .and_then(|id| id.parse().ok());
+
+            match id.and_then(|id| calendars::find(&snapshot.calendars, id)) {
+                Some(calendar) => (calendar.name.clone(), Some(calendar.id)),
+                None => return Err((404, "no such calendar")),
+            }
+        }
+    };
+
+    let events: Vec<Event> = snapshot
+        .events
+        .iter()
+        .filter(|event| calendar.is_none_or(|id| event.calendar == id))
+        .filter(|event| categories.contains(&event.category))
+        .cloned()
+        .collect();
+
+    Ok(ics::feed(&name, &events, publish.refresh_minutes))
+}
@@ -0,0 +1,262 @@
+//! Settings panel, editing the config file.
+
+use std::path::PathBuf;
+
+use calendar_core::holidays::Country;
+use calendar_render::{Orientation, PageSize};
+use chrono::Weekday;
+use iced::{
+    alignment,
+    widget::{checkbox, column, pick_list, row, text, text_input, Button, Column, Row, Text},
+    theme, Alignment, Element, Length,
+};
+use iced_aw::Card;
+
+use crate::{
+    config::{Config, ConfigError, Field, Publish},
+    i18n::{Labeled, Locale},
+    style::Palette,
+    Message,
+};
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    DataFileChanged(String),
+    FirstWeekdaySelected(Option<Weekday>),
+    PageSizeSelected(PageSize),
+    OrientationSelected(Orientation),
+    EventDurationChanged(String),
+    ReminderChanged(String),
+    WindowWidthChanged(String),
+    WindowHeightChanged(String),
+    HolidayCountryToggled(Country, bool),
+}
+
+/// Settings being edited, as typed
+#[derive(Debug, Clone, Default)]
+pub struct SettingsForm {
+    pub data_file: String,
+    pub first_weekday: Option<Weekday>,
+    pub page_size: PageSize,
+    pub orientation: Orientation,
+    pub event_duration: String,
+    pub reminder: String,
+    pub window_width: String,
+    pub window_height: String,
+    pub holiday_countries: Vec<Country>,
+    /// Where feeds are served, set in the file only
+    pub publish: Option<Publish>,
+    pub errors: Vec<ConfigError>,
+}
+
+impl SettingsForm {
+    pub fn update(&mut self, message: SettingsMessage) {
+        match message {
+            SettingsMessage::DataFileChanged(value) => self.data_file = value,
+            SettingsMessage::FirstWeekdaySelected(weekday) => self.first_weekday = weekday,
+            SettingsMessage::PageSizeSelected(size) => self.page_size = size,
+            SettingsMessage::OrientationSelected(orientation) => self.orientation = orientation,
+            SettingsMessage::EventDurationChanged(value) => self.event_duration = value,
+            SettingsMessage::ReminderChanged(value) => self.reminder = value,
+            SettingsMessage::WindowWidthChanged(value) => self.window_width = value,
+            SettingsMessage::WindowHeightChanged(value) => self.window_height = value,
+            SettingsMessage::HolidayCountryToggled(country, enabled) => {
+                self.holiday_countries.retain(|&other| other != country);
+
+                if enabled {
+                    self.holiday_countries.push(country);
+                }
+            }
+        }
+    }
+
+    pub fn new(config: &Config) -> Self {
+        SettingsForm {
+            data_file: config.data_file.as_ref().map(|path| path.display().to_string()).unwrap_or_default(),
+            first_weekday: config.first_weekday,
+            page_size: config.page_size,
+            orientation: config.orientation,
+            event_duration: config.event_duration.to_string(),
+            reminder: config.reminder.map(|minutes| minutes.to_string()).unwrap_or_default(),
+            window_width: config.window_width.to_string(),
+            window_height: config.window_height.to_string(),
+            holiday_countries: config.holiday_countries(),
+            publish: config.publish.clone(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// `config` with the edited settings, or every reason they are invalid
+    pub fn apply(&self, config: &Config) -> Result<Config, Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        let mut number = |value: &str, field: Field| match value.trim().parse::<u32>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                errors.push(ConfigError::NotANumber(field));
+                None
+            }
+        };
+
+        let event_duration = number(&self.event_duration, Field::EventDuration);
+        let reminder = if self.reminder.trim().is_empty() {
+            Some(None)
+        } else {
+            number(&self.reminder, Field::Reminder).map(Some)
+        };
+        let window_width = number(&self.window_width, Field::WindowWidth);
+        let window_height = number(&self.window_height, Field::WindowHeight);
+
+        let (Some(event_duration), Some(reminder), Some(window_width), Some(window_height)) =
+            (event_duration, reminder, window_width, window_height)
+        else {
+            return Err(errors);
+        };
+
+        let data_file = self.data_file.trim();
+
+        let config = Config {
+            data_file: (!data_file.is_empty()).then(|| PathBuf::from(data_file)),
+            first_weekday: self.first_weekday,
+            page_size: self.page_size,
+            orientation: self.orientation,
+            event_duration,
+            reminder,
+            window_width,
+            window_height,
+            holiday_countries: Some(self.holiday_countries.iter().map(|country| country.code().to_string()).collect()),
+            ..config.clone()
+        };
+
+        let errors = config.validate();
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Card listing every setting of the config file, with the reasons the
+/// last attempt to save or load them failed
+pub fn view<'a>(form: &SettingsForm, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let field = |label: &str, placeholder: &str, value: &str, on_change: fn(String) -> SettingsMessage| {
+        column![
+            text(label.to_string()).size(14).style(palette.muted),
+            text_input(placeholder, value, move |value| Message::Settings(on_change(value))).padding(5),
+        ]
+        .spacing(2)
+    };
+
+    let weekdays: Vec<Labeled<Option<Weekday>>> = std::iter::once(Labeled {
+        value: None,
+        label: strings.locale_default,
+    })
+    .chain(
+        std::iter::successors(Some(Weekday::Mon), |day| Some(day.succ()))
+            .take(7)
+            .map(|day| Labeled {
+                value: Some(day),
+                label: locale.weekday_name(day),
+            }),
+    )
+    .collect();
+
+    let picked_weekday = weekdays.iter().copied().find(|weekday| weekday.value == form.first_weekday);
+
+    let orientations: Vec<Labeled<Orientation>> = [Orientation::Portrait, Orientation::Landscape]
+        .into_iter()
+        .map(|value| Labeled { value, label: locale.orientation_name(value) })
+        .collect();
+
+    let picked_orientation = orientations.iter().copied().find(|orientation| orientation.value == form.orientation);
+
+    let holidays = Country::ALL.iter().fold(Row::new().spacing(10), |row, &country| {
+        row.push(checkbox(
+            locale.country_name(country),
+            form.holiday_countries.contains(&country),
+            move |enabled| Message::Settings(SettingsMessage::HolidayCountryToggled(country, enabled)),
+        ))
+    });
+
+    // Feeds reachable from the network hand out every event, notes included
+    let publishing = form.publish.iter().fold(Column::new().spacing(2), |publishing, publish| {
+        let publishing = publishing.push(text(format!("{} {}", strings.feeds_served_on, publish.address)).size(14).style(palette.muted));
+
+        match (publish.is_local(), &publish.key) {
+            (true, _) => publishing,
+            (false, Some(_)) => publishing.push(text(strings.feeds_on_network).size(14).style(palette.muted)),
+            (false, None) => publishing.push(text(strings.feeds_open_to_network).size(14).style(palette.danger)),
+        }
+    });
+
+    let errors = form.errors.iter().fold(Column::new().spacing(2), |errors, error| {
+        errors.push(text(error.message(locale)).size(14).style(palette.danger))
+    });
+
+    Card::new(
+        Text::new(strings.settings),
+        column![
+            field(strings.data_file, &Config::default().data_path().display().to_string(), &form.data_file, SettingsMessage::DataFileChanged),
+            column![
+                text(strings.first_weekday).size(14).style(palette.muted),
+                pick_list(weekdays, picked_weekday, |picked| {
+                    Message::Settings(SettingsMessage::FirstWeekdaySelected(picked.value))
+                }),
+            ]
+            .spacing(2),
+            field(strings.event_duration, "60", &form.event_duration, SettingsMessage::EventDurationChanged),
+            field(strings.reminder, strings.no_reminder, &form.reminder, SettingsMessage::ReminderChanged),
+            column![
+                text(strings.printed_page).size(14).style(palette.muted),
+                row![
+                    pick_list(&PageSize::ALL[..], Some(form.page_size), |size| Message::Settings(SettingsMessage::PageSizeSelected(size))),
+                    pick_list(orientations, picked_orientation, |picked| {
+                        Message::Settings(SettingsMessage::OrientationSelected(picked.value))
+                    }),
+                ]
+                .spacing(10),
+            ]
+            .spacing(2),
+            column![text(strings.holidays).size(14).style(palette.muted), holidays].spacing(2),
+            row![
+                field(strings.window_width, "1200", &form.window_width, SettingsMessage::WindowWidthChanged),
+                field(strings.window_height, "850", &form.window_height, SettingsMessage::WindowHeightChanged),
+            ]
+            .spacing(10),
+            text(strings.restart_needed).size(14).style(palette.muted),
+            publishing,
+            errors,
+        ]
+        .spacing(10),
+    )
+    .foot(
+        Row::new()
+            .spacing(10)
+            .padding(5)
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(
+                Button::new(Text::new(strings.reload).horizontal_alignment(alignment::Horizontal::Center))
+                    .width(Length::Fill)
+                    .style(theme::Button::Text)
+                    .on_press(Message::ReloadSettings),
+            )
+            .push(
+                Button::new(Text::new(strings.cancel).horizontal_alignment(alignment::Horizontal::Center))
+                    .width(Length::Fill)
+                    .on_press(Message::CloseSettings),
+            )
+            .push(
+                Button::new(Text::new(strings.save).horizontal_alignment(alignment::Horizontal::Center))
+                    .width(Length::Fill)
+                    .on_press(Message::SaveSettings),
+            ),
+    )
+    .max_width(450.0)
+    .on_close(Message::CloseSettings)
+    .into()
+}
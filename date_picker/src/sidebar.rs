@@ -0,0 +1,67 @@
+//! Named calendars listed beside the grid, to show or hide their events,
+//! change their color, add and remove them.
+
+use calendar_core::NamedCalendar;
+use iced::{
+    theme,
+    widget::{button, checkbox, column, row, text, text_input, Column},
+    Alignment, Element, Length,
+};
+
+use crate::{delete_icon, i18n::Locale, style::{self, Palette}, Message};
+
+const WIDTH: f32 = 180.0;
+
+/// Every calendar with its color and visibility, and the field naming a new
+/// one
+pub fn view<'a>(calendars: &[NamedCalendar], new_name: &str, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let listed = calendars.iter().fold(Column::new().spacing(2), |listed, calendar| {
+        let id = calendar.id;
+
+        // The last calendar stays, events need one to be filed in
+        let remove: Element<'a, Message> = if calendars.len() > 1 {
+            button(delete_icon())
+                .style(theme::Button::Text)
+                .padding(2)
+                .on_press(Message::RemoveCalendar(id))
+                .into()
+        } else {
+            column![].into()
+        };
+
+        listed.push(
+            row![
+                button(text("●").size(18).style(style::calendar_color(calendar.color)))
+                    .style(theme::Button::Text)
+                    .padding(2)
+                    .on_press(Message::CalendarRecolored(id)),
+                checkbox(calendar.name.clone(), calendar.visible, move |visible| Message::CalendarToggled(id, visible))
+                    .width(Length::Fill),
+                remove,
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+        )
+    });
+
+    column![
+        text(strings.calendars).size(16).style(palette.muted),
+        listed,
+        row![
+            text_input(strings.calendar_placeholder, new_name, Message::CalendarNameChanged)
+                .on_submit(Message::AddCalendar)
+                .padding(5),
+            button(strings.add_calendar)
+                .style(theme::Button::Text)
+                .on_press(Message::AddCalendar),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(10)
+    .padding(5)
+    .width(Length::Fixed(WIDTH))
+    .into()
+}
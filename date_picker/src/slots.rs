@@ -0,0 +1,129 @@
+//! Finding free time for a new event: slots of the asked length within
+//! working hours, across the calendars shown, offered in the week view.
+
+use calendar_core::{
+    free_time::{self, Slot, SlotSearch},
+    Calendar, NamedCalendar,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use iced::{
+    theme,
+    widget::{button, checkbox, row, text, text_input},
+    Alignment, Element, Length,
+};
+
+use crate::{config::Config, i18n::Locale, style::Palette, Message};
+
+/// Days searched when none are typed
+const DAYS: u32 = 7;
+
+#[derive(Debug, Clone)]
+pub enum SlotMessage {
+    TitleChanged(String),
+    MinutesChanged(String),
+    DaysChanged(String),
+    WeekendsToggled(bool),
+    Find,
+    Clear,
+}
+
+/// Search for free time, as typed, with what it found
+#[derive(Debug, Clone, Default)]
+pub struct SlotFinder {
+    /// Title of the event created on the chosen slot
+    pub title: String,
+    /// Length of the event, the configured one when empty
+    pub minutes: String,
+    /// Days to search from today, a week when empty
+    pub days: String,
+    pub weekends: bool,
+    /// Slots of the last search
+    pub slots: Vec<Slot>,
+    /// The last search found nothing, or could not be made
+    pub none_found: bool,
+}
+
+impl SlotFinder {
+    pub fn update(&mut self, message: SlotMessage) {
+        match message {
+            SlotMessage::TitleChanged(value) => self.title = value,
+            SlotMessage::MinutesChanged(value) => self.minutes = value,
+            SlotMessage::DaysChanged(value) => self.days = value,
+            SlotMessage::WeekendsToggled(weekends) => self.weekends = weekends,
+            SlotMessage::Find => {}
+            SlotMessage::Clear => {
+                self.slots.clear();
+                self.none_found = false;
+            }
+        }
+    }
+
+    /// Look for slots from `today` on in the calendars of `calendar` shown,
+    /// skipping `days_off`
+    pub fn find(&mut self, calendar: &Calendar, calendars: &[NamedCalendar], today: NaiveDate, now: NaiveDateTime, days_off: Vec<NaiveDate>, config: &Config) {
+        let number = |value: &str, default: u32, most: u32| match value.trim() {
+            "" => Some(default),
+            value => value.parse::<u32>().ok().filter(|number| (1..=most).contains(number)),
+        };
+
+        self.slots = match (number(&self.minutes, config.event_duration, 24 * 60), number(&self.days, DAYS, 366)) {
+            (Some(minutes), Some(days)) => {
+                let search = SlotSearch {
+                    from: today,
+                    to: today + Duration::days(i64::from(days) - 1),
+                    minutes,
+                    day_start: config.work_start,
+                    day_end: config.work_end,
+                    weekdays_only: !self.weekends,
+                    days_off,
+                    calendars: calendars.iter().filter(|named| named.visible).map(|named| named.id).collect(),
+                    not_before: Some(now),
+                };
+
+                free_time::find(calendar, &search)
+            }
+            _ => Vec::new(),
+        };
+
+        self.none_found = self.slots.is_empty();
+    }
+}
+
+/// The search fields, and how many slots were found
+pub fn view<'a>(finder: &SlotFinder, config: &Config, locale: Locale, palette: Palette) -> Element<'a, Message> {
+    let strings = locale.strings();
+
+    let found = if finder.none_found {
+        text(strings.no_free_slots).size(14).style(palette.danger)
+    } else if finder.slots.is_empty() {
+        text(format!("{}–{}", config.work_start.format("%H:%M"), config.work_end.format("%H:%M"))).size(14).style(palette.muted)
+    } else {
+        text(format!("{} {}", finder.slots.len(), strings.free_slots_found)).size(14).style(palette.muted)
+    };
+
+    row![
+        text(strings.find_time).size(14),
+        text_input(strings.title_placeholder, &finder.title, |value| Message::Slots(SlotMessage::TitleChanged(value)))
+            .on_submit(Message::Slots(SlotMessage::Find))
+            .padding(5)
+            .width(Length::Fixed(200.0)),
+        text_input(&config.event_duration.to_string(), &finder.minutes, |value| Message::Slots(SlotMessage::MinutesChanged(value)))
+            .on_submit(Message::Slots(SlotMessage::Find))
+            .padding(5)
+            .width(Length::Fixed(60.0)),
+        text(strings.minutes_short).size(14),
+        text_input(&DAYS.to_string(), &finder.days, |value| Message::Slots(SlotMessage::DaysChanged(value)))
+            .on_submit(Message::Slots(SlotMessage::Find))
+            .padding(5)
+            .width(Length::Fixed(50.0)),
+        text(strings.days_ahead).size(14),
+        checkbox(strings.weekends, finder.weekends, |weekends| Message::Slots(SlotMessage::WeekendsToggled(weekends))),
+        button(strings.find).style(theme::Button::Text).on_press(Message::Slots(SlotMessage::Find)),
+        found.width(Length::Fill),
+        button(strings.clear).style(theme::Button::Text).on_press(Message::Slots(SlotMessage::Clear)),
+    ]
+    .spacing(5)
+    .padding(5)
+    .align_items(Alignment::Center)
+    .into()
+}
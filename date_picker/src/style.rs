@@ -0,0 +1,294 @@
+//! Color palettes and the style sheets built on them.
+
+use iced::{
+    theme::{self, Theme},
+    widget::{container, scrollable},
+    Background, Color,
+};
+use calendar_core::{calendars::Rgb, Category};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+    /// Palette of the `custom_palette` table of the config file
+    Custom,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 4] = [
+        ThemeChoice::Light,
+        ThemeChoice::Dark,
+        ThemeChoice::HighContrast,
+        ThemeChoice::Custom,
+    ];
+}
+
+/// Every color of the interface. A custom palette may leave some out,
+/// they are then taken from the light one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    #[serde(with = "hex")]
+    pub background: Color,
+    #[serde(with = "hex")]
+    pub text: Color,
+    /// Headers, labels and days of the month
+    #[serde(with = "hex")]
+    pub muted: Color,
+    /// Grid lines
+    #[serde(with = "hex")]
+    pub border: Color,
+    /// Today and highlighted events
+    #[serde(with = "hex")]
+    pub accent: Color,
+    #[serde(with = "hex")]
+    pub lunar: Color,
+    #[serde(with = "hex")]
+    pub primary: Color,
+    #[serde(with = "hex")]
+    pub success: Color,
+    #[serde(with = "hex")]
+    pub danger: Color,
+    #[serde(with = "hex")]
+    pub todo: Color,
+    #[serde(with = "hex")]
+    pub reminder: Color,
+    #[serde(with = "hex")]
+    pub holiday: Color,
+    /// Events of subscribed calendars
+    #[serde(with = "hex")]
+    pub subscribed: Color,
+}
+
+impl Palette {
+    pub fn light() -> Palette {
+        Palette {
+            background: Color::WHITE,
+            text: Color::BLACK,
+            muted: Color::from_rgb(0.6, 0.6, 0.6),
+            border: Color::BLACK,
+            accent: Color::from_rgb8(44, 138, 252),
+            lunar: Color::from_rgb(0.8, 0.3, 0.3),
+            primary: Color::from_rgb8(0x5E, 0x7C, 0xE2),
+            success: Color::from_rgb8(0x12, 0x66, 0x4F),
+            danger: Color::from_rgb8(0xC3, 0x42, 0x3F),
+            todo: Color::from_rgb(0.2, 0.2, 0.2),
+            reminder: Color::from_rgb(0.9, 0.55, 0.1),
+            holiday: Color::from_rgb(0.8, 0.3, 0.3),
+            subscribed: Color::from_rgb(0.35, 0.5, 0.65),
+        }
+    }
+
+    pub fn dark() -> Palette {
+        Palette {
+            background: Color::from_rgb8(0x20, 0x22, 0x25),
+            text: Color::from_rgb(0.9, 0.9, 0.9),
+            muted: Color::from_rgb(0.55, 0.55, 0.55),
+            border: Color::from_rgb(0.35, 0.35, 0.35),
+            accent: Color::from_rgb(0.4, 0.65, 1.0),
+            lunar: Color::from_rgb(0.95, 0.5, 0.5),
+            todo: Color::from_rgb(0.85, 0.85, 0.85),
+            reminder: Color::from_rgb(1.0, 0.7, 0.3),
+            holiday: Color::from_rgb(0.95, 0.5, 0.5),
+            subscribed: Color::from_rgb(0.55, 0.7, 0.85),
+            ..Palette::light()
+        }
+    }
+
+    pub fn high_contrast() -> Palette {
+        Palette {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            muted: Color::WHITE,
+            border: Color::WHITE,
+            accent: Color::from_rgb(1.0, 1.0, 0.0),
+            lunar: Color::from_rgb(0.0, 1.0, 1.0),
+            primary: Color::from_rgb(0.0, 0.4, 1.0),
+            success: Color::from_rgb(0.0, 0.8, 0.0),
+            danger: Color::from_rgb(1.0, 0.2, 0.2),
+            todo: Color::WHITE,
+            reminder: Color::from_rgb(1.0, 0.75, 0.0),
+            holiday: Color::from_rgb(0.0, 1.0, 1.0),
+            subscribed: Color::from_rgb(0.6, 1.0, 0.6),
+        }
+    }
+
+    /// Palette of `choice`, `custom` being the one loaded from disk, if any
+    pub fn of(choice: ThemeChoice, custom: Option<Palette>) -> Palette {
+        match choice {
+            ThemeChoice::Light => Palette::light(),
+            ThemeChoice::Dark => Palette::dark(),
+            ThemeChoice::HighContrast => Palette::high_contrast(),
+            ThemeChoice::Custom => custom.unwrap_or_default(),
+        }
+    }
+
+    /// Text color of events of `category`
+    pub fn category(&self, category: Category) -> Color {
+        match category {
+            Category::Todo => self.todo,
+            Category::Reminder => self.reminder,
+            Category::Holiday => self.holiday,
+        }
+    }
+
+    /// Colors of the grids rendered without the window
+    pub fn render_colors(&self) -> calendar_render::Colors {
+        calendar_render::Colors {
+            background: rgb(self.background),
+            text: rgb(self.text),
+            muted: rgb(self.muted),
+            border: rgb(self.border),
+            accent: rgb(self.accent),
+            note: rgb(self.lunar),
+        }
+    }
+
+    /// Theme of the built-in widgets
+    pub fn theme(&self) -> Theme {
+        Theme::custom(theme::Palette {
+            background: self.background,
+            text: self.text,
+            primary: self.primary,
+            success: self.success,
+            danger: self.danger,
+        })
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::light()
+    }
+}
+
+/// Color of a named calendar, the same in every theme
+pub fn calendar_color(color: Rgb) -> Color {
+    Color::from_rgb8(color.0, color.1, color.2)
+}
+
+/// `color` without its transparency
+pub fn rgb(color: Color) -> Rgb {
+    let [r, g, b, _] = color.into_rgba8();
+
+    Rgb(r, g, b)
+}
+
+// The colors iced's theme has no slot for (grid lines, accent, muted
+// text) travel with the style sheets; the rest comes from the theme, so
+// both always agree on the palette in use.
+
+/// Cells of the month grid and the timelines
+pub struct CellStyle(pub Palette);
+
+impl container::StyleSheet for CellStyle {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Theme) -> container::Appearance {
+        container::Appearance {
+            text_color: Some(style.palette().text),
+            border_width: 1.0,
+            border_radius: 1.0,
+            border_color: self.0.border,
+            ..Default::default()
+        }
+    }
+}
+
+/// Event picked from the search results
+pub struct HighlightStyle(pub Palette);
+
+impl container::StyleSheet for HighlightStyle {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Theme) -> container::Appearance {
+        container::Appearance {
+            background: Some(Background::Color(Color { a: 0.2, ..self.0.accent })),
+            text_color: Some(style.palette().text),
+            border_width: 1.0,
+            border_radius: 2.0,
+            border_color: self.0.accent,
+        }
+    }
+}
+
+/// Scrollable list of events of a day, with a scroller only shown on hover
+pub struct EventListStyle(pub Palette);
+
+impl scrollable::StyleSheet for EventListStyle {
+    type Style = Theme;
+
+    fn active(&self, _style: &Theme) -> scrollable::Scrollbar {
+        scrollbar(Color::TRANSPARENT)
+    }
+
+    fn hovered(&self, _style: &Theme) -> scrollable::Scrollbar {
+        scrollbar(Color { a: 0.5, ..self.0.muted })
+    }
+}
+
+pub fn cell(palette: Palette) -> theme::Container {
+    theme::Container::Custom(Box::new(CellStyle(palette)))
+}
+
+pub fn highlight(palette: Palette) -> theme::Container {
+    theme::Container::Custom(Box::new(HighlightStyle(palette)))
+}
+
+pub fn event_list(palette: Palette) -> theme::Scrollable {
+    theme::Scrollable::Custom(Box::new(EventListStyle(palette)))
+}
+
+fn scrollbar(scroller: Color) -> scrollable::Scrollbar {
+    scrollable::Scrollbar {
+        background: Color::TRANSPARENT.into(),
+        border_radius: 2.0,
+        border_width: 0.0,
+        border_color: Color::TRANSPARENT,
+        scroller: scrollable::Scroller {
+            color: scroller,
+            border_radius: 2.0,
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        },
+    }
+}
+
+/// Colors written as `#rrggbb` or `#rrggbbaa`
+mod hex {
+    use iced::Color;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b, a] = color.into_rgba8();
+
+        if a == u8::MAX {
+            serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}"))
+        } else {
+            serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}{a:02x}"))
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        parse(&value).ok_or_else(|| D::Error::custom(format!("invalid color `{value}`, expected #rrggbb")))
+    }
+
+    fn parse(value: &str) -> Option<Color> {
+        let digits = value.strip_prefix('#')?;
+
+        if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        let alpha = if digits.len() == 8 { channel(6)? } else { u8::MAX };
+
+        Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, f32::from(alpha) / 255.0))
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike};
+use chrono_tz::Tz;
+use iced::{
+    alignment,
+    theme,
+    widget::{button, column, scrollable, text, Column, Container, Row},
+    Element, Length,
+};
+
+use calendar_core::{calendars, free_time::Slot, holidays::Holiday, zone, Calendar, NamedCalendar};
+
+use crate::{event_view, holiday_view, i18n::Locale, style::{self, Palette}, Message};
+
+const HOUR_HEIGHT: f32 = 48.0;
+const LABEL_WIDTH: f32 = 70.0;
+
+/// Hour-by-hour columns for the `days` days starting at `from`, laid out in
+/// the zone of `calendar`, with an optional column of hour labels in `secondary_zone`.
+/// Events of calendars hidden in `calendars` are left out, `conflicting`
+/// ones marked, and free `slots` are offered in the hour they start.
+#[allow(clippy::too_many_arguments)]
+pub fn view<'a>(
+    calendar: &'a Calendar,
+    calendars: &'a [NamedCalendar],
+    from: NaiveDate,
+    days: i64,
+    secondary_zone: Option<Tz>,
+    editing: Option<usize>,
+    highlighted_event: Option<usize>,
+    conflicting: &HashSet<usize>,
+    holidays: &[Holiday],
+    slots: &[Slot],
+    locale: Locale,
+    palette: Palette,
+) -> Element<'a, Message> {
+    let display_zone = calendar.zone();
+    let dates: Vec<NaiveDate> = (0..days).map(|offset| from + Duration::days(offset)).collect();
+    let to = from + Duration::days(days - 1);
+
+    // For every day: all-day events, then timed events by starting hour
+    let mut all_day: Vec<Vec<usize>> = vec![Vec::new(); dates.len()];
+    let mut by_hour: Vec<Vec<Vec<usize>>> = vec![vec![Vec::new(); 24]; dates.len()];
+
+    for (date, positions) in calendar.occurrences(from, to) {
+        let day = (date - from).num_days() as usize;
+
+        for i in positions.into_iter().filter(|&i| calendars::is_visible(calendars, calendar.events()[i].calendar)) {
+            match calendar.events()[i].start_in(display_zone) {
+                Some(start) => by_hour[day][start.hour() as usize].push(i),
+                None => all_day[day].push(i),
+            }
+        }
+    }
+
+    let event_element = move |i: usize| -> Element<'a, Message> {
+        event_view::view(&calendar.events()[i], i, editing == Some(i), highlighted_event == Some(i), conflicting.contains(&i), calendars, display_zone, locale, palette)
+            .map(move |message| Message::EventMessage(i, message))
+    };
+
+    let hours: Vec<NaiveTime> = (0..24).filter_map(|hour| NaiveTime::from_hms_opt(hour, 0, 0)).collect();
+
+    // Header: zone names above the label columns, then one title per day
+    let mut header = Row::new().push(label(palette, display_zone.name().to_string()));
+    let mut all_day_row = Row::new().push(label(palette, String::new()));
+
+    if let Some(secondary_zone) = secondary_zone {
+        header = header.push(label(palette, secondary_zone.name().to_string()));
+        all_day_row = all_day_row.push(label(palette, String::new()));
+    }
+
+    for (day, date) in dates.iter().enumerate() {
+        header = header.push(cell(
+            text(format!("{} {}", locale.weekday_name(date.weekday()), date.day()))
+                .width(Length::Fill)
+                .horizontal_alignment(alignment::Horizontal::Center)
+                .style(palette.muted)
+                .into(),
+            30.0,
+            palette,
+        ));
+
+        all_day_row = all_day_row.push(cell(
+            column(
+                holidays
+                    .iter()
+                    .filter(|holiday| holiday.date == *date)
+                    .map(|holiday| holiday_view(holiday, &palette))
+                    .chain(all_day[day].iter().map(|&i| event_element(i)))
+                    .collect(),
+            )
+            .spacing(2)
+            .into(),
+            HOUR_HEIGHT,
+            palette,
+        ));
+    }
+
+    let mut grid = Column::new();
+
+    for (hour, time) in hours.iter().enumerate() {
+        let mut row = Row::new().push(label(palette, time.format("%H:%M").to_string()));
+
+        // The hour seen from the secondary zone on every day shown, which
+        // changes during the week when only one of the zones changes its
+        // clocks. The label column gives the first day's, and days differing
+        // from it give their own at the top of the cell.
+        let secondary: Vec<Option<NaiveTime>> = dates
+            .iter()
+            .map(|date| secondary_zone.map(|secondary_zone| zone::convert(display_zone, *date, *time, secondary_zone)))
+            .collect();
+
+        let first = secondary.first().copied().flatten();
+
+        if secondary_zone.is_some() {
+            row = row.push(label(palette, first.map(|time| time.format("%H:%M").to_string()).unwrap_or_default()));
+        }
+
+        for (day, date) in dates.iter().enumerate() {
+            let shifted: Option<Element<'a, Message>> = secondary[day]
+                .filter(|time| Some(*time) != first)
+                .map(|time| text(time.format("%H:%M").to_string()).size(12).style(palette.muted).into());
+
+            let offered = slots
+                .iter()
+                .filter(|slot| slot.date == *date && slot.start.hour() as usize == hour)
+                .map(|&slot| {
+                    button(text(format!("+ {}–{}", slot.start.format("%H:%M"), slot.end.format("%H:%M"))).size(14))
+                        .style(theme::Button::Positive)
+                        .padding(2)
+                        .on_press(Message::SlotChosen(slot))
+                        .into()
+                });
+
+            row = row.push(cell(
+                column(shifted.into_iter().chain(by_hour[day][hour].iter().map(|&i| event_element(i))).chain(offered).collect())
+                    .spacing(2)
+                    .into(),
+                HOUR_HEIGHT,
+                palette,
+            ));
+        }
+
+        grid = grid.push(row);
+    }
+
+    column![
+        header,
+        all_day_row,
+        scrollable(grid).height(Length::Fill),
+    ]
+    .into()
+}
+
+fn label<'a>(palette: Palette, value: String) -> Element<'a, Message> {
+    text(value)
+        .size(14)
+        .width(Length::Fixed(LABEL_WIDTH))
+        .horizontal_alignment(alignment::Horizontal::Right)
+        .style(palette.muted)
+        .into()
+}
+
+fn cell<'a>(content: Element<'a, Message>, height: f32, palette: Palette) -> Element<'a, Message> {
+    Container::new(content)
+        .width(Length::Fill)
+        .height(Length::Fixed(height))
+        .padding(2)
+        .style(style::cell(palette))
+        .into()
+}
@@ -0,0 +1,74 @@
+//! Notice changes made to the calendar file by other programs, such as the
+//! command line, scripts or a sync client.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use calendar_core::{storage::LoadError, SavedState};
+use iced::futures::{channel::mpsc, future, FutureExt, StreamExt};
+use iced::{subscription, Subscription};
+use notify::{RecursiveMode, Watcher};
+
+/// Writers often touch the file several times in a row; changes closer
+/// than this are read once
+const SETTLE: Duration = Duration::from_millis(300);
+
+enum Watch {
+    Starting(PathBuf),
+    Watching {
+        path: PathBuf,
+        // Dropping the watcher stops it
+        _watcher: notify::RecommendedWatcher,
+        changes: mpsc::UnboundedReceiver<()>,
+    },
+    Stopped,
+}
+
+/// Contents of the calendar file at `path` after every change on disk,
+/// including the ones the window makes itself
+pub fn changes(path: PathBuf) -> Subscription<Result<SavedState, LoadError>> {
+    subscription::unfold(path.clone(), Watch::Starting(path), |watch| async move {
+        match watch {
+            Watch::Starting(path) => (None, start(path)),
+            Watch::Watching { path, _watcher, mut changes } => {
+                if changes.next().await.is_none() {
+                    return (None, Watch::Stopped);
+                }
+
+                async_std::task::sleep(SETTLE).await;
+                while let Some(Some(())) = changes.next().now_or_never() {}
+
+                let state = crate::load(path.clone()).await;
+
+                (Some(state), Watch::Watching { path, _watcher, changes })
+            }
+            Watch::Stopped => future::pending().await,
+        }
+    })
+}
+
+fn start(path: PathBuf) -> Watch {
+    let (sender, changes) = mpsc::unbounded();
+    let name = path.file_name().map(ToOwned::to_owned);
+
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if !event.kind.is_access() && event.paths.iter().any(|changed| changed.file_name() == name.as_deref()) {
+                let _ = sender.unbounded_send(());
+            }
+        }
+    });
+
+    // The folder is watched rather than the file, as saving replaces the
+    // file instead of writing into it
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let _ = std::fs::create_dir_all(&dir);
+
+    match watcher.and_then(|mut watcher| watcher.watch(&dir, RecursiveMode::NonRecursive).map(|()| watcher)) {
+        Ok(watcher) => Watch::Watching { path, _watcher: watcher, changes },
+        Err(_) => Watch::Stopped,
+    }
+}
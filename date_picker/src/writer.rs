@@ -0,0 +1,183 @@
+//! Background writer of the calendar file. Bursts of changes, like typing
+//! a title, are written once the edits pause.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use calendar_core::{event, storage::SaveError, SavedState};
+use iced::futures::{channel::mpsc as channel, future, StreamExt};
+use iced::{subscription, Subscription};
+
+/// Quiet time after the last change before writing
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest a change waits while edits keep coming
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Handle queueing writes to the writer thread
+#[derive(Clone)]
+pub struct Writer {
+    requests: mpsc::Sender<Request>,
+}
+
+impl std::fmt::Debug for Writer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Writer")
+    }
+}
+
+/// What to write and where, with the events the file may hold until then
+struct Job {
+    state: SavedState,
+    path: PathBuf,
+    known: Vec<Vec<event::Event>>,
+}
+
+enum Request {
+    Write(Job),
+    Flush(mpsc::Sender<Result<(), SaveError>>),
+    Discard,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The writer thread is running
+    Ready(Writer),
+    Saving,
+    /// What is now in the file. The writes queued before it were replaced
+    /// by it and will not happen.
+    Saved(SavedState),
+    /// What could not be written, with the writes queued before it
+    Failed(SavedState, SaveError),
+}
+
+impl Writer {
+    /// Write `state` to `path` once changes pause, replacing any write
+    /// still waiting. It fails with `SaveError::Changed` if the file then
+    /// holds none of the `known` events, as another program changed it.
+    pub fn write(&self, state: SavedState, path: PathBuf, known: Vec<Vec<event::Event>>) {
+        let _ = self.requests.send(Request::Write(Job { state, path, known }));
+    }
+
+    /// Forget the write still waiting, if any, so it does not replace
+    /// changes found in the file. A write already started finds them and
+    /// fails instead.
+    pub fn discard(&self) {
+        let _ = self.requests.send(Request::Discard);
+    }
+
+    /// Write what is waiting now, returning when it is on disk
+    pub fn flush(&self) -> Result<(), SaveError> {
+        let (done, result) = mpsc::channel();
+
+        // Only fails if the writer thread is gone
+        self.requests
+            .send(Request::Flush(done))
+            .map_err(|_| SaveError::Write(io::ErrorKind::BrokenPipe))?;
+
+        result.recv().map_err(|_| SaveError::Write(io::ErrorKind::BrokenPipe))?
+    }
+}
+
+enum Run {
+    Starting,
+    Running(channel::UnboundedReceiver<Event>),
+    Stopped,
+}
+
+/// Starts the writer thread, then reports what it does
+pub fn run() -> Subscription<Event> {
+    struct Id;
+
+    subscription::unfold(std::any::TypeId::of::<Id>(), Run::Starting, |run| async move {
+        match run {
+            Run::Starting => {
+                let (requests, receiver) = mpsc::channel();
+                let (events, statuses) = channel::unbounded();
+
+                std::thread::spawn(move || write_loop(receiver, events));
+
+                (Some(Event::Ready(Writer { requests })), Run::Running(statuses))
+            }
+            Run::Running(mut statuses) => match statuses.next().await {
+                Some(event) => (Some(event), Run::Running(statuses)),
+                None => (None, Run::Stopped),
+            },
+            Run::Stopped => future::pending().await,
+        }
+    })
+}
+
+fn write_loop(requests: mpsc::Receiver<Request>, events: channel::UnboundedSender<Event>) {
+    // Latest state waiting to be written, with when the first change
+    // it holds was made
+    let mut pending: Option<(Job, Instant)> = None;
+
+    loop {
+        let request = match &pending {
+            None => match requests.recv() {
+                Ok(request) => Some(request),
+                Err(_) => return,
+            },
+            Some((_, since)) => {
+                let timeout = DEBOUNCE.min(MAX_DELAY.saturating_sub(since.elapsed()));
+
+                match requests.recv_timeout(timeout) {
+                    Ok(request) => Some(request),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    // The window is gone: write what is left and stop
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if let Some((job, _)) = pending.take() {
+                            let _ = write(job, &events);
+                        }
+
+                        return;
+                    }
+                }
+            }
+        };
+
+        match request {
+            Some(Request::Write(job)) => {
+                let since = pending.take().map_or_else(Instant::now, |(_, since)| since);
+
+                pending = Some((job, since));
+            }
+            Some(Request::Flush(done)) => {
+                let result = match pending.take() {
+                    Some((job, _)) => write(job, &events),
+                    None => Ok(()),
+                };
+
+                let _ = done.send(result);
+            }
+            Some(Request::Discard) => {
+                pending = None;
+            }
+            None => {
+                if let Some((job, _)) = pending.take() {
+                    let _ = write(job, &events);
+                }
+            }
+        }
+    }
+}
+
+fn write(Job { state, path, known }: Job, events: &channel::UnboundedSender<Event>) -> Result<(), SaveError> {
+    let _ = events.unbounded_send(Event::Saving);
+
+    let result = state.replace(&path, &known);
+
+    if let Err(error) = &result {
+        crate::error_log::write(&path, &format!("save failed: {error}"));
+    }
+
+    let _ = events.unbounded_send(match &result {
+        Ok(()) => Event::Saved(state),
+        Err(error) => Event::Failed(state, error.clone()),
+    });
+
+    result
+}